@@ -0,0 +1,45 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use webgraph_rust::utils::encodings::Code;
+use webgraph_rust::webgraph::bvgraph::BVGraphBuilder;
+
+fn code(byte: u8) -> Code {
+    match byte % 4 {
+        0 => Code::Unary,
+        1 => Code::Gamma,
+        2 => Code::Delta,
+        _ => Code::Zeta,
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((params, graph)) = data.split_first_chunk::<12>() else {
+        return;
+    };
+
+    let n = params[0] as usize + 1;
+    let graph = BVGraphBuilder::new()
+        .set_num_nodes(n)
+        .set_in_window_size(params[1] as usize % 8)
+        .set_in_min_interval_len(params[2] as usize % 4)
+        .set_in_max_ref_count(params[3] as usize % 8)
+        .set_in_zeta(Some(params[4] as u64 % 8))
+        .set_in_block_coding(code(params[5]))
+        .set_in_block_count_coding(code(params[6]))
+        .set_in_outdegree_coding(code(params[7]))
+        .set_in_offset_coding(code(params[8]))
+        .set_in_reference_coding(code(params[9]))
+        .set_in_interval_coding(code(params[10]))
+        .set_in_residual_coding(code(params[11]))
+        .load_graph_from(graph.to_vec())
+        .build();
+
+    for item in graph.try_iter_nodes() {
+        let Ok((_, successors)) = item else {
+            break;
+        };
+        assert!(successors.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(successors.iter().all(|&succ| succ < n));
+    }
+});
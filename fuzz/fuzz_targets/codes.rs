@@ -0,0 +1,22 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use webgraph_rust::bitstreams::BinaryReader;
+use webgraph_rust::utils::encodings::Code;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&[code, zk], bits)) = data.split_first_chunk() else {
+        return;
+    };
+
+    let code = match code % 4 {
+        0 => Code::Unary,
+        1 => Code::Gamma,
+        2 => Code::Delta,
+        _ => Code::Zeta,
+    };
+    let mut reader = BinaryReader::new(bits.to_vec().into());
+
+    // Every value consumes at least one bit, so the stream is eventually exhausted
+    while code.try_read_next(&mut reader, Some((zk % 8) as u64)).is_ok() {}
+});
@@ -0,0 +1,27 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use webgraph_rust::bitstreams::BinaryReader;
+use webgraph_rust::huffman_zuckerli::huffman_decoder::HuffmanDecoder;
+use webgraph_rust::webgraph::{bvgraph_huffman_out, zuckerli_in};
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&format, bits)) = data.split_first() else {
+        return;
+    };
+
+    let num_contexts = if format % 2 == 0 { bvgraph_huffman_out::NUM_CONTEXTS } else { zuckerli_in::NUM_CONTEXTS };
+    let mut reader = BinaryReader::new(bits.to_vec().into());
+    let mut decoder = HuffmanDecoder::new();
+
+    if decoder.decode_headers(&mut reader, num_contexts).is_err() {
+        return;
+    }
+
+    // Every symbol consumes at least one bit, so the stream is eventually exhausted
+    for ctx in (0..num_contexts).cycle() {
+        if decoder.try_read_next(&mut reader, ctx).is_err() {
+            break;
+        }
+    }
+});
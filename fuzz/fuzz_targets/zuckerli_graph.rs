@@ -0,0 +1,30 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use webgraph_rust::webgraph::zuckerli_in::DefaultBVGraphBuilder;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((params, graph)) = data.split_first_chunk::<4>() else {
+        return;
+    };
+
+    let n = params[0] as usize + 1;
+    let Ok(builder) = DefaultBVGraphBuilder::<Box<[usize]>>::new()
+        .set_num_nodes(n)
+        .set_in_window_size(params[1] as usize % 8)
+        .set_in_min_interval_len(params[2] as usize % 4)
+        .set_in_max_ref_count(params[3] as usize % 8)
+        .load_graph_from(graph.to_vec())
+    else {
+        return;
+    };
+    let graph = builder.build();
+
+    for item in graph.try_iter_nodes() {
+        let Ok((_, successors)) = item else {
+            break;
+        };
+        assert!(successors.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(successors.iter().all(|&succ| succ < n));
+    }
+});
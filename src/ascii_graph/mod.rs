@@ -2,7 +2,7 @@ use std::{fs, str::FromStr, fmt::{self, Debug}, marker::PhantomData};
 
 use serde::{Serialize, Deserialize, de::DeserializeOwned};
 
-use crate::ImmutableGraph;
+use crate::{SequentialGraph, RandomAccessGraph, Store, Error, Result};
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct AsciiGraph<T> 
@@ -19,7 +19,7 @@ where T:
     pub offsets: Vec<usize>,
 }
 
-impl<T> ImmutableGraph for AsciiGraph<T> 
+impl<T> SequentialGraph for AsciiGraph<T> 
 where T: 
         num_traits::Num 
         + PartialOrd 
@@ -39,6 +39,23 @@ where T:
         self.m
     }
 
+    /// Returns an iterator over the nodes of the graph, each paired with its successors.
+    fn iter_nodes(&self) -> impl Iterator<Item = (Self::NodeT, Box<[Self::NodeT]>)> + '_ {
+        self.offsets.iter().take(self.n).map(|&offset| {
+            let outd = self.graph_memory[offset + 1].to_usize().unwrap();
+            (self.graph_memory[offset], self.graph_memory[offset + 2..offset + 2 + outd].into())
+        })
+    }
+}
+
+impl<T> RandomAccessGraph for AsciiGraph<T> 
+where T: 
+        num_traits::Num 
+        + PartialOrd 
+        + num_traits::ToPrimitive
+        + serde::Serialize
+        + Copy
+{
     /// Returns the number of adjacent nodes of the given node (if in bounds).
     /// 
     /// Since the nodes are numbered from *0* to *n - 1*, asking for the outdegree of node *x* 
@@ -74,6 +91,20 @@ where T:
         succ.into_boxed_slice()
     }
 
+    fn successors_iter(&self, x: Self::NodeT) -> impl Iterator<Item = Self::NodeT> + '_ {
+        assert!(x.to_usize().unwrap() < self.n, "Node index out of range {}", x.to_usize().unwrap());
+        self.successors_internal(x)
+    }
+}
+
+impl<T> Store for AsciiGraph<T> 
+where T: 
+        num_traits::Num 
+        + PartialOrd 
+        + num_traits::ToPrimitive
+        + serde::Serialize
+        + Copy
+{
     /// Stores both `graph_memory` and `offsets` into their respective files.
     /// 
     /// # Arguments
@@ -88,11 +119,13 @@ where T:
     ///     .build();
     /// let result = ascii_graph.store("graph_name");
     /// ```
-    fn store(&mut self, basename: &str) -> std::io::Result<()>{
+    fn store(&mut self, basename: &str) -> Result<()>{
         assert_ne!(basename, "");
 
-        fs::write(format!("{}.graph.bin", basename), bincode::serialize(&self.graph_memory).unwrap())?;
-        fs::write(format!("{}.offsets.bin", basename), bincode::serialize(&self.offsets).unwrap())?;
+        let graph_path = format!("{}.graph.bin", basename);
+        fs::write(&graph_path, bincode::serialize(&self.graph_memory).unwrap()).map_err(Error::io(&graph_path))?;
+        let offsets_path = format!("{}.offsets.bin", basename);
+        fs::write(&offsets_path, bincode::serialize(&self.offsets).unwrap()).map_err(Error::io(&offsets_path))?;
 
         Ok(())
     }
@@ -286,9 +319,8 @@ where
     /// # Arguments
     /// 
     /// * `basename` - The basename of the ASCII graph
-    pub fn load_ascii(mut self, basename: &str) -> Self {
-        let data = fs::read_to_string(basename)
-                            .expect("Failed to load the graph file");
+    pub fn load_ascii(mut self, basename: &str) -> Result<Self> {
+        let data = fs::read_to_string(basename).map_err(Error::io(basename))?;
         let mut lines: Vec<&str> = data.split('\n').collect();
         if lines.last().unwrap().is_empty() {
             lines.pop();
@@ -304,21 +336,27 @@ where
 
         let mut nodes_idx = 0;
 
-        for line in lines {
+        let parse = |line_idx: usize, value: &str| value.parse::<T>()
+            .map_err(|_| Error::Malformed(format!("invalid node {:?} at line {}", value, line_idx + 1)));
+
+        for (line_idx, line) in lines.into_iter().enumerate() {
             let mut node_list = line.split('\t');
 
             // Since the file also represents nodes without connections, 
             // we are sure that at least one node exists in the parse
-            let node: T = node_list.next().unwrap().parse().unwrap();
+            let node: T = parse(line_idx, node_list.next().unwrap())?;
 
-            offsets[node.to_usize().unwrap()] = nodes_idx;
+            match node.to_usize() {
+                Some(node_idx) if node_idx < num_nodes => offsets[node_idx] = nodes_idx,
+                _ => return Err(Error::Malformed(format!("node {:?} at line {} is out of range", node, line_idx + 1))),
+            }
 
             nodes.push(node);
             nodes_idx += 1;
             
             for succ in node_list {
                 if !succ.is_empty() {
-                    let succ = succ.parse().unwrap();
+                    let succ = parse(line_idx, succ)?;
                     edges += 1;
                     temp_succs.push(succ);
                     nodes_idx += 1;
@@ -335,7 +373,7 @@ where
         self.loaded_graph = nodes;
         self.loaded_offsets = offsets;
 
-        self
+        Ok(self)
     }
 
     /// Loads a serialized graph file.
@@ -343,12 +381,14 @@ where
     /// # Arguments
     /// 
     /// * `basename` - The basename of the graph file
-    pub fn load_graph_bin(mut self, basename: &str) -> Self {
-        let file = fs::read(format!("{}.graph.bin", basename)).expect("Failed reading the graph file");
+    pub fn load_graph_bin(mut self, basename: &str) -> Result<Self> {
+        let path = format!("{}.graph.bin", basename);
+        let file = fs::read(&path).map_err(Error::io(&path))?;
         
-        self.loaded_graph = bincode::deserialize(&file).expect("Error in deserializing the graph file");
+        self.loaded_graph = bincode::deserialize(&file)
+            .map_err(|e| Error::Malformed(format!("could not deserialize {}: {}", path, e)))?;
 
-        self
+        Ok(self)
     }
 
     /// Loads a serialized offsets file.
@@ -356,11 +396,13 @@ where
     /// # Arguments
     /// 
     /// * `basename` - The basename of the offsets file
-    pub fn load_offsets_bin(mut self, basename: &str) -> Self {
-        let f = fs::read(format!("{}.offsets.bin", basename)).expect("Failed reading the offsets file");
-        self.loaded_offsets = bincode::deserialize(&f).expect("Error in deserializing the offsets file");
+    pub fn load_offsets_bin(mut self, basename: &str) -> Result<Self> {
+        let path = format!("{}.offsets.bin", basename);
+        let f = fs::read(&path).map_err(Error::io(&path))?;
+        self.loaded_offsets = bincode::deserialize(&f)
+            .map_err(|e| Error::Malformed(format!("could not deserialize {}: {}", path, e)))?;
 
-        self
+        Ok(self)
     }
 
     /// Computes the number of nodes by counting the offsets' file entries.
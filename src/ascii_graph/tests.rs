@@ -10,7 +10,7 @@ const TEST_PERMUTED_FILE: &str = "cnr-2000-permuted-ascii";
 fn test_graph_building_ascii() {
     let uncompressed_graph: AsciiGraph<usize> = 
         AsciiGraphBuilder::new()
-        .load_ascii(&(TEST_DATA_PATH.to_owned() + TEST_FILE))
+        .load_ascii(&(TEST_DATA_PATH.to_owned() + TEST_FILE)).unwrap()
         .build();
     assert_eq!(uncompressed_graph.num_nodes(), 325557);
     assert_eq!(uncompressed_graph.num_arcs(), 3216152);
@@ -20,8 +20,8 @@ fn test_graph_building_ascii() {
 fn test_graph_building_bin() {
     let uncompressed_graph: AsciiGraph<usize> = 
         AsciiGraphBuilder::new()
-        .load_graph_bin(&(TEST_DATA_PATH.to_owned() + TEST_FILE))
-        .load_offsets_bin(&(TEST_DATA_PATH.to_owned() + TEST_FILE))
+        .load_graph_bin(&(TEST_DATA_PATH.to_owned() + TEST_FILE)).unwrap()
+        .load_offsets_bin(&(TEST_DATA_PATH.to_owned() + TEST_FILE)).unwrap()
         .count_nodes()
         .count_edges()
         .build();
@@ -33,8 +33,8 @@ fn test_graph_building_bin() {
 fn test_iterate_on_first_successors() {
     let uncompressed_graph: AsciiGraph<usize> = 
         AsciiGraphBuilder::new()
-        .load_graph_bin(&(TEST_DATA_PATH.to_owned() + TEST_FILE))
-        .load_offsets_bin(&(TEST_DATA_PATH.to_owned() + TEST_FILE))
+        .load_graph_bin(&(TEST_DATA_PATH.to_owned() + TEST_FILE)).unwrap()
+        .load_offsets_bin(&(TEST_DATA_PATH.to_owned() + TEST_FILE)).unwrap()
         .count_nodes()
         .count_edges()
         .build();
@@ -49,8 +49,8 @@ fn test_iterate_on_first_successors() {
 fn test_iterate_on_mid_successors() {
     let uncompressed_graph: AsciiGraph<usize> = 
         AsciiGraphBuilder::new()
-        .load_graph_bin(&(TEST_DATA_PATH.to_owned() + TEST_FILE))
-        .load_offsets_bin(&(TEST_DATA_PATH.to_owned() + TEST_FILE))
+        .load_graph_bin(&(TEST_DATA_PATH.to_owned() + TEST_FILE)).unwrap()
+        .load_offsets_bin(&(TEST_DATA_PATH.to_owned() + TEST_FILE)).unwrap()
         .count_nodes()
         .count_edges()
         .build();
@@ -65,8 +65,8 @@ fn test_iterate_on_mid_successors() {
 fn test_iterate_on_end_successors() {
     let uncompressed_graph: AsciiGraph<usize> = 
         AsciiGraphBuilder::new()
-        .load_graph_bin(&(TEST_DATA_PATH.to_owned() + TEST_FILE))
-        .load_offsets_bin(&(TEST_DATA_PATH.to_owned() + TEST_FILE))
+        .load_graph_bin(&(TEST_DATA_PATH.to_owned() + TEST_FILE)).unwrap()
+        .load_offsets_bin(&(TEST_DATA_PATH.to_owned() + TEST_FILE)).unwrap()
         .count_nodes()
         .count_edges()
         .build();
@@ -83,7 +83,7 @@ fn test_iterate_on_end_successors() {
 fn test_from_ascii_first_index() {
     let uncompressed_graph: AsciiGraph<usize> = 
         AsciiGraphBuilder::new()
-        .load_ascii(&(TEST_DATA_PATH.to_owned() + TEST_FILE))
+        .load_ascii(&(TEST_DATA_PATH.to_owned() + TEST_FILE)).unwrap()
         .build();
 
     let it = uncompressed_graph.successors(0);
@@ -97,7 +97,7 @@ fn test_from_ascii_first_index() {
 fn test_from_ascii_middle_index() {
     let uncompressed_graph: AsciiGraph<usize> = 
         AsciiGraphBuilder::new()
-        .load_ascii(&(TEST_DATA_PATH.to_owned() + TEST_FILE))
+        .load_ascii(&(TEST_DATA_PATH.to_owned() + TEST_FILE)).unwrap()
         .build();
 
     let it = uncompressed_graph.successors(181145);
@@ -111,7 +111,7 @@ fn test_from_ascii_middle_index() {
 fn test_from_ascii_last_index() {
     let uncompressed_graph: AsciiGraph<usize> = 
         AsciiGraphBuilder::new()
-        .load_ascii(&(TEST_DATA_PATH.to_owned() + TEST_FILE))
+        .load_ascii(&(TEST_DATA_PATH.to_owned() + TEST_FILE)).unwrap()
         .build();
 
     let it = uncompressed_graph.successors(325556);
@@ -125,7 +125,7 @@ fn test_from_ascii_last_index() {
 fn test_from_permuted_ascii_first_node() {
     let uncompressed_graph: AsciiGraph<usize> = 
         AsciiGraphBuilder::new()
-        .load_ascii(&(TEST_DATA_PATH.to_owned() + TEST_PERMUTED_FILE))
+        .load_ascii(&(TEST_DATA_PATH.to_owned() + TEST_PERMUTED_FILE)).unwrap()
         .build();
 
     let it = uncompressed_graph.successors(0);
@@ -139,7 +139,7 @@ fn test_from_permuted_ascii_first_node() {
 fn test_from_permuted_ascii_middle_node() {
     let uncompressed_graph: AsciiGraph<usize> = 
         AsciiGraphBuilder::new()
-        .load_ascii(&(TEST_DATA_PATH.to_owned() + TEST_PERMUTED_FILE))
+        .load_ascii(&(TEST_DATA_PATH.to_owned() + TEST_PERMUTED_FILE)).unwrap()
         .build();
 
     let it = uncompressed_graph.successors(181145);
@@ -153,7 +153,7 @@ fn test_from_permuted_ascii_middle_node() {
 fn test_from_permuted_ascii_last_node() {
     let uncompressed_graph: AsciiGraph<usize> = 
         AsciiGraphBuilder::new()
-        .load_ascii(&(TEST_DATA_PATH.to_owned() + TEST_PERMUTED_FILE))
+        .load_ascii(&(TEST_DATA_PATH.to_owned() + TEST_PERMUTED_FILE)).unwrap()
         .build();
 
     let it = uncompressed_graph.successors(325556);
@@ -167,7 +167,7 @@ fn test_from_permuted_ascii_last_node() {
 fn test_bvgraph_iteration() {
     let uncompressed_graph: AsciiGraph<usize> = 
     AsciiGraphBuilder::new()
-    .load_ascii(&(TEST_DATA_PATH.to_owned() + TEST_FILE))
+    .load_ascii(&(TEST_DATA_PATH.to_owned() + TEST_FILE)).unwrap()
     .build();
 
     let mut it = uncompressed_graph.iter();
@@ -201,7 +201,7 @@ fn test_bvgraph_iteration() {
 fn test_bvgraph_iteration_permuted() {
     let uncompressed_graph: AsciiGraph<usize> = 
     AsciiGraphBuilder::new()
-    .load_ascii(&(TEST_DATA_PATH.to_owned() + TEST_PERMUTED_FILE))
+    .load_ascii(&(TEST_DATA_PATH.to_owned() + TEST_PERMUTED_FILE)).unwrap()
     .build();
 
     let mut it = uncompressed_graph.iter();
@@ -1,4 +1,8 @@
-use std::rc::Rc;
+use std::{fs::{self, File}, io::{self, Write}, ops::Deref, path::Path, sync::Arc};
+
+use memmap2::{Mmap, MmapOptions};
+
+use crate::{Error, Result};
 
 pub mod tables;
 
@@ -6,31 +10,49 @@ pub struct BinarySequence {
     pub os: Box<[u8]>,
 }
 
-pub struct BinaryWriter {
+/// The number of bytes a streaming [`BinaryWriter`] accumulates before writing them to its sink.
+pub const SINK_BUFFER_SIZE: usize = 1 << 16;
+
+/// A writer of bits, most significant first, accumulating them in 64-bit words.
+/// 
+/// The bytes written are either kept in memory, and returned by [`Self::build()`], or streamed
+/// to an [`io::Write`] sink (a file, a pipe, a vector...) as they accumulate, and completed by [`Self::finish()`].
+pub struct BinaryWriter<'a> {
     os: Vec<u8>,
+    /// Where the bytes of `os` are written, if the writer is streaming
+    sink: Option<Box<dyn Write + Send + 'a>>,
+    /// The first error returned by the sink, after which no more bytes are written to it
+    error: Option<io::Error>,
     pub written_bits: usize,
+    /// The bits not written yet, in its most significant `64 - free` bits
     pub current: u64,
+    /// The number of free bits in `current`
     pub free: usize,
-    temp_buffer: Vec<u8>,
 }
 
-impl Default for BinaryWriter {
+impl Default for BinaryWriter<'_> {
     fn default() -> Self {
         BinaryWriter {
             os: Vec::default(),
+            sink: None,
+            error: None,
             written_bits: 0,
             current: 0,
-            free: 8,
-            temp_buffer: vec![0u8; 128]
+            free: 64,
         }
     }
 }
 
-impl BinaryWriter {
+impl<'a> BinaryWriter<'a> {
+    /// Returns the bytes written to an in-memory writer.
+    /// 
+    /// # Panics
+    /// 
+    /// If the writer is streaming, as its bytes are in the sink: it must be completed by [`Self::finish()`].
     pub fn build(mut self) -> BinarySequence {
-        if self.free < 8 {
-            self.write(self.current);
-        }
+        assert!(self.sink.is_none(), "The bytes of a streaming writer are in its sink");
+
+        self.write_current();
         
         BinarySequence {
             os: self.os.into_boxed_slice()
@@ -41,93 +63,225 @@ impl BinaryWriter {
         Self::default()
     }
 
-    #[inline(always)]
-    pub(crate) fn write(&mut self, b: u64) {
-        self.os.push(b as u8);
+    /// Creates a writer streaming its bytes to the given sink, in blocks of [`SINK_BUFFER_SIZE`] bytes.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sink` - Where the bytes are written
+    pub fn with_sink(sink: impl Write + Send + 'a) -> Self {
+        BinaryWriter {
+            os: Vec::with_capacity(SINK_BUFFER_SIZE + 8),
+            sink: Some(Box::new(sink)),
+            ..Default::default()
+        }
     }
 
-    #[inline(always)]
-    pub(crate) fn write_in_current(&mut self, b: u64, len: u64) -> u64 {
-        self.free -= len as usize;
-        self.current |= (b & ((1 << len) - 1)) << self.free;
+    /// Writes the last bytes of a streaming writer, padding the last one with zeros, and flushes its sink.
+    /// 
+    /// Returns the first error of the sink, if any.
+    pub fn finish(mut self) -> io::Result<()> {
+        self.write_current();
+        self.write_to_sink();
+
+        match (self.error, self.sink) {
+            (Some(error), _) => Err(error),
+            (None, Some(mut sink)) => sink.flush(),
+            (None, None) => Ok(()),
+        }
+    }
 
-        if self.free == 0 {
-            self.write(self.current);
-            self.free = 8;
-            self.current = 0;
+    /// Moves the bits of `current` to `os`, padding the last byte with zeros.
+    fn write_current(&mut self) {
+        let bytes = (64 - self.free).div_ceil(8);
+        self.os.extend_from_slice(&self.current.to_be_bytes()[..bytes]);
+        self.current = 0;
+        self.free = 64;
+    }
+
+    /// Moves the bytes of `os` to the sink, if the writer is streaming and the sink has not failed.
+    #[cold]
+    fn write_to_sink(&mut self) {
+        if let (Some(sink), None) = (&mut self.sink, &self.error) {
+            if let Err(error) = sink.write_all(&self.os) {
+                self.error = Some(error);
+            }
         }
 
-        self.written_bits += len as usize;
-        len
+        self.os.clear();
+    }
+
+    #[inline(always)]
+    fn flush(&mut self) {
+        self.os.extend_from_slice(&self.current.to_be_bytes());
+        self.current = 0;
+        self.free = 64;
+
+        if self.sink.is_some() && self.os.len() >= SINK_BUFFER_SIZE {
+            self.write_to_sink();
+        }
     }
 
+    /// Writes the `len` least significant bits of `x`, and returns `len`.
     #[inline(always)]
     pub fn push_bits(&mut self, x: u64, len: u64) -> u64 {
         debug_assert!(len <= 64, "Cannot write {} bits to an integer", len);
 
-        if len <= self.free as u64 {
-            return self.write_in_current(x, len);
+        if len == 0 {
+            return 0;
         }
 
-        let mut x = x;
+        let x = x & (u64::MAX >> (64 - len));
+        let len_bits = len as usize;
+        self.written_bits += len_bits;
 
-        let q = (len - self.free as u64) & 7;
-        let blocks = (len - self.free as u64) >> 3;
-        
-        if q != 0 {
-            self.temp_buffer[blocks as usize] = x as u8;
-            x >>= q;
-        }
-        
-        let mut i = blocks;
-        while i != 0 {
-            i -= 1;
-            self.temp_buffer[i as usize] = x as u8;
-            x >>= 8; 
+        if len_bits <= self.free {
+            self.free -= len_bits;
+            self.current |= x << self.free;
+
+            if self.free == 0 {
+                self.flush();
+            }
+
+            return len;
         }
 
-        self.write_in_current(x, self.free as u64);
+        // The bits not fitting in the current word start the next one
+        let rest = len_bits - self.free;
+        self.current |= x >> rest;
+        self.flush();
+        self.free -= rest;
+        self.current = x << self.free;
+
+        len
+    }
+
+    /// Appends the bits written to another writer, as if they had been pushed to this one.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The writer whose bits are appended
+    pub fn append(&mut self, other: BinaryWriter) {
+        let bits = other.written_bits;
+        let bytes = other.build().os;
+        let (words, tail) = bytes.split_at(bits / 64 * 8);
+        let rem_bits = bits % 64;
+
+        for word in words.chunks_exact(8) {
+            self.push_bits(u64::from_be_bytes(word.try_into().unwrap()), 64);
+        }
 
-        for i in 0..blocks {
-            self.write(self.temp_buffer[i as usize] as u64);
+        if rem_bits != 0 {
+            let mut word = [0; 8];
+            word[..tail.len()].copy_from_slice(tail);
+            self.push_bits(u64::from_be_bytes(word) >> (64 - rem_bits), rem_bits as u64);
         }
+    }
+}
+
+#[derive(Debug)]
+enum Storage {
+    Memory(Box<[u8]>),
+    Mmap(Mmap),
+}
+
+/// A shared, read-only sequence of bytes to be read by a [`BinaryReader`].
+/// 
+/// The bytes are either loaded in memory or memory-mapped from a file, in which case
+/// the OS pages in only the parts that are actually read.
+#[derive(Clone, Debug)]
+pub struct InputStream(Arc<Storage>);
+
+impl InputStream {
+    /// Memory-maps the file at the given path.
+    /// 
+    /// The file must not be modified while it is mapped.
+    pub fn mmap<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::mmap_at(path, 0)
+    }
 
-        self.written_bits += (blocks << 3) as usize;
+    /// Memory-maps the file at the given path, skipping its first `offset` bytes.
+    /// 
+    /// The file must not be modified while it is mapped.
+    pub fn mmap_at<P: AsRef<Path>>(path: P, offset: u64) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: graph files are only read, and they are not expected to change while in use
+        let mmap = unsafe { MmapOptions::new().offset(offset).map(&file)? };
+
+        Ok(Self(Arc::new(Storage::Mmap(mmap))))
+    }
 
-        if q != 0 {
-            self.write_in_current(self.temp_buffer[blocks as usize] as u64, q);
+    /// Reads the file at the given path in memory, or memory-maps it if `mmap` is `true`.
+    pub fn load<P: AsRef<Path>>(path: P, mmap: bool) -> Result<Self> {
+        let stream = if mmap {
+            Self::mmap(&path)
+        } else {
+            fs::read(&path).map(Self::from)
+        };
+
+        stream.map_err(Error::io(path))
+    }
+}
+
+impl Default for InputStream {
+    fn default() -> Self {
+        Self::from(Box::<[u8]>::default())
+    }
+}
+
+impl Deref for InputStream {
+    type Target = [u8];
+
+    #[inline(always)]
+    fn deref(&self) -> &[u8] {
+        match self.0.as_ref() {
+            Storage::Memory(bytes) => bytes,
+            Storage::Mmap(mmap) => mmap,
         }
+    }
+}
 
-        len
+impl PartialEq for InputStream {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for InputStream {}
+
+impl From<Box<[u8]>> for InputStream {
+    fn from(bytes: Box<[u8]>) -> Self {
+        Self(Arc::new(Storage::Memory(bytes)))
+    }
+}
+
+impl From<Vec<u8>> for InputStream {
+    fn from(bytes: Vec<u8>) -> Self {
+        bytes.into_boxed_slice().into()
     }
 }
 
-#[derive(Clone, Eq, PartialEq, Debug)]
+/// A reader of bits, most significant first, refilling a 64-bit buffer a word at a time.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
 pub struct BinaryReader {
-    pub is: Rc<[u8]>,
+    pub is: InputStream,
+    /// The next byte to be loaded in `current`
     pub position: usize,
     pub read_bits: usize,
+    /// The bits to be read next, in its `fill` least significant bits
     pub current: u64,
+    /// The number of bits that can be read from `current`
     pub fill: usize,
 }
 
-impl Default for BinaryReader {
-    fn default() -> Self {
-        Self { 
-            is: Rc::new([]), 
-            position: Default::default(), 
-            read_bits: Default::default(), 
-            current: Default::default(), 
-            fill: Default::default()
-        }
-    }
-}
-
 impl BinaryReader {
-    pub fn new(input_stream: Rc<[u8]>) -> Self {
+    pub fn new(input_stream: InputStream) -> Self {
+        // The fields are not taken from the default reader, whose empty stream would be allocated in vain
         BinaryReader { 
             is: input_stream, 
-            ..Default::default()
+            position: 0,
+            read_bits: 0,
+            current: 0,
+            fill: 0,
         }
     }
 
@@ -155,27 +309,52 @@ impl BinaryReader {
         (self.position << 3) - self.fill
     }
 
+    /// Returns the number of bits that can still be read.
+    #[inline(always)]
+    pub fn remaining_bits(&self) -> usize {
+        (self.is.len() << 3) - self.get_position()
+    }
+
     #[inline(always)]
-    pub(crate) fn read(&mut self) -> Result<u64, ()> {
+    pub(crate) fn read(&mut self) -> Result<u64> {
         if self.position >= self.is.len() {
-            return Err(());
+            return Err(Error::UnexpectedEof);
         }
 
         self.position += 1;
         Ok(self.is[self.position - 1] as u64)
     }
 
+    /// Loads in `current` as many whole bytes as fit, and returns the number of bits that can be read from it,
+    /// which is less than 57 only at the end of the stream.
     #[inline(always)]
     pub(crate) fn refill(&mut self) -> usize {
-        debug_assert!(self.fill < 16);
-        
-        if let Ok(read) = self.read() {
-            self.current = (self.current << 8) | read;
-            self.fill += 8;
+        let bytes = (64 - self.fill) >> 3;
+        if bytes == 0 {
+            return self.fill;
         }
-        if let Ok(read) = self.read() {
-            self.current = (self.current << 8) | read;
-            self.fill += 8;
+
+        match self.is.get(self.position..self.position + 8) {
+            Some(word) => {
+                // An unaligned load of the next eight bytes, of which only the first `bytes` are kept
+                let word = u64::from_be_bytes(word.try_into().unwrap());
+                let shift = bytes << 3;
+
+                self.current = if shift == 64 { word } else { (self.current << shift) | (word >> (64 - shift)) };
+                self.position += bytes;
+                self.fill += shift;
+            },
+            None => {
+                while self.fill <= 56 {
+                    match self.read() {
+                        Ok(read) => {
+                            self.current = (self.current << 8) | read;
+                            self.fill += 8;
+                        },
+                        Err(_) => break,
+                    }
+                }
+            },
         }
 
         self.fill
@@ -187,24 +366,24 @@ impl BinaryReader {
             return 0;
         }
 
-        if self.fill == 0 {
-            self.current = self.read().unwrap();
-            self.fill = 8;
+        if (self.fill as u64) < len {
+            self.refill();
         }
 
-        debug_assert!(len as usize <= self.fill);
+        assert!(len as usize <= self.fill, "Cannot read {} bits past the end of the stream", len);
 
         self.read_bits += len as usize;
 
         self.fill -= len as usize;
-        self.current >> self.fill & ((1 << len) - 1)
+        (self.current >> self.fill) & (u64::MAX >> (64 - len))
     }
 
+    /// Reads an integer of `len` bits, with `len` at most 64.
     #[inline(always)]
     pub fn read_int(&mut self, len: u64) -> u64 {
-        debug_assert!(len < 64);
+        debug_assert!(len <= 64);
         
-        if self.fill < 16 {
+        if len as usize > self.fill {
             self.refill();
         }
 
@@ -212,22 +391,21 @@ impl BinaryReader {
             return self.read_from_current(len);
         }
 
-        let mut len = len - self.fill as u64;
-        
-        let mut x = self.read_from_current(self.fill as u64);
+        // The integer spans the buffer and the next bytes, which fill it after it is emptied
+        let high = self.fill as u64;
+        let x = self.read_from_current(high);
 
-        let mut i = len >> 3;
+        (x << (len - high)) | self.read_from_current(len - high)
+    }
 
-        while i != 0 {
-            x = x << 8 | self.read().unwrap();
-            i -= 1;
+    /// Reads `len` bits like [`Self::read_int()`], returning an error instead of reading past the end of the stream.
+    #[inline(always)]
+    pub fn try_read_int(&mut self, len: u64) -> Result<u64> {
+        if len as usize > self.remaining_bits() {
+            return Err(Error::UnexpectedEof);
         }
 
-        self.read_bits += len as usize & !7;
-
-        len &= 7;
-
-        (x << len) | self.read_from_current(len)
+        Ok(self.read_int(len))
     }
 }
 
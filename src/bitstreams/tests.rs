@@ -1,32 +1,19 @@
-use std::{fs::{self, File}, rc::Rc};
+use std::{fs::{self, File}, io::{self, Write}, sync::Arc, time::Instant};
 
-use super::{BinaryWriter, BinaryReader, tables::{GAMMAS, ZETAS_3, DELTAS}};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
-fn write_unary(writer: &mut BinaryWriter, x: u64) -> u64 {
-    if x < writer.free as u64 {
-        return writer.write_in_current(1, x + 1);
-    }
-
-    let shift = writer.free;
-    let x = x - shift as u64;
-
-    writer.written_bits += shift;
-    writer.write(writer.current);
-    writer.free = 8;
-    writer.current = 0;
-    
-    let mut i = x >> 3;
+use super::{BinaryWriter, BinaryReader, InputStream, SINK_BUFFER_SIZE, tables::{GAMMAS, ZETAS_3, DELTAS}};
 
-    writer.written_bits += x as usize & 0x7FFFFFF8;
-    
-    while i != 0 {
-        writer.write(0);
-        i -= 1;
+fn write_unary(writer: &mut BinaryWriter, x: u64) -> u64 {
+    let mut zeros = x;
+    while zeros >= 64 {
+        writer.push_bits(0, 64);
+        zeros -= 64;
     }
 
-    writer.write_in_current(1, (x & 7) + 1);
+    writer.push_bits(1, zeros + 1);
 
-    x + shift as u64 + 1
+    x + 1
 }
 
 fn write_gamma(writer: &mut BinaryWriter, x: u64) -> u64 {
@@ -64,30 +51,21 @@ fn read_unary(reader: &mut BinaryReader) -> u64 {
         reader.refill();
     }
 
-    let mut x = u32::leading_zeros((reader.current as u32) << (32 - reader.fill));
-    if x < reader.fill as u32{
-        reader.read_bits += x as usize + 1;
-        reader.fill -= x as usize + 1;
-        return x as u64;
-    }
-
-    x = reader.fill as u32;
-    let mut read = reader.read();
-
-    if read.is_ok() {
-        reader.current = read.unwrap();
-        while reader.current == 0 && read.is_ok() {
-            x += 8;
-            read = reader.read();
-            if let Ok(r) = read {
-                reader.current = r;
-            }
+    let mut x = 0;
+    while reader.fill != 0 {
+        let zeros = (reader.current << (64 - reader.fill)).leading_zeros() as usize;
+        if zeros < reader.fill {
+            reader.read_bits += zeros + 1;
+            reader.fill -= zeros + 1;
+            return (x + zeros) as u64;
         }
+
+        x += reader.fill;
+        reader.read_bits += reader.fill;
+        reader.fill = 0;
+        reader.refill();
     }
 
-    reader.fill = (63 - u64::leading_zeros(reader.current)) as usize;
-    x += 7 - reader.fill as u32;
-    reader.read_bits += x as usize + 1;
     x as u64
 }
 
@@ -154,7 +132,7 @@ fn test_correctness_write_and_read_to_file(code: &str) {
         };
     }
 
-    let written: Rc<[u8]> = writer_builder.build().os.into();
+    let written: Arc<[u8]> = writer_builder.build().os.into();
 
     fs::write(code, written).unwrap();
     
@@ -195,6 +173,25 @@ fn test_zeta() {
     test_correctness_write_and_read_to_file("ZETA");
 }
 
+#[test]
+fn test_mmap() {
+    let mut writer_builder = BinaryWriter::new();
+
+    for x in 0..100000 {
+        write_gamma(&mut writer_builder, x);
+    }
+
+    fs::write("MMAP", writer_builder.build().os).unwrap();
+
+    let mut binary_reader = BinaryReader::new(InputStream::mmap("MMAP").unwrap());
+
+    for x in 0..100000 {
+        assert_eq!(read_gamma(&mut binary_reader, false), x);
+    }
+
+    fs::remove_file("MMAP").unwrap();
+}
+
 #[test]
 fn test_reposition() {
     let mut writer_builder = BinaryWriter::new();
@@ -275,7 +272,7 @@ fn test_gamma_precomputed_table_correctness() {
     write_gamma(&mut writer_builder, 999);
     write_gamma(&mut writer_builder, 40000);
 
-    let written: Rc<[u8]> = writer_builder.build().os.into();
+    let written: InputStream = writer_builder.build().os.into();
     let mut binary_reader_table = BinaryReader::new(written.clone());
     let mut binary_reader_normal = BinaryReader::new(written);
 
@@ -307,7 +304,7 @@ fn test_delta_precomputed_table_correctness() {
     write_delta(&mut writer_builder, 999);
     write_delta(&mut writer_builder, 40000);
 
-    let written: Rc<[u8]> = writer_builder.build().os.into();
+    let written: InputStream = writer_builder.build().os.into();
     let mut binary_reader_table = BinaryReader::new(written.clone());
     let mut binary_reader_normal = BinaryReader::new(written);
 
@@ -339,7 +336,7 @@ fn test_zeta_precomputed_table_correctness() {
     write_zeta(&mut writer_builder, 999, 3);
     write_zeta(&mut writer_builder, 40000, 3);
 
-    let written: Rc<[u8]> = writer_builder.build().os.into();
+    let written: InputStream = writer_builder.build().os.into();
     let mut binary_reader_table = BinaryReader::new(written.clone());
     let mut binary_reader_normal = BinaryReader::new(written);
 
@@ -357,4 +354,226 @@ fn test_zeta_precomputed_table_correctness() {
     assert_eq!(read_zeta(&mut binary_reader_normal, 3, false), 999);
     assert_eq!(read_zeta(&mut binary_reader_table, 3, true), 40000);
     assert_eq!(read_zeta(&mut binary_reader_normal, 3, false), 40000);
-}
\ No newline at end of file
+}
+#[test]
+fn test_append() {
+    let mut writer_builder = BinaryWriter::new();
+    let mut expected_builder = BinaryWriter::new();
+
+    for shift in [0, 3, 8, 13] {
+        let mut other = BinaryWriter::new();
+
+        writer_builder.push_bits(5, shift);
+        expected_builder.push_bits(5, shift);
+        for x in 0..100 {
+            write_gamma(&mut other, x * shift);
+            write_gamma(&mut expected_builder, x * shift);
+        }
+
+        writer_builder.append(other);
+        assert_eq!(writer_builder.written_bits, expected_builder.written_bits);
+    }
+
+    assert_eq!(writer_builder.build().os, expected_builder.build().os);
+}
+
+
+#[test]
+fn test_streaming_writer() {
+    let mut memory_builder = BinaryWriter::new();
+    let mut streamed = Vec::new();
+    let mut streaming_builder = BinaryWriter::with_sink(&mut streamed);
+
+    // Enough bits for the sink to be written to several times
+    for x in 0..1_000_000 {
+        write_gamma(&mut memory_builder, x);
+        write_gamma(&mut streaming_builder, x);
+        assert_eq!(streaming_builder.written_bits, memory_builder.written_bits);
+    }
+    write_unary(&mut memory_builder, 5);
+    write_unary(&mut streaming_builder, 5);
+
+    streaming_builder.finish().unwrap();
+
+    assert!(streamed.len() > 2 * SINK_BUFFER_SIZE);
+    assert_eq!(*streamed, *memory_builder.build().os);
+}
+
+#[test]
+fn test_append_to_streaming_writer() {
+    let mut memory_builder = BinaryWriter::new();
+    let mut streamed = Vec::new();
+    let mut streaming_builder = BinaryWriter::with_sink(&mut streamed);
+
+    for shift in [3, 8, 13] {
+        let mut memory_other = BinaryWriter::new();
+        let mut streaming_other = BinaryWriter::new();
+        for x in 0..100_000 {
+            write_gamma(&mut memory_other, x * shift);
+            write_gamma(&mut streaming_other, x * shift);
+        }
+
+        memory_builder.push_bits(5, shift);
+        streaming_builder.push_bits(5, shift);
+        memory_builder.append(memory_other);
+        streaming_builder.append(streaming_other);
+    }
+
+    streaming_builder.finish().unwrap();
+
+    assert_eq!(*streamed, *memory_builder.build().os);
+}
+
+#[test]
+fn test_streaming_writer_error() {
+    struct FailingSink;
+
+    impl Write for FailingSink {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::other("full"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut writer_builder = BinaryWriter::with_sink(FailingSink);
+    for x in 0..1_000_000 {
+        write_gamma(&mut writer_builder, x);
+    }
+
+    assert_eq!(writer_builder.finish().unwrap_err().to_string(), "full");
+}
+
+/// Packs the given integers bit by bit, most significant first, as the writer does a word at a time.
+fn pack_bits(integers: &[(u64, u64)]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut bits = 0;
+
+    for &(x, len) in integers {
+        for i in (0..len).rev() {
+            if bits % 8 == 0 {
+                bytes.push(0);
+            }
+            bytes[bits / 8] |= ((x >> i & 1) as u8) << (7 - bits % 8);
+            bits += 1;
+        }
+    }
+
+    bytes
+}
+
+fn random_integers(n: usize) -> Vec<(u64, u64)> {
+    let mut rng = StdRng::seed_from_u64(0);
+
+    (0..n).map(|_| {
+        let len = rng.gen_range(0..=64);
+        (if len == 0 { 0 } else { rng.gen::<u64>() >> (64 - len) }, len)
+    }).collect()
+}
+
+#[test]
+fn test_word_writing_matches_bitwise_packing() {
+    let integers = random_integers(10000);
+    let mut writer_builder = BinaryWriter::new();
+
+    for &(x, len) in &integers {
+        // The bits above `len` are ignored
+        let garbage = u64::MAX.checked_shl(len as u32).unwrap_or(0);
+        writer_builder.push_bits(x | garbage, len);
+    }
+
+    assert_eq!(writer_builder.written_bits, integers.iter().map(|&(_, len)| len as usize).sum::<usize>());
+    assert_eq!(*writer_builder.build().os, *pack_bits(&integers));
+}
+
+#[test]
+fn test_full_64_bit_reads() {
+    let integers = random_integers(10000);
+    let mut binary_reader = BinaryReader::new(pack_bits(&integers).into());
+
+    for &(x, len) in &integers {
+        assert_eq!(binary_reader.read_int(len), x);
+    }
+
+    let mut binary_reader = BinaryReader::new(pack_bits(&[(1, 3), (u64::MAX - 1, 64), (5, 3), (1 << 63, 64)]).into());
+
+    assert_eq!(binary_reader.read_int(3), 1);
+    assert_eq!(binary_reader.read_int(64), u64::MAX - 1);
+    assert_eq!(binary_reader.read_int(3), 5);
+    assert_eq!(binary_reader.read_int(64), 1 << 63);
+    assert_eq!(binary_reader.read_bits, 134);
+}
+
+#[test]
+fn test_long_unary_codes() {
+    let mut writer_builder = BinaryWriter::new();
+
+    for x in [0, 63, 64, 65, 200, 1000, 1] {
+        write_unary(&mut writer_builder, x);
+    }
+
+    let mut binary_reader = BinaryReader::new(writer_builder.build().os.into());
+
+    for x in [0, 63, 64, 65, 200, 1000, 1] {
+        assert_eq!(read_unary(&mut binary_reader), x);
+    }
+}
+
+fn bench_decode(name: &str, write: impl Fn(&mut BinaryWriter, u64) -> u64, read: impl Fn(&mut BinaryReader) -> u64) {
+    const N: u64 = 10_000_000;
+
+    let mut rng = StdRng::seed_from_u64(0);
+    let values: Vec<u64> = (0..N).map(|_| {
+        let bits = rng.gen_range(0..20);
+        rng.gen_range(0..1 << bits)
+    }).collect();
+    let mut writer_builder = BinaryWriter::new();
+    for &x in &values {
+        write(&mut writer_builder, x);
+    }
+    let written: InputStream = writer_builder.build().os.into();
+
+    let mut binary_reader = BinaryReader::new(written);
+    let start = Instant::now();
+    let mut sum = 0u64;
+    for _ in 0..N {
+        sum = sum.wrapping_add(read(&mut binary_reader));
+    }
+    let elapsed = start.elapsed();
+
+    assert_eq!(sum, values.iter().fold(0u64, |sum, &x| sum.wrapping_add(x)));
+    println!("{}: {:.2} ns/integer", name, elapsed.as_nanos() as f64 / N as f64);
+}
+
+// The benchmarks are ignored by default; run them with
+// `cargo test --release bench_ -- --ignored --nocapture --test-threads=1`
+
+#[test]
+#[ignore]
+fn bench_decode_gamma() {
+    bench_decode("gamma", write_gamma, |reader| read_gamma(reader, false));
+    bench_decode("gamma (table)", write_gamma, |reader| read_gamma(reader, true));
+}
+
+#[test]
+#[ignore]
+fn bench_decode_delta() {
+    bench_decode("delta", write_delta, |reader| read_delta(reader, false));
+    bench_decode("delta (table)", write_delta, |reader| read_delta(reader, true));
+}
+
+#[test]
+#[ignore]
+fn bench_decode_zeta() {
+    bench_decode("zeta_3", |writer, x| write_zeta(writer, x, 3), |reader| read_zeta(reader, 3, false));
+    bench_decode("zeta_3 (table)", |writer, x| write_zeta(writer, x, 3), |reader| read_zeta(reader, 3, true));
+}
+
+#[test]
+#[ignore]
+fn bench_decode_fixed_width() {
+    bench_decode("20-bit integers", |writer, x| writer.push_bits(x, 20), |reader| reader.read_int(20));
+    bench_decode("64-bit integers", |writer, x| writer.push_bits(x, 64), |reader| reader.read_int(64));
+}
@@ -0,0 +1,62 @@
+use std::{fmt::Display, io};
+
+/// The errors that can occur while loading, parsing, decoding or storing a graph.
+#[derive(Debug)]
+pub enum Error {
+    /// A file could not be read or written.
+    Io { path: String, source: io::Error },
+    /// A required key is missing from the properties file.
+    MissingProperty(String),
+    /// A key of the properties file has a value that cannot be parsed.
+    InvalidProperty { key: String, value: String },
+    /// An encoding name is not among the supported ones.
+    UnsupportedEncoding(String),
+    /// A compression flag is unknown, or names an encoding that cannot be used for its section.
+    UnsupportedCompressionFlag(String),
+    /// The graph class is not among the supported ones.
+    UnsupportedGraphClass(String),
+    /// A bitstream ended before the value being read.
+    UnexpectedEof,
+    /// The content of a file does not describe a valid graph.
+    Malformed(String),
+    /// A node to be compressed is out of place, the nodes having to be consecutive, starting from 0.
+    MisplacedNode { node: usize, expected: usize },
+}
+
+/// A specialized [`Result`](std::result::Result) type for this crate.
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// Builds a closure wrapping an I/O error on the given path, to be used with `map_err`.
+    pub fn io<P: AsRef<std::path::Path>>(path: P) -> impl FnOnce(io::Error) -> Error {
+        let path = path.as_ref().display().to_string();
+        move |source| Error::Io { path, source }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io { path, source } => write!(f, "I/O error on {}: {}", path, source),
+            Error::MissingProperty(key) => write!(f, "missing property {}", key),
+            Error::InvalidProperty { key, value } => write!(f, "invalid value {} for property {}", value, key),
+            Error::UnsupportedEncoding(name) => write!(f, "encoding type {} is not supported", name),
+            Error::UnsupportedCompressionFlag(flag) => write!(f, "compression flag {} is not supported", flag),
+            Error::UnsupportedGraphClass(class) => write!(f, "graph class {} is not supported", class),
+            Error::UnexpectedEof => f.write_str("unexpected end of bitstream"),
+            Error::Malformed(reason) => write!(f, "malformed graph: {}", reason),
+            Error::MisplacedNode { node, expected } => {
+                write!(f, "node {} instead of node {}: the nodes must be consecutive, starting from 0", node, expected)
+            },
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
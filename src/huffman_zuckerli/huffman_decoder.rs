@@ -1,14 +1,14 @@
-use crate::{huffman_zuckerli::{HuffmanSymbolInfo, K_MAX_HUFFMAN_BITS, K_MAX_NUM_CONTEXTS, K_NUM_SYMBOLS}, bitstreams::BinaryReader, utils::encodings::{zuck_decode, K_ZUCK, I_ZUCK, J_ZUCK}};
+use crate::{huffman_zuckerli::{HuffmanSymbolInfo, K_MAX_HUFFMAN_BITS, K_MAX_NUM_CONTEXTS, K_NUM_SYMBOLS}, bitstreams::BinaryReader, Error, Result, utils::encodings::{try_zuck_decode, zuck_decode, K_ZUCK, I_ZUCK, J_ZUCK}};
 
 use super::Huffman;
 
-#[derive(Clone, Copy, Default, Debug)]
+#[derive(Clone, Copy, Default, Eq, PartialEq, Debug)]
 struct HuffmanDecoderInfo {
     nbits: u8,
     symbol: u8,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Eq, PartialEq, Debug)]
 pub struct HuffmanDecoder {
     info_: [[HuffmanDecoderInfo; 1 << K_MAX_HUFFMAN_BITS]; K_MAX_NUM_CONTEXTS],
 }
@@ -26,21 +26,25 @@ impl HuffmanDecoder {
         Self::default()
     }
 
-    fn decode_symbol_n_bits(&self, info: &mut [HuffmanSymbolInfo], reader: &mut BinaryReader) {
-        let ms = reader.read_int(8);
+    fn decode_symbol_n_bits(&self, info: &mut [HuffmanSymbolInfo], reader: &mut BinaryReader) -> Result<()> {
+        let ms = reader.try_read_int(8)?;
         for inf in info.iter_mut().take(ms as usize + 1) {
-            inf.present = reader.read_int(1) as u8;
+            inf.present = reader.try_read_int(1)? as u8;
             if inf.present == 1 {
-                inf.nbits = reader.read_int(3) as u8 + 1;
+                inf.nbits = reader.try_read_int(3)? as u8 + 1;
             }
         }
         for inf in info.iter_mut().skip(ms as usize + 1) {
             inf.present = 0;
         }
+
+        Ok(())
     }
 
     /// Computes the lookup table from bitstream bits to decoded symbol for the decoder.
-    fn compute_decoder_table(&mut self, sym_info: &mut [HuffmanSymbolInfo], ctx: usize) {
+    /// 
+    /// Returns an error if the code lengths do not describe a prefix code.
+    fn compute_decoder_table(&mut self, sym_info: &mut [HuffmanSymbolInfo], ctx: usize) -> Result<()> {
         let mut cnt = 0;
         let mut s = 0;
         for (i, sym) in sym_info.iter().enumerate() {
@@ -52,12 +56,17 @@ impl HuffmanDecoder {
             s = i;
         }
 
+        // The lengths of an over-subscribed code leave some symbols without a codeword of their length
+        if sym_info.iter().any(|sym| sym.present != 0 && sym.bits >> sym.nbits != 0) {
+            return Err(Error::Malformed(format!("invalid Huffman table for context {}", ctx)));
+        }
+
         if cnt <= 1 {
             for dinfo in self.info_[ctx].iter_mut() {
                 dinfo.nbits = sym_info[s].nbits;
                 dinfo.symbol = s as u8;
             }
-            return;
+            return Ok(());
         }
 
         for (i, dinfo) in self.info_[ctx].iter_mut().enumerate() {
@@ -72,27 +81,30 @@ impl HuffmanDecoder {
                     break;
                 }
             }
-            // assert_ne!(s, K_NUM_SYMBOLS, "Invalid table");
             if s != K_NUM_SYMBOLS {
                 dinfo.nbits = sym_info[s].nbits;
                 dinfo.symbol = s as u8;
             }
         }
+
+        Ok(())
     }
 
     #[inline(always)]
-    pub fn decode_headers(&mut self, bin_reader: &mut BinaryReader, num_contexts: usize) {
+    pub fn decode_headers(&mut self, bin_reader: &mut BinaryReader, num_contexts: usize) -> Result<()> {
         assert!(num_contexts < K_MAX_NUM_CONTEXTS, "The number of contexts has to be smaller than {K_MAX_NUM_CONTEXTS}");
         for ctx in 0..num_contexts {
             let mut symbol_info = [HuffmanSymbolInfo::default(); K_NUM_SYMBOLS];
-            self.decode_symbol_n_bits(&mut symbol_info, bin_reader);
+            self.decode_symbol_n_bits(&mut symbol_info, bin_reader)?;
             HuffmanDecoder::compute_symbol_bits(&mut symbol_info);
-            self.compute_decoder_table(&mut symbol_info, ctx);
+            self.compute_decoder_table(&mut symbol_info, ctx)?;
         }
+
+        Ok(())
     }
 
     #[inline(always)]
-    pub fn read_next(&mut self, bin_reader: &mut BinaryReader, ctx: usize) -> usize {
+    pub fn read_next(&self, bin_reader: &mut BinaryReader, ctx: usize) -> usize {
         let mut curr_len = 0;
         let mut curr_code = 0;
 
@@ -107,4 +119,20 @@ impl HuffmanDecoder {
         
         panic!("Malformed code")
     }
+
+    /// Reads the next value like [`Self::read_next()`], returning an error on an invalid code or instead of reading
+    /// past the end of the stream.
+    #[inline(always)]
+    pub fn try_read_next(&self, bin_reader: &mut BinaryReader, ctx: usize) -> Result<usize> {
+        let mut curr_code = 0;
+
+        for curr_len in 1..=K_MAX_HUFFMAN_BITS {
+            curr_code = (curr_code << 1) | bin_reader.try_read_int(1)?;
+            if self.info_[ctx][curr_code as usize].nbits == curr_len as u8 {
+                return try_zuck_decode(self.info_[ctx][curr_code as usize].symbol as usize, bin_reader, K_ZUCK, I_ZUCK, J_ZUCK);
+            }
+        }
+
+        Err(Error::Malformed(format!("invalid Huffman code in context {}", ctx)))
+    }
 }
\ No newline at end of file
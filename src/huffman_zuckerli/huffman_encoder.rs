@@ -8,6 +8,7 @@ use crate::{
 
 use super::Huffman;
 
+#[derive(Clone)]
 pub struct HuffmanEncoder {
     info_: [[HuffmanSymbolInfo; 1 << K_MAX_HUFFMAN_BITS]; K_MAX_NUM_CONTEXTS],
     pub(crate) bits_per_context: [u64; K_MAX_NUM_CONTEXTS],
@@ -29,6 +30,11 @@ impl HuffmanEncoder {
         Self::default()
     }
 
+    /// Returns the number of bits written so far in each context, headers excluded.
+    pub fn bits_per_context(&self) -> &[u64] {
+        &self.bits_per_context
+    }
+
     /// Computes the optimal number of bits for each symbol given the input
     /// distribution. Uses a (quadratic version) of the package-merge/coin-collector
     /// algorithm.
@@ -94,8 +100,7 @@ impl HuffmanEncoder {
         // In the groups of symbols for the highest bit length we need to select the
         // last 2*num_symbols-2 groups, and assign to each symbol one bit of cost for
         // each of its occurrences in these groups.
-        for i in 0..(2 * nzsym - 2) {
-            let b = &bags[K_MAX_HUFFMAN_BITS - 1][i];
+        for b in bags[K_MAX_HUFFMAN_BITS - 1].iter().take(2 * nzsym - 2) {
             for &x in b.1.iter() {
                 self.info_[ctx][x as usize].nbits += 1;
             }
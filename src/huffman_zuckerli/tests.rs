@@ -24,7 +24,7 @@ fn test_single_context_sequential() {
 
     let mut huff_decoder = HuffmanDecoder::new();
 
-    huff_decoder.decode_headers(&mut reader, num_contexts);
+    huff_decoder.decode_headers(&mut reader, num_contexts).unwrap();
 
     for (ctx, int) in ints.iter().enumerate() {
         for x in int {
@@ -60,7 +60,7 @@ fn test_multi_context_sequential() {
 
     let mut huff_decoder = HuffmanDecoder::new();
 
-    huff_decoder.decode_headers(&mut reader, num_contexts);
+    huff_decoder.decode_headers(&mut reader, num_contexts).unwrap();
 
     for (ctx, int) in ints.iter().enumerate() {
         for x in int {
@@ -94,7 +94,7 @@ fn test_single_context_alternate() {
 
     let mut huff_decoder = HuffmanDecoder::new();
 
-    huff_decoder.decode_headers(&mut reader, num_contexts);
+    huff_decoder.decode_headers(&mut reader, num_contexts).unwrap();
     
     assert_eq!(ints[0][2], huff_decoder.read_next(&mut reader, 0));
     assert_eq!(ints[0][1], huff_decoder.read_next(&mut reader, 0));
@@ -132,7 +132,7 @@ fn test_multi_context_alternate() {
 
     let mut huff_decoder = HuffmanDecoder::new();
 
-    huff_decoder.decode_headers(&mut reader, num_contexts);
+    huff_decoder.decode_headers(&mut reader, num_contexts).unwrap();
 
     for i in 0..ints[0].len() {
         for (ctx, int) in ints.iter().enumerate() {
@@ -154,4 +154,64 @@ fn test_non_existing_code() {
     huff_encoder.init(&ints , &mut writer);
 
     huff_encoder.write_next(30, &mut writer, 0);   
-}
\ No newline at end of file
+}
+#[test]
+fn test_checked_sequential() {
+    let ints = vec![
+        vec![100, 200, 3, 0, 10, 3, 2500000000]
+    ];
+    let num_contexts = ints.len();
+
+    let mut writer = BinaryWriter::new();
+
+    let mut huff_encoder = HuffmanEncoder::new();
+    huff_encoder.init(&ints , &mut writer);
+
+    for (ctx, int_arr) in ints.iter().enumerate() {
+        for int in int_arr {
+            huff_encoder.write_next(*int, &mut writer, ctx);
+        }
+    }
+
+    let mut reader = BinaryReader::new(writer.build().os.into());
+
+    let mut huff_decoder = HuffmanDecoder::new();
+
+    huff_decoder.decode_headers(&mut reader, num_contexts).unwrap();
+
+    for (ctx, int) in ints.iter().enumerate() {
+        for x in int {
+            assert_eq!(*x, huff_decoder.try_read_next(&mut reader, ctx).unwrap());
+        }
+    }
+
+    // Only the padding of the last byte is left
+    while reader.remaining_bits() > 0 {
+        if huff_decoder.try_read_next(&mut reader, 0).is_err() {
+            return;
+        }
+    }
+    assert!(huff_decoder.try_read_next(&mut reader, 0).is_err());
+}
+
+#[test]
+fn test_truncated_headers() {
+    let ints = vec![
+        vec![100, 200, 3, 0, 10, 3, 2500000000],
+        vec![1, 1, 2, 3, 5, 8, 13],
+    ];
+    let num_contexts = ints.len();
+
+    let mut writer = BinaryWriter::new();
+
+    let mut huff_encoder = HuffmanEncoder::new();
+    huff_encoder.init(&ints , &mut writer);
+
+    let headers = writer.build().os;
+
+    let mut reader = BinaryReader::new(headers[..headers.len() / 2].to_vec().into());
+
+    let mut huff_decoder = HuffmanDecoder::new();
+
+    assert!(huff_decoder.decode_headers(&mut reader, num_contexts).is_err());
+}
@@ -4,13 +4,259 @@ pub mod ascii_graph;
 pub mod properties;
 pub mod utils;
 pub mod huffman_zuckerli;
+pub mod error;
 
-pub trait ImmutableGraph {
-    type NodeT;
+pub use error::{Error, Result};
+
+/// A graph whose successor lists can only be enumerated in order of node, e.g., a graph
+/// being streamed or a compressed graph whose offsets have not been loaded.
+pub trait SequentialGraph {
+    type NodeT: PartialOrd + Copy;
 
     fn num_nodes(&self) -> usize;
     fn num_arcs(&self) -> usize;
+
+    /// Returns an iterator over the nodes of the graph, in increasing order, each paired with its successors.
+    fn iter_nodes(&self) -> impl Iterator<Item = (Self::NodeT, Box<[Self::NodeT]>)> + '_;
+}
+
+/// A graph giving access to the successors of any node.
+pub trait RandomAccessGraph: SequentialGraph {
     fn outdegree(&self, x: Self::NodeT) -> Option<usize>; 
     fn successors(&self, x: Self::NodeT) -> Box<[Self::NodeT]>;
-    fn store(&mut self, filename: &str) -> std::io::Result<()>;
-}
\ No newline at end of file
+    fn successors_iter(&self, x: Self::NodeT) -> impl Iterator<Item = Self::NodeT> + '_;
+
+    /// Returns whether the arc from `x` to `y` exists.
+    /// 
+    /// Since successor lists are sorted, decoding stops at the first successor not smaller than `y`.
+    fn has_arc(&self, x: Self::NodeT, y: Self::NodeT) -> bool {
+        self.successors_iter(x).find(|&succ| succ >= y) == Some(y)
+    }
+
+    /// Returns the `i`-th successor of `x`, or `None` if `x` has at most `i` successors.
+    fn successor(&self, x: Self::NodeT, i: usize) -> Option<Self::NodeT> {
+        self.successors_iter(x).nth(i)
+    }
+
+    /// Returns a lazy iterator over the successors of `x` in the range `[lo, hi)`.
+    fn successors_in_range(&self, x: Self::NodeT, lo: Self::NodeT, hi: Self::NodeT) -> impl Iterator<Item = Self::NodeT> + '_ {
+        self.successors_iter(x)
+            .skip_while(move |&succ| succ < lo)
+            .take_while(move |&succ| succ < hi)
+    }
+}
+
+/// A graph that can be written to disk.
+pub trait Store {
+    fn store(&mut self, basename: &str) -> Result<()>;
+}
+/// An object-safe counterpart of [`SequentialGraph`], allowing graphs whose type is known only at runtime
+/// to be used through a `Box<dyn DynSequentialGraph>` (see [`webgraph::load::GraphLoader::load_sequential()`]).
+pub trait DynSequentialGraph {
+    fn num_nodes(&self) -> usize;
+    fn num_arcs(&self) -> usize;
+    fn iter_nodes(&self) -> Box<dyn Iterator<Item = (usize, Box<[usize]>)> + '_>;
+}
+
+/// An object-safe counterpart of [`RandomAccessGraph`], allowing graphs whose type is known only at runtime
+/// to be used through a `Box<dyn DynRandomAccessGraph>` (see [`webgraph::load::load()`]).
+pub trait DynRandomAccessGraph: DynSequentialGraph {
+    fn outdegree(&self, x: usize) -> Option<usize>;
+    fn successors(&self, x: usize) -> Box<[usize]>;
+    fn successors_iter(&self, x: usize) -> Box<dyn Iterator<Item = usize> + '_>;
+    fn has_arc(&self, x: usize, y: usize) -> bool;
+    fn successor(&self, x: usize, i: usize) -> Option<usize>;
+    fn successors_in_range(&self, x: usize, lo: usize, hi: usize) -> Box<dyn Iterator<Item = usize> + '_>;
+}
+
+/// The iterator over the nodes of a [`CheckedGraph`] decoded in checked mode, each paired with its successors.
+pub type CheckedNodes<'a> = Box<dyn Iterator<Item = Result<(usize, Box<[usize]>)>> + 'a>;
+
+/// A graph whose successor lists can also be decoded in checked mode, validating the compressed data instead
+/// of assuming it well-formed, so that corrupted or untrusted files produce errors rather than panics or wrong
+/// lists (see [`webgraph::load::GraphLoader::load_checked()`]).
+pub trait CheckedGraph: DynRandomAccessGraph {
+    /// Returns an iterator over the nodes of the graph, in increasing order, each paired with its successors.
+    /// 
+    /// The iteration stops after the first error.
+    fn try_iter_nodes(&self) -> CheckedNodes<'_>;
+
+    /// Returns the successors of `x`, or an error if its successor list, or any list it refers to, is malformed.
+    fn try_successors(&self, x: usize) -> Result<Box<[usize]>>;
+
+    /// Returns the cost of decoding the successors of `x` by random access, measured while decoding them
+    /// in checked mode.
+    fn try_access_cost(&self, x: usize) -> Result<AccessCost>;
+}
+
+/// The cost of decoding a successor list by random access (see [`CheckedGraph::try_access_cost()`]).
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub struct AccessCost {
+    /// The bits read, over the list and the whole chain of lists it refers to
+    pub bits: u64,
+    /// The number of references followed
+    pub chain_depth: usize,
+}
+
+impl<G: SequentialGraph<NodeT = usize>> DynSequentialGraph for G {
+    fn num_nodes(&self) -> usize {
+        SequentialGraph::num_nodes(self)
+    }
+
+    fn num_arcs(&self) -> usize {
+        SequentialGraph::num_arcs(self)
+    }
+
+    fn iter_nodes(&self) -> Box<dyn Iterator<Item = (usize, Box<[usize]>)> + '_> {
+        Box::new(SequentialGraph::iter_nodes(self))
+    }
+}
+
+impl<G: RandomAccessGraph<NodeT = usize>> DynRandomAccessGraph for G {
+    fn outdegree(&self, x: usize) -> Option<usize> {
+        RandomAccessGraph::outdegree(self, x)
+    }
+
+    fn successors(&self, x: usize) -> Box<[usize]> {
+        RandomAccessGraph::successors(self, x)
+    }
+
+    fn successors_iter(&self, x: usize) -> Box<dyn Iterator<Item = usize> + '_> {
+        Box::new(RandomAccessGraph::successors_iter(self, x))
+    }
+
+    fn has_arc(&self, x: usize, y: usize) -> bool {
+        RandomAccessGraph::has_arc(self, x, y)
+    }
+
+    fn successor(&self, x: usize, i: usize) -> Option<usize> {
+        RandomAccessGraph::successor(self, x, i)
+    }
+
+    fn successors_in_range(&self, x: usize, lo: usize, hi: usize) -> Box<dyn Iterator<Item = usize> + '_> {
+        Box::new(RandomAccessGraph::successors_in_range(self, x, lo, hi))
+    }
+}
+
+impl SequentialGraph for Box<dyn DynSequentialGraph> {
+    type NodeT = usize;
+
+    fn num_nodes(&self) -> usize {
+        (**self).num_nodes()
+    }
+
+    fn num_arcs(&self) -> usize {
+        (**self).num_arcs()
+    }
+
+    fn iter_nodes(&self) -> impl Iterator<Item = (usize, Box<[usize]>)> + '_ {
+        (**self).iter_nodes()
+    }
+}
+
+impl SequentialGraph for Box<dyn DynRandomAccessGraph> {
+    type NodeT = usize;
+
+    fn num_nodes(&self) -> usize {
+        (**self).num_nodes()
+    }
+
+    fn num_arcs(&self) -> usize {
+        (**self).num_arcs()
+    }
+
+    fn iter_nodes(&self) -> impl Iterator<Item = (usize, Box<[usize]>)> + '_ {
+        (**self).iter_nodes()
+    }
+}
+
+impl RandomAccessGraph for Box<dyn DynRandomAccessGraph> {
+    fn outdegree(&self, x: usize) -> Option<usize> {
+        (**self).outdegree(x)
+    }
+
+    fn successors(&self, x: usize) -> Box<[usize]> {
+        (**self).successors(x)
+    }
+
+    fn successors_iter(&self, x: usize) -> impl Iterator<Item = usize> + '_ {
+        (**self).successors_iter(x)
+    }
+
+    fn has_arc(&self, x: usize, y: usize) -> bool {
+        (**self).has_arc(x, y)
+    }
+
+    fn successor(&self, x: usize, i: usize) -> Option<usize> {
+        (**self).successor(x, i)
+    }
+
+    fn successors_in_range(&self, x: usize, lo: usize, hi: usize) -> impl Iterator<Item = usize> + '_ {
+        (**self).successors_in_range(x, lo, hi)
+    }
+}
+
+/// The first difference between two graphs, as found by [`first_difference()`], the first graph
+/// being taken as the reference.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Difference {
+    /// The graphs have a different number of nodes (the one of the first graph, then the one of the second).
+    NumNodes(usize, usize),
+    /// The node has different successor lists in the two graphs.
+    Successors { node: usize, first: Box<[usize]>, second: Box<[usize]> },
+}
+
+impl std::fmt::Display for Difference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Difference::NumNodes(first, second) => write!(f, "{} nodes instead of {}", second, first),
+            Difference::Successors { node, first, second } => write!(
+                f,
+                "node {} has outdegree {} and successors {:?} instead of outdegree {} and successors {:?}",
+                node, second.len(), second, first.len(), first
+            ),
+        }
+    }
+}
+
+/// Compares two graphs list by list, whatever their formats and parameters, returning the first node whose
+/// successors differ, or `None` if the graphs are the same.
+///
+/// Both graphs are enumerated sequentially only once.
+pub fn first_difference<A, B>(first: &A, second: &B) -> Option<Difference>
+where
+    A: SequentialGraph<NodeT = usize> + ?Sized,
+    B: SequentialGraph<NodeT = usize> + ?Sized,
+{
+    if first.num_nodes() != second.num_nodes() {
+        return Some(Difference::NumNodes(first.num_nodes(), second.num_nodes()));
+    }
+
+    let mut first_nodes = first.iter_nodes();
+    let mut second_nodes = second.iter_nodes();
+    let mut node = 0;
+
+    loop {
+        match (first_nodes.next(), second_nodes.next()) {
+            (None, None) => return None,
+            (Some((_, first)), Some((_, second))) => {
+                if first != second {
+                    return Some(Difference::Successors { node, first, second });
+                }
+            },
+            // One of the iterators ended before the number of nodes it declares
+            (first_list, _) => {
+                let (first_len, second_len) = if first_list.is_some() {
+                    (node + 1 + first_nodes.count(), node)
+                } else {
+                    (node, node + 1 + second_nodes.count())
+                };
+                return Some(Difference::NumNodes(first_len, second_len));
+            },
+        }
+        node += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests;
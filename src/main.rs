@@ -1,302 +1,668 @@
-use webgraph_rust::ascii_graph::{AsciiGraphBuilder, AsciiGraph};
-use webgraph_rust::properties::Properties;
+use webgraph_rust::ascii_graph::AsciiGraphBuilder;
+use webgraph_rust::bitstreams::BinaryWriter;
+use webgraph_rust::properties::{GraphFormat, Properties};
 use webgraph_rust::utils::EncodingType;
-use webgraph_rust::utils::encodings::{UniversalCode, GammaCode, UnaryCode, ZetaCode, DeltaCode};
-use webgraph_rust::webgraph::bvgraph::{BVGraph, BVGraphBuilder};
-use webgraph_rust::ImmutableGraph;
+use webgraph_rust::utils::encodings::Code;
+use webgraph_rust::webgraph::{bvgraph, bvgraph_huffman_out, zuckerli_out};
+use webgraph_rust::webgraph::container::{self, FileKind};
+use webgraph_rust::webgraph::bench::{Benchmark, BenchmarkReport, QueryDistribution};
+use webgraph_rust::webgraph::load::GraphLoader;
+use webgraph_rust::webgraph::stats::CompressionStats;
+use webgraph_rust::webgraph::offsets::store_elias_fano;
+use webgraph_rust::{first_difference, CheckedGraph, Error, Result, SequentialGraph};
 
-use core::panic;
-use std::fs::File;
-use std::io::BufReader;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::process;
 use std::time::Instant;
 
-use clap::Parser;
-
-use rand::Rng;
+use clap::{builder::RangedU64ValueParser, Args, Parser, Subcommand, ValueEnum};
 
 #[derive(Parser, Debug)]
-struct WGArgs {
+#[command(name = "webgraph", about = "Compress, convert and inspect BV, Huffman and Zuckerli graphs")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Compresses a graph in the given format, with the default parameters unless specified
+    Compress {
+        #[command(flatten)]
+        source: SourceArgs,
+        /// Destination basename
+        dest_name: String,
+        /// The format of the compressed graph
+        #[arg(short, long, value_enum, default_value_t = GraphFormat::BV)]
+        format: GraphFormat,
+        #[command(flatten)]
+        params: CompressionArgs,
+    },
+    /// Compresses a graph again in its own format, keeping the parameters that are not specified
+    Recompress {
+        #[command(flatten)]
+        source: SourceArgs,
+        /// Destination basename
+        dest_name: String,
+        #[command(flatten)]
+        params: CompressionArgs,
+    },
+    /// Converts a graph to another format, keeping the parameters that are not specified
+    Transcode {
+        #[command(flatten)]
+        source: SourceArgs,
+        /// Destination basename
+        dest_name: String,
+        /// The format of the converted graph
+        #[arg(short, long = "to", value_enum)]
+        format: GraphFormat,
+        #[command(flatten)]
+        params: CompressionArgs,
+    },
+    /// Writes the ASCII version of a graph (<node_id, posting_list>) to `<dest_name>.txt`
+    ToAscii {
+        #[command(flatten)]
+        source: SourceArgs,
+        /// The basename of the ascii file to be written
+        dest_name: String,
+    },
+    /// Compresses an ASCII graph, with the default parameters unless specified
+    FromAscii {
+        /// The ascii file to be read
+        source_name: String,
+        /// Destination basename
+        dest_name: String,
+        /// The format of the compressed graph
+        #[arg(short, long, value_enum, default_value_t = GraphFormat::BV)]
+        format: GraphFormat,
+        #[command(flatten)]
+        params: CompressionArgs,
+    },
+    /// Generates the .offsets (or .offsets.ef) file of a BV graph
+    BuildOffsets {
+        #[command(flatten)]
+        source: SourceArgs,
+        /// Whether to generate the Elias-Fano encoded offsets
+        #[arg(long = "ef", default_value_t = false)]
+        elias_fano: bool,
+    },
+    /// Prints the size of a graph and of its offsets
+    Stats {
+        #[command(flatten)]
+        source: SourceArgs,
+    },
+    /// Decodes a whole graph in checked mode, checking that sequential and random access agree with each other and with its properties
+    Check {
+        #[command(flatten)]
+        source: SourceArgs,
+    },
+    /// Benchmarks random access, and optionally a sequential scan, on one or more graphs, writing a CSV or JSON report
+    Bench {
+        /// The basenames of the graphs, which are queried on the same nodes if they have the same outdegrees
+        #[arg(required = true)]
+        source_names: Vec<String>,
+        /// Whether to memory-map the graphs instead of reading them in memory
+        #[arg(long = "mmap", default_value_t = false)]
+        mmap: bool,
+        #[command(flatten)]
+        params: BenchArgs,
+    },
+    /// Compresses graphs with each code for their outdegrees, blocks, intervals and residuals, and in the Huffman
+    /// and Zuckerli formats, writing the size and timing tables of the resulting graphs as CSV
+    Experiments {
+        /// The basenames of the graphs
+        #[arg(required = true)]
+        source_names: Vec<String>,
+        /// The directory where the compressed graphs and the tables are written
+        #[arg(short = 'o', long = "out-dir", default_value = "experiments")]
+        out_dir: String,
+        /// The number of timed random queries on each compressed graph
+        #[arg(short = 'n', long = "queries", default_value_t = 100000, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+        queries: usize,
+        /// The seed of the generator drawing the queries
+        #[arg(long = "seed", default_value_t = 0)]
+        seed: u64,
+        /// Whether to keep the compressed graphs instead of deleting them once measured
+        #[arg(long = "keep", default_value_t = false)]
+        keep: bool,
+    },
+}
+
+#[derive(Args, Debug)]
+struct SourceArgs {
+    /// Source basename
+    source_name: String,
+    /// Whether to memory-map the source graph instead of reading it in memory
+    #[arg(long = "mmap", default_value_t = false)]
+    mmap: bool,
+}
+
+#[derive(Args, Debug)]
+struct BenchArgs {
+    /// The number of timed queries
+    #[arg(short = 'n', long = "queries", default_value_t = 1000000, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    queries: usize,
+    /// The number of untimed queries run before the timed ones
+    #[arg(long = "warmup", default_value_t = 10000)]
+    warmup: usize,
+    /// The seed of the generator drawing the queries
+    #[arg(long = "seed", default_value_t = 0)]
+    seed: u64,
+    /// The distribution of the queried nodes
+    #[arg(long = "distribution", value_enum, default_value_t = Distribution::Uniform)]
+    distribution: Distribution,
+    /// A file of nodes, one per line, to be queried in order instead of drawing them
+    #[arg(long = "replay")]
+    replay: Option<String>,
+    /// Whether to time a full sequential scan too
+    #[arg(long = "sequential", default_value_t = false)]
+    sequential: bool,
+    /// The format of the report
+    #[arg(long = "format", value_enum, default_value_t = ReportFormat::Csv)]
+    format: ReportFormat,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Distribution {
+    /// Nodes drawn uniformly at random
+    Uniform,
+    /// Nodes drawn with probability proportional to their outdegree
+    Degree,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum ReportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Args, Debug, Default)]
+struct CompressionArgs {
     /// The size of the window
-    #[arg(short = 'w', long = "window-size", default_value_t = 7)]
-    window_size: usize,
+    #[arg(short = 'w', long = "window-size")]
+    window_size: Option<usize>,
     /// The maximum reference chain length
-    #[arg(short = 'r', long = "max-ref-count", default_value_t = 3)]
-    max_ref_count: usize,
+    #[arg(short = 'r', long = "max-ref-count")]
+    max_ref_count: Option<usize>,
     /// The minimum length of the interval
-    #[arg(short = 'i', long = "min-interval-len", default_value_t = 4)]
-    min_interval_length: usize,
-    /// Specifies the block coding type
-    #[arg(long = "block-coding", default_value_t = EncodingType::GAMMA)]
-    block_coding: EncodingType,
-    /// Specifies the block count coding type
-    #[arg(long = "block-count-coding", default_value_t = EncodingType::GAMMA)]
-    block_count_coding: EncodingType,
-    /// Specifies the outdegree coding type
-    #[arg(long = "outdegree-coding", default_value_t = EncodingType::GAMMA)]
-    outdegree_coding: EncodingType,
-    /// Specifies the offset coding type
-    #[arg(long = "offset-coding", default_value_t = EncodingType::GAMMA)]
-    offset_coding: EncodingType,
-    /// Specifies the reference coding type
-    #[arg(long = "reference-coding", default_value_t = EncodingType::UNARY)]
-    reference_coding: EncodingType,
-    /// Specifies the intervals coding type
-    #[arg(long = "interval-coding", default_value_t = EncodingType::GAMMA)]
-    interval_coding: EncodingType,
-    /// Specifies the residual coding type
-    #[arg(long = "residual-coding", default_value_t = EncodingType::ZETA)]
-    residual_coding: EncodingType,
+    #[arg(short = 'i', long = "min-interval-len")]
+    min_interval_length: Option<usize>,
     /// Specifies the k parameter for ZetaK coding
-    #[arg(short = 'k', long = "zetak", default_value_t = 3)]
-    zeta_k: usize,
+    #[arg(short = 'k', long = "zetak")]
+    zeta_k: Option<u64>,
+    /// Specifies the block coding type (BV graphs only)
+    #[arg(long = "block-coding")]
+    block_coding: Option<EncodingType>,
+    /// Specifies the block count coding type (BV graphs only)
+    #[arg(long = "block-count-coding")]
+    block_count_coding: Option<EncodingType>,
+    /// Specifies the outdegree coding type (BV graphs only)
+    #[arg(long = "outdegree-coding")]
+    outdegree_coding: Option<EncodingType>,
+    /// Specifies the offset coding type (BV graphs only)
+    #[arg(long = "offset-coding")]
+    offset_coding: Option<EncodingType>,
+    /// Specifies the reference coding type (BV graphs only)
+    #[arg(long = "reference-coding")]
+    reference_coding: Option<EncodingType>,
+    /// Specifies the intervals coding type (BV graphs only)
+    #[arg(long = "interval-coding")]
+    interval_coding: Option<EncodingType>,
+    /// Specifies the residual coding type (BV graphs only)
+    #[arg(long = "residual-coding")]
+    residual_coding: Option<EncodingType>,
     /// Whether to compress offsets through Elias-Fano. It overwrites the offset_coding, if specified.
     #[arg(long = "ef", default_value_t = false)]
     elias_fano: bool,
-    /// Source basename
-    source_name: String,                                                    // TODO: PathBuf instead of str?
-    /// Destination basename
-    dest_name: Option<String>,                                              // TODO: PathBuf instead of str?
-    /// Check that the written graph is equal to the read graph
-    #[arg(short, long = "check", default_value_t = false)]
+    /// Whether to precede each file with a container header, which identifies it and records its checksums
+    #[arg(long = "container", default_value_t = false)]
+    container: bool,
+    /// Whether to check, after compressing, that the compressed graph has the same successor lists as the source
+    #[arg(long = "check", default_value_t = false)]
     check: bool,
-    /// Performance test
-    #[arg(short, long = "perf", default_value_t = false)]
-    perf_test: bool,
-    /// Compress starting from an ASCII graph
-    #[arg(short = 'a', long = "ascii", default_value_t = false)]
-    from_plain: bool,
+    /// Whether to write a `.stats` file reporting where the bits of the compressed graph go
+    #[arg(long = "stats", default_value_t = false)]
+    stats: bool,
+    /// The number of threads compressing the graph: with more than one, chunks of nodes are compressed in parallel
+    #[arg(short = 't', long = "threads", default_value_t = 1)]
+    threads: usize,
+    /// The number of nodes between the sync points written to a `.sync` file, from which scans can start without offsets (0 not to write them)
+    #[arg(long = "sync-interval", default_value_t = 0)]
+    sync_interval: usize,
 }
 
-const N_QUERIES: usize = 1000000;
+impl CompressionArgs {
+    /// Returns the properties of the graph to be written in the given format, taking the parameters
+    /// that are not specified from `base`.
+    ///
+    /// The codes of `base` are kept only if it has the same format, and they can be specified only
+    /// for BV graphs, since the codes of Huffman and Zuckerli graphs are fixed.
+    fn resolve(&self, base: &Properties, format: GraphFormat) -> Result<Properties> {
+        let defaults = Properties::default();
+        let codes = if base.format == format { base } else { &defaults };
+
+        let custom_codes = [
+            ("--block-coding", self.block_coding),
+            ("--block-count-coding", self.block_count_coding),
+            ("--outdegree-coding", self.outdegree_coding),
+            ("--offset-coding", self.offset_coding),
+            ("--reference-coding", self.reference_coding),
+            ("--interval-coding", self.interval_coding),
+            ("--residual-coding", self.residual_coding),
+        ];
 
-fn gen_queries(n_queries: usize, range_size: usize) -> Vec<usize> {
-    let mut rng = rand::thread_rng();
-    (0..n_queries)
-        .map(|_| rng.gen_range(0..range_size))
-        .collect()
+        if format != GraphFormat::BV {
+            if let Some((flag, _)) = custom_codes.iter().find(|(_, code)| code.is_some()) {
+                return Err(Error::UnsupportedCompressionFlag(format!("{} for {:?} graphs", flag, format)));
+            }
+        }
+
+        Ok(Properties {
+            format,
+            nodes: base.nodes,
+            arcs: base.arcs,
+            window_size: self.window_size.unwrap_or(base.window_size),
+            max_ref_count: self.max_ref_count.unwrap_or(base.max_ref_count),
+            min_interval_len: self.min_interval_length.unwrap_or(base.min_interval_len),
+            zeta_k: self.zeta_k.or(base.zeta_k),
+            block_coding: self.block_coding.unwrap_or(codes.block_coding),
+            block_count_coding: self.block_count_coding.unwrap_or(codes.block_count_coding),
+            outdegree_coding: self.outdegree_coding.unwrap_or(codes.outdegree_coding),
+            offset_coding: self.offset_coding.unwrap_or(codes.offset_coding),
+            reference_coding: self.reference_coding.unwrap_or(codes.reference_coding),
+            interval_coding: self.interval_coding.unwrap_or(codes.interval_coding),
+            residual_coding: self.residual_coding.unwrap_or(codes.residual_coding),
+        })
+    }
 }
 
-fn decompression_perf_test<
-    InBlockCoding: UniversalCode,
-    InBlockCountCoding: UniversalCode,
-    InOutDegreeCoding: UniversalCode,
-    InOffsetCoding: UniversalCode,
-    InReferenceCoding: UniversalCode,
-    InIntervalCoding: UniversalCode,
-    InResidualCoding: UniversalCode,
-    OutBlockCoding: UniversalCode,
-    OutBlockCountCoding: UniversalCode,
-    OutOutDegreeCoding: UniversalCode,
-    OutOffsetCoding: UniversalCode,
-    OutReferenceCoding: UniversalCode,
-    OutIntervalCoding: UniversalCode,
-    OutResidualCoding: UniversalCode,
->(bvgraph: &mut BVGraph<
-    InBlockCoding,
-    InBlockCountCoding,
-    InOutDegreeCoding,
-    InOffsetCoding,
-    InReferenceCoding,
-    InIntervalCoding,
-    InResidualCoding,
-    OutBlockCoding,
-    OutBlockCountCoding,
-    OutOutDegreeCoding,
-    OutOffsetCoding,
-    OutReferenceCoding,
-    OutIntervalCoding,
-    OutResidualCoding,
->) {
-    let n = bvgraph.num_nodes();
-
-    let queries = gen_queries(N_QUERIES, n - 1);
-
-    let total = Instant::now();
-    for &query in queries.iter() {
-        bvgraph.decode_list(query, &mut bvgraph.graph_binary_wrapper.borrow_mut(), None, &mut []);
+fn bv_builder(source: &SourceArgs, props: &Properties) -> Result<bvgraph::BVGraphBuilder> {
+    bvgraph::BVGraphBuilder::new()
+        .set_in_min_interval_len(props.min_interval_len)
+        .set_in_max_ref_count(props.max_ref_count)
+        .set_in_window_size(props.window_size)
+        .set_in_zeta(props.zeta_k)
+        .set_in_block_coding(props.block_coding.try_into()?)
+        .set_in_block_count_coding(props.block_count_coding.try_into()?)
+        .set_in_outdegree_coding(props.outdegree_coding.try_into()?)
+        .set_in_offset_coding(props.offset_coding.try_into()?)
+        .set_in_reference_coding(props.reference_coding.try_into()?)
+        .set_in_interval_coding(props.interval_coding.try_into()?)
+        .set_in_residual_coding(props.residual_coding.try_into()?)
+        .set_num_nodes(props.nodes)
+        .set_num_edges(props.arcs)
+        .set_mmap(source.mmap)
+        .load_graph(&source.source_name)
+}
+
+/// Compresses a graph in the format and with the parameters given by `props`.
+fn store<G: SequentialGraph<NodeT = usize>>(graph: &G, props: &Properties, params: &CompressionArgs, dest_name: &str) -> Result<()> {
+    match props.format {
+        GraphFormat::BV => bvgraph::BVGraphBuilder::new()
+            .set_out_min_interval_len(props.min_interval_len)
+            .set_out_max_ref_count(props.max_ref_count)
+            .set_out_window_size(props.window_size)
+            .set_out_zeta(props.zeta_k)
+            .set_out_block_coding(props.block_coding.try_into()?)
+            .set_out_block_count_coding(props.block_count_coding.try_into()?)
+            .set_out_outdegree_coding(props.outdegree_coding.try_into()?)
+            .set_out_offset_coding(props.offset_coding.try_into()?)
+            .set_out_reference_coding(props.reference_coding.try_into()?)
+            .set_out_interval_coding(props.interval_coding.try_into()?)
+            .set_out_residual_coding(props.residual_coding.try_into()?)
+            .set_elias_fano(params.elias_fano)
+            .set_container(params.container)
+            .set_stats(params.stats)
+            .set_threads(params.threads)
+            .set_sync_interval(params.sync_interval)
+            .build()
+            .store_graph(graph, dest_name),
+        GraphFormat::Huffman => bvgraph_huffman_out::DefaultBVGraphBuilder::new()
+            .set_out_min_interval_len(props.min_interval_len)
+            .set_out_max_ref_count(props.max_ref_count)
+            .set_out_window_size(props.window_size)
+            .set_out_zeta(props.zeta_k)
+            .set_elias_fano(params.elias_fano)
+            .set_container(params.container)
+            .set_stats(params.stats)
+            .set_threads(params.threads)
+            .set_sync_interval(params.sync_interval)
+            .build()
+            .store_graph(graph, dest_name),
+        GraphFormat::Zuckerli => zuckerli_out::DefaultBVGraphBuilder::new()
+            .set_out_min_interval_len(props.min_interval_len)
+            .set_out_max_ref_count(props.max_ref_count)
+            .set_out_window_size(props.window_size)
+            .set_out_zeta(props.zeta_k)
+            .set_elias_fano(params.elias_fano)
+            .set_container(params.container)
+            .set_stats(params.stats)
+            .set_threads(params.threads)
+            .set_sync_interval(params.sync_interval)
+            .build()
+            .store_graph(graph, dest_name),
+    }
+}
+
+fn convert(source: &SourceArgs, out_props: &Properties, params: &CompressionArgs, dest_name: &str) -> Result<()> {
+    let graph = GraphLoader::new().set_mmap(source.mmap).load_sequential(&source.source_name)?;
+
+    let comp_time = Instant::now();
+    store(&graph, out_props, params, dest_name)?;
+    let comp_time = comp_time.elapsed().as_nanos() as f64;
+    println!("compressed the graph in {}ns", comp_time);
+
+    if params.check {
+        check_compressed(&graph, &source.source_name, dest_name)?;
     }
-    let avg_query = (total.elapsed().as_nanos() as f64) / N_QUERIES as f64;
 
-    println!("time per query: {}ns", avg_query);
+    Ok(())
 }
 
-fn create_graph<
-    InBlockCoding: UniversalCode,
-    InBlockCountCoding: UniversalCode,
-    InOutDegreeCoding: UniversalCode,
-    InOffsetCoding: UniversalCode,
-    InReferenceCoding: UniversalCode,
-    InIntervalCoding: UniversalCode,
-    InResidualCoding: UniversalCode,
-    OutBlockCoding: UniversalCode,
-    OutBlockCountCoding: UniversalCode,
-    OutOutDegreeCoding: UniversalCode,
-    OutOffsetCoding: UniversalCode,
-    OutReferenceCoding: UniversalCode,
-    OutIntervalCoding: UniversalCode,
-    OutResidualCoding: UniversalCode,
->(props: &Properties, args: &WGArgs, plain_graph: Option<AsciiGraph<usize>>) {
-    if let Some(plain_graph) = plain_graph {
-        let bvgraph = BVGraphBuilder::<
-            InBlockCoding,
-            InBlockCountCoding,
-            InOutDegreeCoding,
-            InOffsetCoding,
-            InReferenceCoding,
-            InIntervalCoding,
-            InResidualCoding,
-            OutBlockCoding,
-            OutBlockCountCoding,
-            OutOutDegreeCoding,
-            OutOffsetCoding,
-            OutReferenceCoding,
-            OutIntervalCoding,
-            OutResidualCoding,
-        >::new()
-            .set_out_min_interval_len(args.min_interval_length)
-            .set_out_max_ref_count(args.max_ref_count)
-            .set_out_window_size(args.window_size)
-            .set_out_zeta(Some(args.zeta_k as u64))
-            .set_num_nodes(plain_graph.num_nodes())
-            .set_num_edges(plain_graph.num_arcs())
-            .build();
-
-        let comp_time = Instant::now();
-        bvgraph.store_plain(&plain_graph, args.dest_name.as_ref().unwrap().as_str()).expect("Failed storing the plain graph");
-        let comp_time = comp_time.elapsed().as_nanos() as f64;
-        println!("compressed the plain graph in {}ns", comp_time);
-    } else {
-        let mut bvgraph = BVGraphBuilder::<
-            InBlockCoding,
-            InBlockCountCoding,
-            InOutDegreeCoding,
-            InOffsetCoding,
-            InReferenceCoding,
-            InIntervalCoding,
-            InResidualCoding,
-            OutBlockCoding,
-            OutBlockCountCoding,
-            OutOutDegreeCoding,
-            OutOffsetCoding,
-            OutReferenceCoding,
-            OutIntervalCoding,
-            OutResidualCoding,
-        >::new()
-            .set_in_min_interval_len(props.min_interval_len)
-            .set_out_min_interval_len(args.min_interval_length)
-            .set_in_max_ref_count(props.max_ref_count)
-            .set_out_max_ref_count(args.max_ref_count)
-            .set_in_window_size(props.window_size)
-            .set_out_window_size(args.window_size)
-            .set_in_zeta(props.zeta_k)
-            .set_out_zeta(Some(args.zeta_k as u64))
-            .set_elias_fano(args.elias_fano)
-            .set_num_nodes(props.nodes)
-            .set_num_edges(props.arcs)
-            .load_graph(&args.source_name)
-            .load_offsets(&args.source_name)
-            .load_outdegrees()
-            .build();
-
-        if args.perf_test {
-            decompression_perf_test(&mut bvgraph);
-        } else if let Some(out_name) = args.dest_name.as_ref(){
-            let comp_time = Instant::now();
-            bvgraph.store(out_name.as_str()).expect("Failed storing the graph");
-            let comp_time = comp_time.elapsed().as_nanos() as f64;
-            println!("compressed the graph in {}ns", comp_time);
-
-            if args.check {
-                let compressed_graph = BVGraphBuilder::<
-                    InBlockCoding,
-                    InBlockCountCoding,
-                    InOutDegreeCoding,
-                    InOffsetCoding,
-                    InReferenceCoding,
-                    InIntervalCoding,
-                    InResidualCoding,
-                    OutBlockCoding,
-                    OutBlockCountCoding,
-                    OutOutDegreeCoding,
-                    OutOffsetCoding,
-                    OutReferenceCoding,
-                    OutIntervalCoding,
-                    OutResidualCoding,
-                >::new()
-                    .set_in_min_interval_len(args.min_interval_length)
-                    .set_in_max_ref_count(args.max_ref_count)
-                    .set_in_window_size(args.window_size)
-                    .set_in_zeta(props.zeta_k)
-                    .set_num_nodes(props.nodes)
-                    .set_num_edges(props.arcs)
-                    .load_graph(out_name.as_str())
-                    .load_offsets(out_name.as_str())
-                    .load_outdegrees()
-                    .build();
-                
-                assert_eq!(bvgraph.graph_memory, compressed_graph.graph_memory);
-                assert_eq!(bvgraph.offsets, compressed_graph.offsets);
-
-                println!("Check passed");
-            }
-        } else {
-            panic!("Neither an outname nor a performance test flag were provided");
+/// Checks that the graph compressed in `dest_name` has the same successor lists as the source `graph`,
+/// whatever their formats and parameters.
+fn check_compressed<G: SequentialGraph<NodeT = usize>>(graph: &G, source_name: &str, dest_name: &str) -> Result<()> {
+    let compressed = GraphLoader::new().load_sequential(dest_name)?;
+
+    if let Some(difference) = first_difference(graph, &compressed) {
+        return Err(Error::Malformed(format!("{} does not match {}: {}", dest_name, source_name, difference)));
+    }
+    println!("the compressed graph matches the source");
+
+    Ok(())
+}
+
+fn to_ascii<G: SequentialGraph<NodeT = usize>>(graph: &G, dest_name: &str) -> std::io::Result<()> {
+    let f = File::create(format!("{}.txt", dest_name))?;
+    let mut writer = BufWriter::new(f);
+
+    for (node, succ) in graph.iter_nodes() {
+        writeln!(writer, "{}\t{}", node, succ.iter().map(|x| x.to_string()).collect::<Vec<_>>().join("\t"))?;
+    }
+
+    writer.flush()
+}
+
+fn build_offsets(source: &SourceArgs, props: &Properties, elias_fano: bool) -> Result<()> {
+    if props.format != GraphFormat::BV {
+        return Err(Error::UnsupportedGraphClass(props.format.graph_class().to_owned()));
+    }
+
+    let graph = bv_builder(source, props)?.build();
+
+    let mut offsets = Vec::with_capacity(graph.num_nodes() + 1);
+
+    let mut iter = graph.iter();
+    let mut n = graph.num_nodes();
+
+    while n > 0 {
+        offsets.push(iter.ibs.read_bits);
+
+        iter.next();
+        iter.outdegree();
+        iter.successor_array();
+
+        n -= 1;
+    }
+    offsets.push(iter.ibs.read_bits);
+
+    // The offsets are written in a container if the graph is
+    let container = container::read_header(format!("{}.graph", source.source_name))?.map(|_| props);
+
+    if elias_fano {
+        let path = format!("{}.offsets.ef", source.source_name);
+        return store_elias_fano(&offsets, &path, container);
+    }
+
+    let path = format!("{}.offsets", source.source_name);
+    let offset_coding = Code::try_from(props.offset_coding)?;
+    let mut offsets_writer = BinaryWriter::with_sink(container::create(&path)?);
+    let mut last_offset = 0;
+
+    for offset in offsets {
+        offset_coding.write_next(&mut offsets_writer, (offset - last_offset) as u64, props.zeta_k);
+        last_offset = offset;
+    }
+
+    offsets_writer.finish().map_err(Error::io(&path))?;
+    container::seal(&path, FileKind::Offsets, container)
+}
+
+fn stats(source: &SourceArgs, props: &Properties) -> Result<()> {
+    let file_bits = |extension: &str| {
+        let path = format!("{}.{}", source.source_name, extension);
+        fs::metadata(&path).map(|m| m.len() * 8).map_err(Error::io(&path))
+    };
+
+    let graph_stats = CompressionStats {
+        nodes: props.nodes,
+        arcs: props.arcs,
+        graph_bits: file_bits("graph")?,
+        ..Default::default()
+    };
+
+    println!("format: {:?}", props.format);
+    println!("nodes: {}", props.nodes);
+    println!("arcs: {}", props.arcs);
+    println!("graph bits: {}", graph_stats.graph_bits);
+    println!("bits per link: {}", graph_stats.bits_per_link());
+    println!("bits per node: {}", graph_stats.bits_per_node());
+
+    if let Ok(offsets_bits) = file_bits("offsets.ef").or_else(|_| file_bits("offsets")) {
+        let offsets_stats = CompressionStats { graph_bits: offsets_bits, ..graph_stats };
+        println!("offsets bits: {}", offsets_bits);
+        println!("offsets bits per node: {}", offsets_stats.bits_per_node());
+    }
+
+    Ok(())
+}
+
+fn check(graph: &dyn CheckedGraph, props: &Properties) -> Result<()> {
+    let mut nodes = 0;
+    let mut arcs = 0;
+
+    for item in graph.try_iter_nodes() {
+        let (node, successors) = item?;
+        let mismatch = |what: String| Err(Error::Malformed(format!("node {}: {}", node, what)));
+
+        if node != nodes {
+            return mismatch("out of order".to_string());
+        }
+        if successors != graph.try_successors(node)? {
+            return mismatch("sequential and random access disagree on its successors".to_string());
         }
+        if graph.outdegree(node) != Some(successors.len()) {
+            return mismatch(format!("wrong outdegree {:?} instead of {}", graph.outdegree(node), successors.len()));
+        }
+
+        nodes += 1;
+        arcs += successors.len();
+    }
+
+    if nodes != props.nodes || arcs != props.arcs {
+        return Err(Error::Malformed(format!("the graph has {} nodes and {} arcs instead of {} and {}", nodes, arcs, props.nodes, props.arcs)));
     }
+
+    println!("Check passed");
+
+    Ok(())
 }
 
-fn main() {
-    let args = WGArgs::parse();
+fn bench(source_names: &[String], mmap: bool, params: &BenchArgs) -> Result<()> {
+    let loader = GraphLoader::new().set_mmap(mmap);
+    let mut reports = Vec::with_capacity(source_names.len());
 
-    if args.perf_test && args.check {
-        panic!("Both performance test and compression test flags were provided");
+    for source_name in source_names {
+        let distribution = match (&params.replay, params.distribution) {
+            (Some(path), _) => QueryDistribution::replay(path, Properties::load(source_name)?.nodes)?,
+            (None, Distribution::Uniform) => QueryDistribution::Uniform,
+            (None, Distribution::Degree) => QueryDistribution::Degree,
+        };
+
+        let report = Benchmark::new()
+            .set_seed(params.seed)
+            .set_queries(params.queries)
+            .set_warmup(params.warmup)
+            .set_distribution(distribution)
+            .set_sequential(params.sequential)
+            .run(source_name, &loader)?;
+
+        if let ReportFormat::Csv = params.format {
+            if reports.is_empty() {
+                println!("{}", BenchmarkReport::CSV_HEADER);
+            }
+            println!("{}", report.to_csv());
+        }
+        reports.push(report);
     }
 
-    if args.check && args.dest_name.is_none() {
-        panic!("Cannot execute a correctness check without compressing to file");
+    if let ReportFormat::Json = params.format {
+        println!("{}", serde_json::to_string_pretty(&reports).unwrap());
     }
 
-    if args.perf_test && args.dest_name.is_some() {
-        println!("Warning: both destination name and performance test flag provided, nothing will be written");
+    Ok(())
+}
+
+/// Returns the property holding the code of a section.
+type CodeProperty = fn(&mut Properties) -> &mut EncodingType;
+
+/// The sections whose codes are swept by the experiments, each with the property holding its code.
+const SWEPT_SECTIONS: [(&str, CodeProperty); 4] = [
+    ("outdegrees", |props| &mut props.outdegree_coding),
+    ("blocks", |props| &mut props.block_coding),
+    ("intervals", |props| &mut props.interval_coding),
+    ("residuals", |props| &mut props.residual_coding),
+];
+
+/// The codes tried for each swept section.
+const SWEPT_CODES: [EncodingType; 3] = [EncodingType::GAMMA, EncodingType::DELTA, EncodingType::ZETA];
+
+/// Compresses each graph once for each code of each swept section, keeping the default codes of the other
+/// sections, and once in the Huffman and Zuckerli formats, writing to `sizes.csv` the bits of the sections of
+/// each compressed graph and to `times.csv` its access times.
+fn experiments(source_names: &[String], out_dir: &str, queries: usize, seed: u64, keep: bool) -> Result<()> {
+    fs::create_dir_all(out_dir).map_err(Error::io(out_dir))?;
+    let sizes_path = format!("{}/sizes.csv", out_dir);
+    let times_path = format!("{}/times.csv", out_dir);
+    let mut sizes = BufWriter::new(File::create(&sizes_path).map_err(Error::io(&sizes_path))?);
+    let mut times = BufWriter::new(File::create(&times_path).map_err(Error::io(&times_path))?);
+
+    writeln!(sizes, "graph,format,section,code,graph_bits,bits_per_link,header_bits,outdegree_bits,reference_bits,block_bits,interval_bits,residual_bits")
+        .map_err(Error::io(&sizes_path))?;
+    writeln!(times, "graph,format,section,code,scan_ns_per_arc,random_ns_per_arc,mean_ns,p50_ns,p99_ns,bits_per_query,avg_chain_depth")
+        .map_err(Error::io(&times_path))?;
+
+    let params = CompressionArgs { stats: true, ..Default::default() };
+    let benchmark = Benchmark::new().set_seed(seed).set_queries(queries).set_sequential(true);
+
+    for source_name in source_names {
+        let graph = GraphLoader::new().load_sequential(source_name)?;
+        let name = Path::new(source_name).file_name().map_or(source_name.clone(), |name| name.to_string_lossy().into_owned());
+        let defaults = Properties { nodes: graph.num_nodes(), arcs: graph.num_arcs(), ..Default::default() };
+
+        let mut variants = Vec::new();
+        for (section, coding) in SWEPT_SECTIONS {
+            for code in SWEPT_CODES {
+                let mut props = defaults.clone();
+                *coding(&mut props) = code;
+                variants.push((section, code.to_string(), props));
+            }
+        }
+        for format in [GraphFormat::Huffman, GraphFormat::Zuckerli] {
+            variants.push(("all", EncodingType::HUFFMAN.to_string(), Properties { format, ..defaults.clone() }));
+        }
+
+        for (section, code, props) in variants {
+            let dest_name = format!("{}/{}-{:?}-{}-{}", out_dir, name, props.format, section, code).to_lowercase();
+            store(&graph, &props, &params, &dest_name)?;
+
+            let stats = CompressionStats::load(format!("{}.stats", dest_name))?;
+            let report = benchmark.run(&dest_name, &GraphLoader::new())?;
+            println!("{}: {} bits per link, {}ns per random query", dest_name, stats.bits_per_link(), report.mean_ns);
+
+            let row = format!("{},{:?},{},{}", name, props.format, section, code);
+            writeln!(sizes, "{},{},{},{},{},{},{},{},{}", row, stats.graph_bits, stats.bits_per_link(), stats.header_bits,
+                stats.outdegree_bits, stats.reference_bits, stats.block_bits, stats.interval_bits, stats.residual_bits)
+                .map_err(Error::io(&sizes_path))?;
+            // The times per arc are left empty for graphs without arcs
+            let scan_ns_per_arc = report.scan_ns_per_arc.map_or(String::new(), |ns| ns.to_string());
+            let random_ns_per_arc = if report.avg_outdegree > 0.0 { (report.mean_ns / report.avg_outdegree).to_string() } else { String::new() };
+            writeln!(times, "{},{},{},{},{},{},{},{}", row, scan_ns_per_arc, random_ns_per_arc,
+                report.mean_ns, report.p50_ns, report.p99_ns, report.bits_per_query, report.avg_chain_depth)
+                .map_err(Error::io(&times_path))?;
+
+            if !keep {
+                for extension in ["graph", "offsets", "properties", "stats"] {
+                    let path = format!("{}.{}", dest_name, extension);
+                    fs::remove_file(&path).map_err(Error::io(&path))?;
+                }
+            }
+        }
     }
 
-    if args.from_plain && args.dest_name.is_none() {
-        panic!("No destination name provided.");
+    sizes.flush().map_err(Error::io(&sizes_path))?;
+    times.flush().map_err(Error::io(&times_path))
+}
+
+fn run(command: Command) -> Result<()> {
+    match command {
+        Command::Compress { source, dest_name, format, params } => {
+            let props = Properties::load(&source.source_name)?;
+            let defaults = Properties { nodes: props.nodes, arcs: props.arcs, ..Default::default() };
+            convert(&source, &params.resolve(&defaults, format)?, &params, &dest_name)
+        },
+        Command::Recompress { source, dest_name, params } => {
+            let props = Properties::load(&source.source_name)?;
+            convert(&source, &params.resolve(&props, props.format)?, &params, &dest_name)
+        },
+        Command::Transcode { source, dest_name, format, params } => {
+            let props = Properties::load(&source.source_name)?;
+            convert(&source, &params.resolve(&props, format)?, &params, &dest_name)
+        },
+        Command::ToAscii { source, dest_name } => {
+            let graph = GraphLoader::new().set_mmap(source.mmap).load_sequential(&source.source_name)?;
+            to_ascii(&graph, &dest_name).map_err(Error::io(format!("{}.txt", dest_name)))
+        },
+        Command::FromAscii { source_name, dest_name, format, params } => {
+            let props = params.resolve(&Properties::default(), format)?;
+            let plain_graph = AsciiGraphBuilder::new()
+                .load_ascii(&source_name)?
+                .build();
+
+            let comp_time = Instant::now();
+            store(&plain_graph, &props, &params, &dest_name)?;
+            let comp_time = comp_time.elapsed().as_nanos() as f64;
+            println!("compressed the plain graph in {}ns", comp_time);
+
+            if params.check {
+                check_compressed(&plain_graph, &source_name, &dest_name)?;
+            }
+
+            Ok(())
+        },
+        Command::BuildOffsets { source, elias_fano } => {
+            let props = Properties::load(&source.source_name)?;
+            build_offsets(&source, &props, elias_fano)
+        },
+        Command::Stats { source } => {
+            let props = Properties::load(&source.source_name)?;
+            stats(&source, &props)
+        },
+        Command::Check { source } => {
+            let props = Properties::load(&source.source_name)?;
+            let graph = GraphLoader::new().set_mmap(source.mmap).load_checked(&source.source_name)?;
+            check(&*graph, &props)
+        },
+        Command::Bench { source_names, mmap, params } => bench(&source_names, mmap, &params),
+        Command::Experiments { source_names, out_dir, queries, seed, keep } => experiments(&source_names, &out_dir, queries, seed, keep),
     }
+}
 
-    let mut plain_graph = None;
-    let mut props = Properties::default();
-    
-    if !args.from_plain {
-        let properties_file = File::open(format!("{}.properties", args.source_name));
-        let properties_file = properties_file.unwrap_or_else(|_| panic!("Could not find {}.properties", args.source_name));
-        let p = java_properties::read(BufReader::new(properties_file)).unwrap_or_else(|_| panic!("Failed parsing the properties file"));
-
-        props = Properties::from(p);
-    } else {
-        plain_graph = Some(AsciiGraphBuilder::new()
-                        .load_ascii(&args.source_name)
-                        .build());
+fn main() {
+    if let Err(e) = run(Cli::parse().command) {
+        eprintln!("error: {}", e);
+        process::exit(1);
     }
-    
-    match (props.block_coding, props.block_count_coding, props.outdegree_coding, props.offset_coding, props.reference_coding, props.interval_coding, props.residual_coding, 
-        args.block_coding, args.block_count_coding, args.outdegree_coding, args.offset_coding, args.reference_coding, args.interval_coding, args.residual_coding) {
-    (EncodingType::GAMMA, EncodingType::GAMMA, EncodingType::GAMMA, EncodingType::GAMMA, EncodingType::UNARY, EncodingType::GAMMA, EncodingType::ZETA, // Default case
-    EncodingType::GAMMA, EncodingType::GAMMA, EncodingType::GAMMA, EncodingType::GAMMA, EncodingType::UNARY, EncodingType::GAMMA, EncodingType::ZETA) => 
-        create_graph::<GammaCode, GammaCode, GammaCode, GammaCode, UnaryCode, GammaCode, ZetaCode, GammaCode, GammaCode, GammaCode, GammaCode, UnaryCode, GammaCode, ZetaCode>
-        (&props, &args, plain_graph),
-    (EncodingType::GAMMA, EncodingType::GAMMA, EncodingType::GAMMA, EncodingType::GAMMA, EncodingType::UNARY, EncodingType::GAMMA, EncodingType::ZETA, // Default to gamma
-    EncodingType::GAMMA, EncodingType::GAMMA, EncodingType::GAMMA, EncodingType::GAMMA, EncodingType::GAMMA, EncodingType::GAMMA, EncodingType::GAMMA) => 
-        create_graph::<GammaCode, GammaCode, GammaCode, GammaCode, UnaryCode, GammaCode, ZetaCode, GammaCode, GammaCode, GammaCode, GammaCode, GammaCode, GammaCode, GammaCode>
-        (&props, &args, plain_graph),
-    (EncodingType::GAMMA, EncodingType::GAMMA, EncodingType::GAMMA, EncodingType::GAMMA, EncodingType::UNARY, EncodingType::GAMMA, EncodingType::ZETA, // Default to delta
-    EncodingType::DELTA, EncodingType::DELTA, EncodingType::DELTA, EncodingType::DELTA, EncodingType::DELTA, EncodingType::DELTA, EncodingType::DELTA) => 
-        create_graph::<GammaCode, GammaCode, GammaCode, GammaCode, UnaryCode, GammaCode, ZetaCode, DeltaCode, DeltaCode, DeltaCode, DeltaCode, DeltaCode, DeltaCode, DeltaCode>
-        (&props, &args, plain_graph),
-    (EncodingType::GAMMA, EncodingType::GAMMA, EncodingType::GAMMA, EncodingType::GAMMA, EncodingType::UNARY, EncodingType::GAMMA, EncodingType::ZETA, // Default to zeta
-    EncodingType::ZETA, EncodingType::ZETA, EncodingType::ZETA, EncodingType::ZETA, EncodingType::ZETA, EncodingType::ZETA, EncodingType::ZETA) => 
-        create_graph::<GammaCode, GammaCode, GammaCode, GammaCode, UnaryCode, GammaCode, ZetaCode, ZetaCode, ZetaCode, ZetaCode, ZetaCode, ZetaCode, ZetaCode, ZetaCode>
-        (&props, &args, plain_graph),
-    _ => panic!("Unexpected encoding types", )
 }
-}
\ No newline at end of file
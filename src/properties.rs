@@ -1,9 +1,47 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fs::File, io::BufReader, str::FromStr};
 
-use crate::utils::EncodingType;
+use clap::ValueEnum;
 
-#[derive(Debug)]
+use crate::{utils::EncodingType, Error, Result};
+
+/// The representation of a compressed graph, as recorded by the `graphclass` property.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, ValueEnum)]
+pub enum GraphFormat {
+    /// WebGraph's representation, each section being encoded by an instantaneous code
+    BV,
+    /// WebGraph's representation with Huffman-coded sections (see `bvgraph_huffman_out`)
+    Huffman,
+    /// Zuckerli's representation (see `zuckerli_out`)
+    Zuckerli,
+}
+
+impl GraphFormat {
+    /// Returns the value of the `graphclass` property for this format.
+    pub fn graph_class(&self) -> &'static str {
+        match self {
+            GraphFormat::BV => "it.unimi.dsi.webgraph.BVGraph",
+            GraphFormat::Huffman => "webgraph_rust.HuffmanBVGraph",
+            GraphFormat::Zuckerli => "webgraph_rust.ZuckerliGraph",
+        }
+    }
+}
+
+impl FromStr for GraphFormat {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim() {
+            "it.unimi.dsi.webgraph.BVGraph" | "it.unimi.dsi.webgraph.ImmutableGraph" => Ok(GraphFormat::BV),
+            "webgraph_rust.HuffmanBVGraph" => Ok(GraphFormat::Huffman),
+            "webgraph_rust.ZuckerliGraph" => Ok(GraphFormat::Zuckerli),
+            class => Err(Error::UnsupportedGraphClass(class.to_owned()))
+        }
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
 pub struct Properties {
+    pub format: GraphFormat,
     pub nodes: usize,
     pub arcs: usize,
     pub window_size: usize,
@@ -22,6 +60,7 @@ pub struct Properties {
 impl Default for Properties {
     fn default() -> Self {
         Self { 
+            format: GraphFormat::BV,
             nodes: 0, 
             arcs: 0, 
             window_size: 7, 
@@ -39,45 +78,123 @@ impl Default for Properties {
     }
 }
 
-impl From<HashMap<String, String>> for Properties {
-    fn from(value: HashMap<String, String>) -> Self {
+impl Properties {
+    /// Reads the properties of a graph from its `.properties` file.
+    ///
+    /// # Arguments
+    ///
+    /// * `basename` - The basename of the graph
+    pub fn load(basename: &str) -> Result<Self> {
+        let path = format!("{}.properties", basename);
+        let properties_file = File::open(&path).map_err(Error::io(&path))?;
+        let p = java_properties::read(BufReader::new(properties_file))
+            .map_err(|e| Error::Malformed(format!("could not parse {}: {}", path, e)))?;
+
+        Properties::try_from(p)
+    }
+
+    /// Checks that the codes of the sections are the ones that graphs of this format can be read with.
+    fn check_codes(&self) -> Result<()> {
+        let codes = [
+            ("OUTDEGREES", self.outdegree_coding, EncodingType::HUFFMAN),
+            ("REFERENCES", self.reference_coding, EncodingType::UNARY),
+            ("BLOCKS", self.block_coding, EncodingType::HUFFMAN),
+            ("BLOCK_COUNT", self.block_count_coding, EncodingType::GAMMA),
+            ("INTERVALS", self.interval_coding, EncodingType::HUFFMAN),
+            ("RESIDUALS", self.residual_coding, EncodingType::HUFFMAN),
+            ("OFFSETS", self.offset_coding, EncodingType::GAMMA),
+        ];
+
+        for (section, code, huffman_code) in codes {
+            let supported = match self.format {
+                GraphFormat::BV => code != EncodingType::HUFFMAN,
+                GraphFormat::Huffman | GraphFormat::Zuckerli => code == huffman_code,
+            };
+
+            if !supported {
+                return Err(Error::UnsupportedCompressionFlag(format!("{}_{} for {:?} graphs", section, code, self.format)));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses the value of the given key, if present.
+pub(crate) fn parse_property<T: FromStr>(value: &HashMap<String, String>, key: &str) -> Result<Option<T>> {
+    value.get(key)
+        .map(|v| v.trim().parse().map_err(|_| Error::InvalidProperty { key: key.to_owned(), value: v.to_owned() }))
+        .transpose()
+}
+
+/// Parses the value of the given key, which has to be present.
+pub(crate) fn parse_required_property<T: FromStr>(value: &HashMap<String, String>, key: &str) -> Result<T> {
+    parse_property(value, key)?.ok_or_else(|| Error::MissingProperty(key.to_owned()))
+}
+
+impl TryFrom<HashMap<String, String>> for Properties {
+    type Error = Error;
+
+    fn try_from(value: HashMap<String, String>) -> Result<Self> {
         let mut props = Properties {
-            nodes: value.get("nodes").expect("Failed in reading nodes from properties").parse().unwrap(),
-            arcs: value.get("arcs").expect("Failed in reading arcs from properties").parse().unwrap(),
-            window_size: value.get("windowsize").expect("Failed in reading window size from properties").parse().unwrap(),
-            max_ref_count: value.get("maxrefcount").expect("Failed in reading maximum reference count from properties").parse().unwrap(),
-            min_interval_len: value.get("minintervallength").expect("Failed in reading minimum interval length from properties").parse().unwrap(),
+            nodes: parse_required_property(&value, "nodes")?,
+            arcs: parse_required_property(&value, "arcs")?,
+            window_size: parse_required_property(&value, "windowsize")?,
+            max_ref_count: parse_required_property(&value, "maxrefcount")?,
+            min_interval_len: parse_required_property(&value, "minintervallength")?,
             ..Default::default()
         };
 
-        if let Some(zeta_k) = value.get("zetak") {
-            props.zeta_k = Some(zeta_k.parse().unwrap())
+        if let Some(graph_class) = value.get("graphclass") {
+            props.format = graph_class.parse()?;
+        }
+
+        if let Some(zeta_k) = parse_property(&value, "zetak")? {
+            props.zeta_k = Some(zeta_k)
         }
 
         if let Some(compression_flags) = value.get("compressionflags") {
             if !compression_flags.is_empty() {
                 for flag in compression_flags.split('|') {
                     let s: Vec<_> = flag.split('_').collect();
+                    let unsupported = || Error::UnsupportedCompressionFlag(flag.trim().to_owned());
 
-                    // if s.last().unwrap().trim().to_uppercase() == "HUFFMAN" {
-                    //     continue;
-                    // }
-
-                    match s[0].trim().to_uppercase().as_str() {
-                        "OUTDEGREES" => props.outdegree_coding = EncodingType::from(s[1]),
-                        "REFERENCES" => props.reference_coding = EncodingType::from(s[1]),
-                        "BLOCKS" => props.block_coding = EncodingType::from(s[1]),
-                        "INTERVALS" => props.interval_coding = EncodingType::from(s[1]),
-                        "RESIDUALS" => props.residual_coding = EncodingType::from(s[1]),
-                        "OFFSETS" => props.offset_coding = EncodingType::from(s[1]),
-                        "BLOCK" => props.block_count_coding = EncodingType::from(s[2]),
-                        _ => panic!("Unkown compression flag {}", s[0]),
+                    let (section, encoding) = match s[0].trim().to_uppercase().as_str() {
+                        "OUTDEGREES" => (&mut props.outdegree_coding, s.get(1)),
+                        "REFERENCES" => (&mut props.reference_coding, s.get(1)),
+                        "BLOCKS" => (&mut props.block_coding, s.get(1)),
+                        "INTERVALS" => (&mut props.interval_coding, s.get(1)),
+                        "RESIDUALS" => (&mut props.residual_coding, s.get(1)),
+                        "OFFSETS" => (&mut props.offset_coding, s.get(1)),
+                        "BLOCK" => (&mut props.block_count_coding, s.get(2)),
+                        _ => return Err(unsupported()),
                     };
+
+                    *section = encoding.ok_or_else(unsupported)?.parse()?;
                 }
             }
         }
 
-        props
+        // Huffman-coded graphs used to be written claiming to be BVGraphs (Zuckerli ones cannot be told
+        // apart from them, and their graphclass has to be fixed by hand)
+        if props.format == GraphFormat::BV && props.outdegree_coding == EncodingType::HUFFMAN {
+            props.format = GraphFormat::Huffman;
+        }
+
+        props.check_codes()?;
+
+        Ok(props)
+    }
+}
+
+impl FromStr for Properties {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let p = java_properties::read(value.as_bytes())
+            .map_err(|e| Error::Malformed(format!("could not parse the properties: {}", e)))?;
+
+        Properties::try_from(p)
     }
 }
 
@@ -87,13 +204,13 @@ impl From<Properties> for String {
 
         s.push_str("#BVGraph properties\n");
         s.push_str("version=0\n");
-        s.push_str("graphclass=it.unimi.dsi.webgraph.BVGraph\n");
+        s.push_str(&format!("graphclass={}\n", val.format.graph_class()));
         s.push_str(&format!("nodes={}\n", val.nodes));
         s.push_str(&format!("arcs={}\n", val.arcs));
         s.push_str(&format!("minintervallength={}\n", val.min_interval_len));
         s.push_str(&format!("maxrefcount={}\n", val.max_ref_count));
         s.push_str(&format!("windowsize={}\n", val.window_size));
-        s.push_str(&format!("zetak={}\n", if val.zeta_k.is_some() {val.zeta_k.unwrap()} else {3}));
+        s.push_str(&format!("zetak={}\n", val.zeta_k.unwrap_or(3)));
         s.push_str("compressionflags=");
 
         let mut cflags = false;
@@ -0,0 +1,102 @@
+use std::{env, fs, process};
+
+use crate::ascii_graph::{AsciiGraph, AsciiGraphBuilder};
+
+use super::*;
+
+/// Writes the given successor lists as an ASCII graph and loads it.
+fn ascii_graph(name: &str, lists: &[&[usize]]) -> AsciiGraph<usize> {
+    let path = env::temp_dir().join(format!("webgraph_test_{}_{}.txt", process::id(), name));
+    let ascii: String = lists
+        .iter()
+        .enumerate()
+        .map(|(x, list)| format!("{}\t{}\n", x, list.iter().map(|y| y.to_string()).collect::<Vec<_>>().join("\t")))
+        .collect();
+    fs::write(&path, ascii).unwrap();
+
+    let graph = AsciiGraphBuilder::new().load_ascii(path.to_str().unwrap()).unwrap().build();
+    fs::remove_file(path).unwrap();
+
+    graph
+}
+
+/// A graph declaring a number of nodes that may differ from the number of its lists.
+struct Lists {
+    num_nodes: usize,
+    lists: Vec<Vec<usize>>,
+}
+
+impl SequentialGraph for Lists {
+    type NodeT = usize;
+
+    fn num_nodes(&self) -> usize {
+        self.num_nodes
+    }
+
+    fn num_arcs(&self) -> usize {
+        self.lists.iter().map(Vec::len).sum()
+    }
+
+    fn iter_nodes(&self) -> impl Iterator<Item = (usize, Box<[usize]>)> + '_ {
+        self.lists.iter().enumerate().map(|(x, list)| (x, list.as_slice().into()))
+    }
+}
+
+#[test]
+fn test_first_difference_equal() {
+    let first = ascii_graph("equal_first", &[&[1, 2], &[], &[0, 1, 2]]);
+    let second = ascii_graph("equal_second", &[&[1, 2], &[], &[0, 1, 2]]);
+
+    assert_eq!(first_difference(&first, &second), None);
+    assert_eq!(first_difference(&first, &first), None);
+
+    let empty = ascii_graph("equal_empty", &[]);
+    assert_eq!(first_difference(&empty, &empty), None);
+}
+
+#[test]
+fn test_first_difference_outdegree() {
+    let first = ascii_graph("outdegree_first", &[&[1, 2], &[0], &[0, 1]]);
+    let second = ascii_graph("outdegree_second", &[&[1, 2], &[0, 2], &[0]]);
+
+    let difference = first_difference(&first, &second).unwrap();
+    assert_eq!(difference, Difference::Successors { node: 1, first: vec![0].into(), second: vec![0, 2].into() });
+    assert_eq!(difference.to_string(), "node 1 has outdegree 2 and successors [0, 2] instead of outdegree 1 and successors [0]");
+}
+
+#[test]
+fn test_first_difference_successors() {
+    let first = ascii_graph("successors_first", &[&[1, 2], &[0], &[0, 1]]);
+    let second = ascii_graph("successors_second", &[&[1, 2], &[0], &[1, 2]]);
+
+    let difference = first_difference(&first, &second).unwrap();
+    assert_eq!(difference, Difference::Successors { node: 2, first: vec![0, 1].into(), second: vec![1, 2].into() });
+    assert_eq!(difference.to_string(), "node 2 has outdegree 2 and successors [1, 2] instead of outdegree 2 and successors [0, 1]");
+
+    // The first graph is the reference
+    assert_eq!(
+        first_difference(&second, &first),
+        Some(Difference::Successors { node: 2, first: vec![1, 2].into(), second: vec![0, 1].into() })
+    );
+}
+
+#[test]
+fn test_first_difference_num_nodes() {
+    let first = ascii_graph("num_nodes_first", &[&[1], &[0]]);
+    let second = ascii_graph("num_nodes_second", &[&[1], &[0], &[]]);
+
+    let difference = first_difference(&first, &second).unwrap();
+    assert_eq!(difference, Difference::NumNodes(2, 3));
+    assert_eq!(difference.to_string(), "3 nodes instead of 2");
+    assert_eq!(first_difference(&second, &first), Some(Difference::NumNodes(3, 2)));
+}
+
+#[test]
+fn test_first_difference_truncated() {
+    let first = Lists { num_nodes: 3, lists: vec![vec![1], vec![0], vec![]] };
+    // Declares three nodes, but has only two lists
+    let second = Lists { num_nodes: 3, lists: vec![vec![1], vec![0]] };
+
+    assert_eq!(first_difference(&first, &second), Some(Difference::NumNodes(3, 2)));
+    assert_eq!(first_difference(&second, &first), Some(Difference::NumNodes(2, 3)));
+}
@@ -3,9 +3,12 @@ use crate::bitstreams::{
     BinaryReader, BinaryWriter,
 };
 use crate::utils::EncodingType;
+use crate::{Error, Result};
 
 pub trait UniversalCode {
     fn read_next(reader: &mut BinaryReader, zk: Option<u64>) -> u64;
+    /// Reads the next value like [`Self::read_next()`], returning an error instead of reading past the end of the stream.
+    fn try_read_next(reader: &mut BinaryReader, zk: Option<u64>) -> Result<u64>;
     fn write_next(writer: &mut BinaryWriter, x: u64, zk: Option<u64>) -> u64;
     fn to_encoding_type() -> EncodingType;
 }
@@ -15,65 +18,58 @@ pub struct UnaryCode;
 impl UniversalCode for UnaryCode {
     #[inline(always)]
     fn read_next(reader: &mut BinaryReader, _zk: Option<u64>) -> u64 {
-        debug_assert!(reader.fill < 64);
-
         if reader.fill < 16 {
             reader.refill();
         }
 
-        let mut x = u32::leading_zeros((reader.current as u32) << (32 - reader.fill));
-        if x < reader.fill as u32 {
-            reader.read_bits += x as usize + 1;
-            reader.fill -= x as usize + 1;
-            return x as u64;
-        }
-
-        x = reader.fill as u32;
-        let mut read = reader.read();
-
-        if read.is_ok() {
-            reader.current = read.unwrap();
-            while reader.current == 0 && read.is_ok() {
-                x += 8;
-                read = reader.read();
-                if let Ok(r) = read {
-                    reader.current = r;
-                }
+        let mut x = 0;
+        // The zeros in the buffer are skipped a word at a time, until the buffer holds the final one
+        while reader.fill != 0 {
+            let zeros = (reader.current << (64 - reader.fill)).leading_zeros() as usize;
+            if zeros < reader.fill {
+                reader.read_bits += zeros + 1;
+                reader.fill -= zeros + 1;
+                return (x + zeros) as u64;
             }
+
+            x += reader.fill;
+            reader.read_bits += reader.fill;
+            reader.fill = 0;
+            reader.refill();
         }
 
-        reader.fill = (63 - u64::leading_zeros(reader.current)) as usize;
-        x += 7 - reader.fill as u32;
-        reader.read_bits += x as usize + 1;
         x as u64
     }
 
-    #[inline(always)]
-    fn write_next(writer: &mut BinaryWriter, x: u64, _zk: Option<u64>) -> u64 {
-        if x < writer.free as u64 {
-            return writer.write_in_current(1, x + 1);
-        }
-
-        let shift = writer.free;
-        let x = x - shift as u64;
-
-        writer.written_bits += shift;
-        writer.write(writer.current);
-        writer.free = 8;
-        writer.current = 0;
+    fn try_read_next(reader: &mut BinaryReader, _zk: Option<u64>) -> Result<u64> {
+        let mut x = 0;
+        while reader.fill != 0 || reader.refill() != 0 {
+            let zeros = (reader.current << (64 - reader.fill)).leading_zeros() as usize;
+            if zeros < reader.fill {
+                reader.read_bits += zeros + 1;
+                reader.fill -= zeros + 1;
+                return Ok((x + zeros) as u64);
+            }
 
-        let mut i = x >> 3;
+            x += reader.fill;
+            reader.read_bits += reader.fill;
+            reader.fill = 0;
+        }
 
-        writer.written_bits += x as usize & 0x7FFFFFF8;
+        Err(Error::UnexpectedEof)
+    }
 
-        while i != 0 {
-            writer.write(0);
-            i -= 1;
+    #[inline(always)]
+    fn write_next(writer: &mut BinaryWriter, x: u64, _zk: Option<u64>) -> u64 {
+        let mut zeros = x;
+        while zeros >= 64 {
+            writer.push_bits(0, 64);
+            zeros -= 64;
         }
 
-        writer.write_in_current(1, (x & 7) + 1);
+        writer.push_bits(1, zeros + 1);
 
-        x + shift as u64 + 1
+        x + 1
     }
 
     #[inline(always)]
@@ -102,6 +98,15 @@ impl UniversalCode for GammaCode {
         ((1 << msb) | reader.read_int(msb)) - 1
     }
 
+    fn try_read_next(reader: &mut BinaryReader, _zk: Option<u64>) -> Result<u64> {
+        let msb = UnaryCode::try_read_next(reader, None)?;
+        if msb >= 64 {
+            return Err(Error::Malformed(format!("gamma code with {} leading zeros", msb)));
+        }
+
+        Ok(((1 << msb) | reader.try_read_int(msb)?) - 1)
+    }
+
     #[inline(always)]
     fn write_next(writer: &mut BinaryWriter, x: u64, _zk: Option<u64>) -> u64 {
         debug_assert!(x < u64::MAX);
@@ -138,6 +143,15 @@ impl UniversalCode for DeltaCode {
         ((1 << msb) | reader.read_int(msb)) - 1
     }
 
+    fn try_read_next(reader: &mut BinaryReader, _zk: Option<u64>) -> Result<u64> {
+        let msb = GammaCode::try_read_next(reader, None)?;
+        if msb >= 64 {
+            return Err(Error::Malformed(format!("delta code with a {}-bit value", msb + 1)));
+        }
+
+        Ok(((1 << msb) | reader.try_read_int(msb)?) - 1)
+    }
+
     #[inline(always)]
     fn write_next(writer: &mut BinaryWriter, x: u64, _zk: Option<u64>) -> u64 {
         debug_assert!(x < u64::MAX);
@@ -183,6 +197,27 @@ impl UniversalCode for ZetaCode {
         }
     }
 
+    fn try_read_next(reader: &mut BinaryReader, zk: Option<u64>) -> Result<u64> {
+        let zk = zk.ok_or_else(|| Error::MissingProperty("zetak".to_owned()))?;
+
+        if zk == 0 {
+            return Err(Error::InvalidProperty { key: "zetak".to_owned(), value: zk.to_string() });
+        }
+
+        let unary = UnaryCode::try_read_next(reader, None)?;
+        if unary.saturating_mul(zk).saturating_add(zk) >= 64 {
+            return Err(Error::Malformed(format!("zeta code with {} leading zeros", unary)));
+        }
+
+        let left = 1 << (unary * zk);
+        let m = reader.try_read_int(unary * zk + zk - 1)?;
+        if m < left {
+            Ok(m + left - 1)
+        } else {
+            Ok((m << 1) + reader.try_read_int(1)? - 1)
+        }
+    }
+
     #[inline(always)]
     fn write_next(writer: &mut BinaryWriter, x: u64, zk: Option<u64>) -> u64 {
         let zk = zk.unwrap();
@@ -208,6 +243,74 @@ impl UniversalCode for ZetaCode {
     }
 }
 
+/// A universal code chosen at runtime.
+///
+/// Each variant dispatches to the corresponding [`UniversalCode`] implementation, so that
+/// the codes of the different sections of a graph can be taken from its properties
+/// instead of being fixed at compile time.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Code {
+    Unary,
+    Gamma,
+    Delta,
+    Zeta,
+}
+
+impl Code {
+    #[inline(always)]
+    pub fn read_next(&self, reader: &mut BinaryReader, zk: Option<u64>) -> u64 {
+        match self {
+            Code::Unary => UnaryCode::read_next(reader, zk),
+            Code::Gamma => GammaCode::read_next(reader, zk),
+            Code::Delta => DeltaCode::read_next(reader, zk),
+            Code::Zeta => ZetaCode::read_next(reader, zk),
+        }
+    }
+
+    pub fn try_read_next(&self, reader: &mut BinaryReader, zk: Option<u64>) -> Result<u64> {
+        match self {
+            Code::Unary => UnaryCode::try_read_next(reader, zk),
+            Code::Gamma => GammaCode::try_read_next(reader, zk),
+            Code::Delta => DeltaCode::try_read_next(reader, zk),
+            Code::Zeta => ZetaCode::try_read_next(reader, zk),
+        }
+    }
+
+    #[inline(always)]
+    pub fn write_next(&self, writer: &mut BinaryWriter, x: u64, zk: Option<u64>) -> u64 {
+        match self {
+            Code::Unary => UnaryCode::write_next(writer, x, zk),
+            Code::Gamma => GammaCode::write_next(writer, x, zk),
+            Code::Delta => DeltaCode::write_next(writer, x, zk),
+            Code::Zeta => ZetaCode::write_next(writer, x, zk),
+        }
+    }
+
+    #[inline(always)]
+    pub fn to_encoding_type(&self) -> EncodingType {
+        match self {
+            Code::Unary => EncodingType::UNARY,
+            Code::Gamma => EncodingType::GAMMA,
+            Code::Delta => EncodingType::DELTA,
+            Code::Zeta => EncodingType::ZETA,
+        }
+    }
+}
+
+impl TryFrom<EncodingType> for Code {
+    type Error = Error;
+
+    fn try_from(value: EncodingType) -> Result<Self> {
+        match value {
+            EncodingType::UNARY => Ok(Code::Unary),
+            EncodingType::GAMMA => Ok(Code::Gamma),
+            EncodingType::DELTA => Ok(Code::Delta),
+            EncodingType::ZETA => Ok(Code::Zeta),
+            EncodingType::HUFFMAN => Err(Error::UnsupportedEncoding(value.to_string())),
+        }
+    }
+}
+
 pub trait Huffman {
     fn to_encoding_type() -> EncodingType;
 }
@@ -258,6 +361,36 @@ pub fn zuck_decode(
         | low
 }
 
+/// Decodes a value like [`zuck_decode()`], returning an error instead of reading past the end of the stream.
+#[inline(always)]
+pub fn try_zuck_decode(
+    token: usize,
+    reader: &mut BinaryReader,
+    k: usize,
+    msb_in_token: usize,
+    lsb_in_token: usize,
+) -> Result<usize> {
+    let split_token = 1 << k;
+
+    if token < split_token {
+        return Ok(token);
+    }
+
+    let nbits = k - (msb_in_token + lsb_in_token)
+        + ((token - split_token) >> (msb_in_token + lsb_in_token));
+    if nbits + msb_in_token + lsb_in_token >= usize::BITS as usize {
+        return Err(Error::Malformed(format!("token {} of a {}-bit value", token, nbits + msb_in_token + lsb_in_token + 1)));
+    }
+
+    let low = token & ((1 << lsb_in_token) - 1);
+    let token = token >> lsb_in_token;
+
+    let bits = reader.try_read_int(nbits as u64)? as usize;
+    Ok((((((1 << msb_in_token) | (token & ((1 << msb_in_token) - 1))) << nbits) | bits)
+        << lsb_in_token)
+        | low)
+}
+
 #[inline(always)]
 pub fn zuck_encode(
     value: usize,
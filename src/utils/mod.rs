@@ -1,8 +1,10 @@
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
 
 use clap::ValueEnum;
 use serde::{Serialize, Deserialize};
 
+use crate::{Error, Result};
+
 pub mod encodings;
 
 /// Maps integers bijectively into natural numbers.
@@ -29,20 +31,24 @@ pub enum EncodingType {
     DELTA,
     ZETA,
     UNARY,
+    /// Not a universal code, so it cannot be chosen for the sections of BV graphs
+    #[value(skip)]
     HUFFMAN,
 }
 
-impl From<&str> for EncodingType {
-    fn from(value: &str) -> Self {
+impl FromStr for EncodingType {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
         let sanitized = value.trim().to_uppercase();
 
         match sanitized.as_str() {
-            "GAMMA" => EncodingType::GAMMA,
-            "DELTA" => EncodingType::DELTA,
-            "ZETA" => EncodingType::ZETA,
-            "UNARY" => EncodingType::UNARY,
-            "HUFFMAN" => EncodingType::HUFFMAN,
-            _ => panic!("Encoding type {} is not supported", sanitized)
+            "GAMMA" => Ok(EncodingType::GAMMA),
+            "DELTA" => Ok(EncodingType::DELTA),
+            "ZETA" => Ok(EncodingType::ZETA),
+            "UNARY" => Ok(EncodingType::UNARY),
+            "HUFFMAN" => Ok(EncodingType::HUFFMAN),
+            _ => Err(Error::UnsupportedEncoding(sanitized))
         }
     }
 }
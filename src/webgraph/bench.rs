@@ -0,0 +1,340 @@
+use std::{fs, hint::black_box, io, time::Instant};
+
+use rand::{distributions::{Distribution, WeightedIndex}, rngs::StdRng, Rng, SeedableRng};
+use serde::Serialize;
+
+use crate::{properties::Properties, CheckedGraph, Error, Result};
+
+use super::load::GraphLoader;
+
+/// The nodes whose successors are queried by a [`Benchmark`].
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub enum QueryDistribution {
+    /// Nodes drawn uniformly at random
+    #[default]
+    Uniform,
+    /// Nodes drawn with probability proportional to their outdegree (uniformly if the graph has no arcs)
+    Degree,
+    /// Given nodes of the graph, queried in order
+    Replay(Box<[usize]>),
+}
+
+impl QueryDistribution {
+    /// Reads the nodes to be replayed from a file holding a node per line, ignoring empty lines.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file of the nodes
+    /// * `num_nodes` - The number of nodes of the graph to be queried
+    pub fn replay(path: &str, num_nodes: usize) -> Result<Self> {
+        let invalid = |reason: String| Error::Io {
+            path: path.to_string(),
+            source: io::Error::new(io::ErrorKind::InvalidData, reason),
+        };
+
+        fs::read_to_string(path)
+            .map_err(Error::io(path))?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| match line.parse() {
+                Ok(node) if node < num_nodes => Ok(node),
+                Ok(node) => Err(invalid(format!("node {} out of range for a graph with {} nodes", node, num_nodes))),
+                Err(_) => Err(invalid(format!("invalid node {}", line))),
+            })
+            .collect::<Result<_>>()
+            .map(QueryDistribution::Replay)
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            QueryDistribution::Uniform => "uniform",
+            QueryDistribution::Degree => "degree",
+            QueryDistribution::Replay(_) => "replay",
+        }
+    }
+}
+
+/// A benchmark of random and sequential access on a compressed graph, reporting the same measures
+/// whatever its format, so that BV, Huffman and Zuckerli graphs can be compared on equal terms.
+///
+/// Random access times the decoding of the successors of each query, after some untimed warm-up queries;
+/// the bits read and the references followed by each query are measured afterwards, outside of the timings.
+///
+/// # Examples
+/// ```no_run
+/// use webgraph_rust::webgraph::{bench::{Benchmark, BenchmarkReport}, load::GraphLoader};
+///
+/// let report = Benchmark::new().set_seed(42).set_sequential(true).run("graph1", &GraphLoader::new())?;
+/// println!("{}\n{}", BenchmarkReport::CSV_HEADER, report.to_csv());
+/// # Ok::<(), webgraph_rust::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct Benchmark {
+    seed: u64,
+    queries: usize,
+    warmup: usize,
+    distribution: QueryDistribution,
+    sequential: bool,
+}
+
+impl Default for Benchmark {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            queries: 1000000,
+            warmup: 10000,
+            distribution: QueryDistribution::default(),
+            sequential: false,
+        }
+    }
+}
+
+impl Benchmark {
+    pub fn new() -> Benchmark {
+        Self::default()
+    }
+
+    /// Sets the seed of the generator drawing the queries.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The seed
+    pub fn set_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+
+        self
+    }
+
+    /// Sets the number of timed queries, which is ignored when replaying given nodes.
+    ///
+    /// # Arguments
+    ///
+    /// * `queries` - The number of queries, which has to be positive
+    pub fn set_queries(mut self, queries: usize) -> Self {
+        assert!(queries > 0, "The number of queries has to be >0.");
+        self.queries = queries;
+
+        self
+    }
+
+    /// Sets the number of untimed queries run before the timed ones, drawn from the same distribution
+    /// (or taken from the beginning of the replayed nodes).
+    ///
+    /// # Arguments
+    ///
+    /// * `warmup` - The number of warm-up queries
+    pub fn set_warmup(mut self, warmup: usize) -> Self {
+        self.warmup = warmup;
+
+        self
+    }
+
+    /// Sets the distribution of the queried nodes.
+    ///
+    /// # Arguments
+    ///
+    /// * `distribution` - The distribution
+    pub fn set_distribution(mut self, distribution: QueryDistribution) -> Self {
+        self.distribution = distribution;
+
+        self
+    }
+
+    /// Sets whether a full sequential scan of the graph has to be timed too.
+    ///
+    /// # Arguments
+    ///
+    /// * `flag` - If `true`, the sequential scan is timed
+    pub fn set_sequential(mut self, flag: bool) -> Self {
+        self.sequential = flag;
+
+        self
+    }
+
+    /// Loads a graph and runs the benchmark on it.
+    ///
+    /// # Arguments
+    ///
+    /// * `basename` - The basename of the compressed graph
+    /// * `loader` - The loader of the graph, telling, e.g., whether it has to be memory-mapped
+    pub fn run(&self, basename: &str, loader: &GraphLoader) -> Result<BenchmarkReport> {
+        let props = Properties::load(basename)?;
+        let graph = loader.load_checked(basename)?;
+
+        let mut report = BenchmarkReport {
+            graph: basename.to_string(),
+            format: format!("{:?}", props.format),
+            nodes: graph.num_nodes(),
+            arcs: graph.num_arcs(),
+            distribution: self.distribution.name().to_string(),
+            seed: self.seed,
+            warmup: self.warmup,
+            ..Default::default()
+        };
+
+        let (warmup, queries) = self.queries(&*graph);
+        Self::random_access(&*graph, &warmup, &queries, &mut report)?;
+        if self.sequential {
+            Self::sequential(&*graph, &mut report);
+        }
+
+        Ok(report)
+    }
+
+    /// Returns the warm-up and the timed queries.
+    fn queries(&self, graph: &dyn CheckedGraph) -> (Vec<usize>, Vec<usize>) {
+        let n = graph.num_nodes();
+
+        let nodes: Vec<usize> = match &self.distribution {
+            QueryDistribution::Replay(nodes) => {
+                let warmup = nodes.iter().cycle().take(self.warmup).copied().collect();
+                return (warmup, nodes.to_vec());
+            },
+            _ if n == 0 => return (Vec::new(), Vec::new()),
+            QueryDistribution::Degree if graph.num_arcs() > 0 => {
+                let outdegrees = (0..n).map(|x| graph.outdegree(x).unwrap());
+                let index = WeightedIndex::new(outdegrees).unwrap();
+
+                index.sample_iter(StdRng::seed_from_u64(self.seed)).take(self.warmup + self.queries).collect()
+            },
+            _ => {
+                let mut rng = StdRng::seed_from_u64(self.seed);
+
+                (0..self.warmup + self.queries).map(|_| rng.gen_range(0..n)).collect()
+            },
+        };
+
+        let (warmup, queries) = nodes.split_at(self.warmup);
+        (warmup.to_vec(), queries.to_vec())
+    }
+
+    /// Times the queries one by one, then measures what they read.
+    fn random_access(graph: &dyn CheckedGraph, warmup: &[usize], queries: &[usize], report: &mut BenchmarkReport) -> Result<()> {
+        for &x in warmup {
+            black_box(graph.successors(x));
+        }
+
+        let mut latencies = Vec::with_capacity(queries.len());
+        let mut successors = 0;
+        for &x in queries {
+            let start = Instant::now();
+            let list = black_box(graph.successors(x));
+            latencies.push(start.elapsed().as_nanos() as u64);
+
+            successors += list.len();
+        }
+
+        let mut bits = 0;
+        let mut chain_depth = 0;
+        for &x in queries {
+            let cost = graph.try_access_cost(x)?;
+            bits += cost.bits;
+            chain_depth += cost.chain_depth;
+        }
+
+        latencies.sort_unstable();
+        let count = queries.len() as f64;
+
+        report.queries = queries.len();
+        report.avg_outdegree = successors as f64 / count;
+        report.mean_ns = latencies.iter().sum::<u64>() as f64 / count;
+        report.p50_ns = percentile(&latencies, 0.5);
+        report.p99_ns = percentile(&latencies, 0.99);
+        report.bits_per_query = bits as f64 / count;
+        report.avg_chain_depth = chain_depth as f64 / count;
+
+        Ok(())
+    }
+
+    /// Times a full scan of the graph.
+    fn sequential(graph: &dyn CheckedGraph, report: &mut BenchmarkReport) {
+        let mut nodes = 0;
+        let mut arcs = 0;
+
+        let start = Instant::now();
+        for (_, successors) in graph.iter_nodes() {
+            nodes += 1;
+            arcs += black_box(successors).len();
+        }
+        let scan_ns = start.elapsed().as_nanos() as u64;
+
+        let seconds = scan_ns as f64 / 1e9;
+        report.scan_ns = Some(scan_ns);
+        report.scan_nodes_per_second = Some(nodes as f64 / seconds);
+        report.scan_arcs_per_second = Some(arcs as f64 / seconds);
+        report.scan_ns_per_arc = (arcs > 0).then(|| scan_ns as f64 / arcs as f64);
+    }
+}
+
+/// Returns the given percentile of the sorted values, by the nearest-rank method (0 if there are no values).
+fn percentile(sorted: &[u64], p: f64) -> u64 {
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+
+    sorted.get(rank.max(1) - 1).copied().unwrap_or(0)
+}
+
+/// The results of a [`Benchmark`] on a graph, which can be written as a CSV row (see [`Self::CSV_HEADER`])
+/// or serialized, e.g., to JSON through `serde_json`.
+///
+/// Times are in nanoseconds; the measures of the sequential scan are missing if it has not been run, and its time
+/// per arc also if the graph has no arcs.
+#[derive(Clone, PartialEq, Debug, Default, Serialize)]
+pub struct BenchmarkReport {
+    /// The basename of the graph
+    pub graph: String,
+    pub format: String,
+    pub nodes: usize,
+    pub arcs: usize,
+    pub distribution: String,
+    pub seed: u64,
+    pub warmup: usize,
+    /// The number of timed queries
+    pub queries: usize,
+    /// The average number of successors returned by a query
+    pub avg_outdegree: f64,
+    pub mean_ns: f64,
+    pub p50_ns: u64,
+    pub p99_ns: u64,
+    /// The average number of bits read by a query, over the whole chain of references
+    pub bits_per_query: f64,
+    /// The average number of references followed by a query
+    pub avg_chain_depth: f64,
+    /// The time of a full sequential scan
+    pub scan_ns: Option<u64>,
+    pub scan_nodes_per_second: Option<f64>,
+    pub scan_arcs_per_second: Option<f64>,
+    pub scan_ns_per_arc: Option<f64>,
+}
+
+impl BenchmarkReport {
+    /// The header of the CSV rows written by [`Self::to_csv()`].
+    pub const CSV_HEADER: &'static str = "graph,format,nodes,arcs,distribution,seed,warmup,queries,avg_outdegree,mean_ns,p50_ns,p99_ns,\
+        bits_per_query,avg_chain_depth,scan_ns,scan_nodes_per_second,scan_arcs_per_second,scan_ns_per_arc";
+
+    /// Returns the report as a CSV row, whose fields are listed by [`Self::CSV_HEADER`].
+    pub fn to_csv(&self) -> String {
+        let optional = |value: Option<String>| value.unwrap_or_default();
+
+        [
+            self.graph.clone(),
+            self.format.clone(),
+            self.nodes.to_string(),
+            self.arcs.to_string(),
+            self.distribution.clone(),
+            self.seed.to_string(),
+            self.warmup.to_string(),
+            self.queries.to_string(),
+            self.avg_outdegree.to_string(),
+            self.mean_ns.to_string(),
+            self.p50_ns.to_string(),
+            self.p99_ns.to_string(),
+            self.bits_per_query.to_string(),
+            self.avg_chain_depth.to_string(),
+            optional(self.scan_ns.map(|v| v.to_string())),
+            optional(self.scan_nodes_per_second.map(|v| v.to_string())),
+            optional(self.scan_arcs_per_second.map(|v| v.to_string())),
+            optional(self.scan_ns_per_arc.map(|v| v.to_string())),
+        ].join(",")
+    }
+}
@@ -0,0 +1,30 @@
+/// Reusable buffers for decoding successor lists by random access.
+///
+/// The buffers grow to the size of the largest lists decoded through them, so that a steady stream of queries
+/// through the same buffers, writing into the same output vector, allocates no memory. Each thread querying
+/// a graph should use buffers of its own.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct DecodeBuffers {
+    pub(crate) blocks: Vec<usize>,
+    /// The left extremes of the intervals
+    pub(crate) left: Vec<i64>,
+    /// The lengths of the intervals
+    pub(crate) len: Vec<usize>,
+    /// The successors belonging to intervals
+    pub(crate) intervals: Vec<i64>,
+    pub(crate) residuals: Vec<i64>,
+    /// The successors belonging to intervals merged with the residuals
+    pub(crate) extra: Vec<i64>,
+    /// The successors copied from the list referred to
+    pub(crate) copied: Vec<usize>,
+    /// The list referred to by the list being decoded
+    pub(crate) reference: Vec<usize>,
+    /// The buffers decoding the list referred to, created the first time a list refers to another one
+    pub(crate) next: Option<Box<DecodeBuffers>>,
+}
+
+impl DecodeBuffers {
+    pub fn new() -> DecodeBuffers {
+        Self::default()
+    }
+}
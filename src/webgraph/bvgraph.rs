@@ -1,45 +1,39 @@
-use std::{fs::{self, File}, vec, cmp::Ordering, marker::PhantomData, cell::{RefCell, Cell}, rc::Rc, borrow::BorrowMut, path::Path};
+use std::{fs, vec, borrow::Cow, cmp::Ordering, marker::PhantomData, ops::Range};
 
-use sucds::{mii_sequences::{EliasFanoBuilder, EliasFano}, Serializable};
+use crate::{Error, SequentialGraph, RandomAccessGraph, AccessCost, CheckedGraph, CheckedNodes, Store, properties::{GraphFormat, Properties}, utils::{encodings::{Code, UniversalCode, GammaCode}, nat2int, int2nat}};
+use crate::bitstreams::{BinaryReader, BinaryWriter, InputStream};
 
-use crate::{ImmutableGraph, ascii_graph::AsciiGraph, properties::Properties, utils::{encodings::{UniversalCode, GammaCode, ZetaCode, UnaryCode}, nat2int, int2nat}};
-use crate::bitstreams::{BinaryReader, BinaryWriter};
+use super::buffers::DecodeBuffers;
+use super::cache::{CacheStats, Pinning, SharedCache};
+use super::container::{self, FileKind};
+use super::stats::CompressionStats;
+use super::offsets::{Offsets, load_offsets, store_elias_fano};
+use super::nodes::NodeCheck;
+use super::parallel::{self, CompressedChunk, Concatenation, RangeStart};
+use super::sync::SyncPoints;
 
 #[derive(Clone, Eq, PartialEq, Debug, Default)]
 struct CompressionVectors {
-    blocks: RefCell<Vec<usize>>,
-    extras: RefCell<Vec<usize>>,
-    left: RefCell<Vec<usize>>,
-    len: RefCell<Vec<usize>>,
-    residuals: RefCell<Vec<usize>>,
+    blocks: Vec<usize>,
+    extras: Vec<usize>,
+    left: Vec<usize>,
+    len: Vec<usize>,
+    residuals: Vec<usize>,
+    /// The positions in the bitstream where the reference, the blocks and the intervals of the last list end
+    section_ends: [usize; 3],
 }
 
-#[derive(Clone, Eq, PartialEq, Debug)]
-pub struct BVGraph<
-    InBlockCoding: UniversalCode,
-    InBlockCountCoding: UniversalCode,
-    InOutdegreeCoding: UniversalCode,
-    InOffsetCoding: UniversalCode,
-    InReferenceCoding: UniversalCode,
-    InIntervalCoding: UniversalCode,
-    InResidualCoding: UniversalCode,
-    OutBlockCoding: UniversalCode,
-    OutBlockCountCoding: UniversalCode,
-    OutOutdegreeCoding: UniversalCode,
-    OutOffsetCoding: UniversalCode,
-    OutReferenceCoding: UniversalCode,
-    OutIntervalCoding: UniversalCode,
-    OutResidualCoding: UniversalCode,
-> {
+/// A graph compressed with the BV format.
+/// 
+/// The type parameter holds the offsets of the successor lists: a graph built without loading them
+/// (with `O = ()`) can only be iterated sequentially, while random access is available once they are loaded
+/// into an [`Offsets`] backend, either explicitly (`Box<[usize]>`) or succinctly (`EliasFano`).
+#[derive(Clone, Debug)]
+pub struct BVGraph<O = Box<[usize]>> {
     n: usize,
     m: usize,
-    pub graph_memory: Rc<[u8]>,
-    pub offsets: Box<[usize]>,
-    pub graph_binary_wrapper: RefCell<BinaryReader>,
-    outdegrees_binary_wrapper: RefCell<BinaryReader>,
-    cached_node: Cell<Option<usize>>,
-    cached_outdegree: Cell<Option<usize>>,
-    cached_ptr: Cell<Option<usize>>,
+    pub graph_memory: InputStream,
+    pub offsets: O,
     in_max_ref_count: usize,
     in_window_size: usize,
     in_min_interval_len: usize,
@@ -49,55 +43,27 @@ pub struct BVGraph<
     in_zeta_k: Option<u64>,
     out_zeta_k: Option<u64>,
     elias_fano: bool,
-    compression_vectors: CompressionVectors,
-    _phantom_in_block_coding: PhantomData<InBlockCoding>,
-    _phantom_in_block_count_coding: PhantomData<InBlockCountCoding>,
-    _phantom_in_outdegree_coding: PhantomData<InOutdegreeCoding>,
-    _phantom_in_offset_coding: PhantomData<InOffsetCoding>,
-    _phantom_in_reference_coding: PhantomData<InReferenceCoding>,
-    _phantom_in_interval_coding: PhantomData<InIntervalCoding>,
-    _phantom_in_residual_coding: PhantomData<InResidualCoding>,
-    _phantom_out_block_coding: PhantomData<OutBlockCoding>,
-    _phantom_out_block_count_coding: PhantomData<OutBlockCountCoding>,
-    _phantom_out_outdegree_coding: PhantomData<OutOutdegreeCoding>,
-    _phantom_out_offset_coding: PhantomData<OutOffsetCoding>,
-    _phantom_out_reference_coding: PhantomData<OutReferenceCoding>,
-    _phantom_out_interval_coding: PhantomData<OutIntervalCoding>,
-    _phantom_out_residual_coding: PhantomData<OutResidualCoding>,
+    container: bool,
+    stats: bool,
+    threads: usize,
+    sync_interval: usize,
+    cache: SharedCache,
+    in_block_coding: Code,
+    in_block_count_coding: Code,
+    in_outdegree_coding: Code,
+    in_reference_coding: Code,
+    in_interval_coding: Code,
+    in_residual_coding: Code,
+    out_block_coding: Code,
+    out_block_count_coding: Code,
+    out_outdegree_coding: Code,
+    out_offset_coding: Code,
+    out_reference_coding: Code,
+    out_interval_coding: Code,
+    out_residual_coding: Code,
 }
 
-impl<
-    InBlockCoding: UniversalCode,
-    InBlockCountCoding: UniversalCode,
-    InOutdegreeCoding: UniversalCode,
-    InOffsetCoding: UniversalCode,
-    InReferenceCoding: UniversalCode,
-    InIntervalCoding: UniversalCode,
-    InResidualCoding: UniversalCode,
-    OutBlockCoding: UniversalCode,
-    OutBlockCountCoding: UniversalCode,
-    OutOutdegreeCoding: UniversalCode,
-    OutOffsetCoding: UniversalCode,
-    OutReferenceCoding: UniversalCode,
-    OutIntervalCoding: UniversalCode,
-    OutResidualCoding: UniversalCode,
-> ImmutableGraph for BVGraph<
-    InBlockCoding,
-    InBlockCountCoding,
-    InOutdegreeCoding,
-    InOffsetCoding,
-    InReferenceCoding,
-    InIntervalCoding,
-    InResidualCoding,
-    OutBlockCoding,
-    OutBlockCountCoding,
-    OutOutdegreeCoding,
-    OutOffsetCoding,
-    OutReferenceCoding,
-    OutIntervalCoding,
-    OutResidualCoding,
->
-{
+impl<O> SequentialGraph for BVGraph<O> {
     type NodeT = usize;
 
     /// Returns the number of nodes in the BVGraph.
@@ -112,27 +78,25 @@ impl<
         self.m
     }
 
+    /// Returns an iterator over the nodes of the BVGraph, each paired with its successors.
+    fn iter_nodes(&self) -> impl Iterator<Item = (Self::NodeT, Box<[Self::NodeT]>)> + '_ {
+        let mut iter = self.iter();
+
+        std::iter::from_fn(move || {
+            let node = iter.next()?;
+            Some((node, iter.successor_array()[..iter.outdegree()].into()))
+        })
+    }
+}
+
+impl<O: Offsets> RandomAccessGraph for BVGraph<O> {
     /// Returns the outdegree of a given node or `None` otherwise.
     /// 
     /// # Arguments
     /// 
     /// * `x` - The node number
     fn outdegree(&self, x: Self::NodeT) -> Option<usize> {
-        if self.cached_node.get().is_some() && x == self.cached_node.get().unwrap() {
-            return self.cached_outdegree.get();
-        }
-        
-        if x >= self.n {
-            return None;
-        }
-
-        self.outdegrees_binary_wrapper.borrow_mut().position(self.offsets[x] as u64);
-        
-        self.cached_node.set(Some(x));
-        self.cached_outdegree.set(Some(InOutdegreeCoding::read_next(&mut self.outdegrees_binary_wrapper.borrow_mut(), self.in_zeta_k) as usize));
-        self.cached_ptr.set(Some(self.outdegrees_binary_wrapper.borrow_mut().get_position()));
-
-        self.cached_outdegree.get()
+        self.reader().outdegree(x)
     }
 
     /// Returns the list of successors of a given node.
@@ -141,104 +105,71 @@ impl<
     /// 
     /// * `x` - The node number
     fn successors(&self, x: usize) -> Box<[Self::NodeT]> {
-        assert!(x < self.n, "Node index out of range {}", x);
-        self.decode_list(x, &mut self.graph_binary_wrapper.borrow_mut(), None, &mut []).into_boxed_slice()
+        self.reader().successors(x)
     }
 
-    fn store(&mut self, basename: &str) -> std::io::Result<()> {      
-        let mut graph_obs = BinaryWriter::new();
-        let mut offsets_values = Vec::with_capacity(self.n);
-
-        self.compress(&mut graph_obs, &mut offsets_values);
-        
-        let graph = graph_obs.build();
-        let props = Properties {
-            nodes: self.n,
-            arcs: self.m,
-            window_size: self.out_window_size,
-            max_ref_count: self.out_max_ref_count,
-            min_interval_len: self.out_min_interval_len,
-            zeta_k: self.out_zeta_k,
-            outdegree_coding: OutOutdegreeCoding::to_encoding_type(),
-            block_coding: OutBlockCoding::to_encoding_type(),
-            interval_coding: OutIntervalCoding::to_encoding_type(),
-            residual_coding: OutResidualCoding::to_encoding_type(),
-            reference_coding: OutReferenceCoding::to_encoding_type(),
-            block_count_coding: OutBlockCountCoding::to_encoding_type(),
-            offset_coding: OutOffsetCoding::to_encoding_type()
-        };
-
-        fs::write(format!("{}.graph", basename), graph.os)?;
-
-        if self.elias_fano {
-            let max_value = offsets_values.last().unwrap();
-            let num_values = offsets_values.len();
-
-            let mut efb = EliasFanoBuilder::new(max_value + 1, num_values).unwrap();
-            efb.extend(offsets_values).unwrap();
-
-            let ef = efb.build();
-
-            let mut serialized_ef = Vec::new();
-            ef.serialize_into(&mut serialized_ef).unwrap();
+    /// Returns a lazy iterator over the successors of a given node.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The node number
+    fn successors_iter(&self, x: Self::NodeT) -> impl Iterator<Item = Self::NodeT> + '_ {
+        assert!(x < self.n, "Node index out of range {}", x);
+        BVGraphSuccessors::new(self, x)
+    }
 
-            fs::write(format!("{}.offsets.ef", basename), serialized_ef)?;
-        } else {
-            let mut prev = 0;
+    /// Returns whether the arc from `x` to `y` exists.
+    /// 
+    /// The successor list is decoded only up to `y`, skipping whole intervals at once.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The source node
+    /// * `y` - The destination node
+    fn has_arc(&self, x: Self::NodeT, y: Self::NodeT) -> bool {
+        assert!(x < self.n, "Node index out of range {}", x);
+        let mut successors = BVGraphSuccessors::new(self, x);
+        successors.skip_to(y);
+        successors.next() == Some(y)
+    }
 
-            for offset in offsets_values.iter_mut() {
-                let old = *offset;
-                *offset -= prev;
-                prev = old;
-            }
+    /// Returns a lazy iterator over the successors of a given node in the range `[lo, hi)`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The node number
+    /// * `lo` - The smallest successor to return
+    /// * `hi` - The first successor not to be returned
+    fn successors_in_range(&self, x: Self::NodeT, lo: Self::NodeT, hi: Self::NodeT) -> impl Iterator<Item = Self::NodeT> + '_ {
+        assert!(x < self.n, "Node index out of range {}", x);
+        let mut successors = BVGraphSuccessors::new(self, x);
+        successors.skip_to(lo);
+        successors.take_while(move |&succ| succ < hi)
+    }
+}
 
-            let mut offsets_obs = BinaryWriter::new();
-            for offset in offsets_values {
-                self.write_offset(&mut offsets_obs, offset).unwrap();
-            }
+impl<O: Offsets> CheckedGraph for BVGraph<O> {
+    fn try_iter_nodes(&self) -> CheckedNodes<'_> {
+        Box::new(BVGraph::try_iter_nodes(self))
+    }
 
-            fs::write(format!("{}.offsets", basename), offsets_obs.build().os)?;
-        }
+    fn try_successors(&self, x: usize) -> crate::Result<Box<[usize]>> {
+        BVGraph::try_successors(self, x)
+    }
 
-        fs::write(format!("{}.properties", basename), Into::<String>::into(props))?;
+    fn try_access_cost(&self, x: usize) -> crate::Result<AccessCost> {
+        BVGraph::try_access_cost(self, x)
+    }
+}
 
-        Ok(())
+impl<O: Sync> Store for BVGraph<O> {
+    fn store(&mut self, basename: &str) -> crate::Result<()> {
+        self.store_graph(&*self, basename)
     }
 }
 
-pub struct BVGraphNodeIterator<
-    InBlockCoding: UniversalCode,
-    InBlockCountCoding: UniversalCode,
-    InOutdegreeCoding: UniversalCode,
-    InOffsetCoding: UniversalCode,
-    InReferenceCoding: UniversalCode,
-    InIntervalCoding: UniversalCode,
-    InResidualCoding: UniversalCode,
-    OutBlockCoding: UniversalCode,
-    OutBlockCountCoding: UniversalCode,
-    OutOutdegreeCoding: UniversalCode,
-    OutOffsetCoding: UniversalCode,
-    OutReferenceCoding: UniversalCode,
-    OutIntervalCoding: UniversalCode,
-    OutResidualCoding: UniversalCode,
-    BV: AsRef<BVGraph<
-        InBlockCoding,
-        InBlockCountCoding,
-        InOutdegreeCoding,
-        InOffsetCoding,
-        InReferenceCoding,
-        InIntervalCoding,
-        InResidualCoding,
-        OutBlockCoding,
-        OutBlockCountCoding,
-        OutOutdegreeCoding,
-        OutOffsetCoding,
-        OutReferenceCoding,
-        OutIntervalCoding,
-        OutResidualCoding,
->>>
-{
-    // The number of nodes
+pub struct BVGraphNodeIterator<O, BV: AsRef<BVGraph<O>>> {
+    // The node at which the iteration ends, that is the number of nodes unless iterating on a range
     n: usize,
     // The graph on which we iterate
     graph: BV,
@@ -250,73 +181,18 @@ pub struct BVGraphNodeIterator<
     window: Vec<Vec<usize>>,
     // Outdegrees of the window's lists to be passed to [`decode_list`]
     outd: Vec<usize>,
+    // The buffers for decoding the lists
+    buffers: DecodeBuffers,
+    // The vector receiving the next list, which then takes the place of the oldest list of the window
+    list: Vec<usize>,
     // The index of the node from which we started iterating
     from: usize,
     // The index of the node just before the next one
     curr: i64,
-    _phantom_in_block_coding: PhantomData<InBlockCoding>,
-    _phantom_in_block_count_coding: PhantomData<InBlockCountCoding>,
-    _phantom_in_outdegree_coding: PhantomData<InOutdegreeCoding>,
-    _phantom_in_offset_coding: PhantomData<InOffsetCoding>,
-    _phantom_in_reference_coding: PhantomData<InReferenceCoding>,
-    _phantom_in_interval_coding: PhantomData<InIntervalCoding>,
-    _phantom_in_residual_coding: PhantomData<InResidualCoding>,
-    _phantom_out_block_coding: PhantomData<OutBlockCoding>,
-    _phantom_out_block_count_coding: PhantomData<OutBlockCountCoding>,
-    _phantom_out_outdegree_coding: PhantomData<OutOutdegreeCoding>,
-    _phantom_out_offset_coding: PhantomData<OutOffsetCoding>,
-    _phantom_out_reference_coding: PhantomData<OutReferenceCoding>,
-    _phantom_out_interval_coding: PhantomData<OutIntervalCoding>,
-    _phantom_out_residual_coding: PhantomData<OutResidualCoding>,
+    _phantom: PhantomData<O>,
 }
 
-impl<
-    InBlockCoding: UniversalCode,
-    InBlockCountCoding: UniversalCode,
-    InOutdegreeCoding: UniversalCode,
-    InOffsetCoding: UniversalCode,
-    InReferenceCoding: UniversalCode,
-    InIntervalCoding: UniversalCode,
-    InResidualCoding: UniversalCode,
-    OutBlockCoding: UniversalCode,
-    OutBlockCountCoding: UniversalCode,
-    OutOutdegreeCoding: UniversalCode,
-    OutOffsetCoding: UniversalCode,
-    OutReferenceCoding: UniversalCode,
-    OutIntervalCoding: UniversalCode,
-    OutResidualCoding: UniversalCode,
-    BV: AsRef<BVGraph<
-        InBlockCoding,
-        InBlockCountCoding,
-        InOutdegreeCoding,
-        InOffsetCoding,
-        InReferenceCoding,
-        InIntervalCoding,
-        InResidualCoding,
-        OutBlockCoding,
-        OutBlockCountCoding,
-        OutOutdegreeCoding,
-        OutOffsetCoding,
-        OutReferenceCoding,
-        OutIntervalCoding,
-        OutResidualCoding,
->>> Iterator for BVGraphNodeIterator<
-        InBlockCoding,
-        InBlockCountCoding,
-        InOutdegreeCoding,
-        InOffsetCoding,
-        InReferenceCoding,
-        InIntervalCoding,
-        InResidualCoding,
-        OutBlockCoding,
-        OutBlockCountCoding,
-        OutOutdegreeCoding,
-        OutOffsetCoding,
-        OutReferenceCoding,
-        OutIntervalCoding,
-        OutResidualCoding,
-        BV
-> {
+impl<O, BV: AsRef<BVGraph<O>>> Iterator for BVGraphNodeIterator<O, BV> {
     type Item = usize;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -326,71 +202,21 @@ impl<
 
         self.curr += 1;
         let curr_idx = self.curr as usize % self.cyclic_buffer_size;
-        let decoded_list = self.graph.as_ref().decode_list(self.curr as usize, &mut self.ibs, Some(&mut self.window), &mut self.outd);
-
-        let d = self.outd[curr_idx];
-
-        if self.window[curr_idx].len() < d {
-            self.window[curr_idx] = vec![0usize; d];
-        }
-        
-        let mut i = 0; 
-        while i < d && i < decoded_list.len() {
-            self.window[curr_idx][i] = decoded_list[i];
-            i += 1;
-        }
+        self.graph.as_ref().decode_window_list_into(
+            self.curr as usize, 
+            &mut self.ibs, 
+            &self.window, 
+            &mut self.outd, 
+            &mut self.buffers, 
+            &mut self.list,
+        );
+        std::mem::swap(&mut self.window[curr_idx], &mut self.list);
 
         Some(self.curr as usize)
     }
 } 
 
-impl<
-    InBlockCoding: UniversalCode,
-    InBlockCountCoding: UniversalCode,
-    InOutdegreeCoding: UniversalCode,
-    InOffsetCoding: UniversalCode,
-    InReferenceCoding: UniversalCode,
-    InIntervalCoding: UniversalCode,
-    InResidualCoding: UniversalCode,
-    OutBlockCoding: UniversalCode,
-    OutBlockCountCoding: UniversalCode,
-    OutOutdegreeCoding: UniversalCode,
-    OutOffsetCoding: UniversalCode,
-    OutReferenceCoding: UniversalCode,
-    OutIntervalCoding: UniversalCode,
-    OutResidualCoding: UniversalCode,
-    BV: AsRef<BVGraph<
-        InBlockCoding,
-        InBlockCountCoding,
-        InOutdegreeCoding,
-        InOffsetCoding,
-        InReferenceCoding,
-        InIntervalCoding,
-        InResidualCoding,
-        OutBlockCoding,
-        OutBlockCountCoding,
-        OutOutdegreeCoding,
-        OutOffsetCoding,
-        OutReferenceCoding,
-        OutIntervalCoding,
-        OutResidualCoding,
->>> BVGraphNodeIterator<
-        InBlockCoding,
-        InBlockCountCoding,
-        InOutdegreeCoding,
-        InOffsetCoding,
-        InReferenceCoding,
-        InIntervalCoding,
-        InResidualCoding,
-        OutBlockCoding,
-        OutBlockCountCoding,
-        OutOutdegreeCoding,
-        OutOffsetCoding,
-        OutReferenceCoding,
-        OutIntervalCoding,
-        OutResidualCoding,
-        BV
-> {
+impl<O, BV: AsRef<BVGraph<O>>> BVGraphNodeIterator<O, BV> {
     #[inline(always)]
     pub fn has_next(&self) -> bool {
         self.curr < self.n as i64 - 1
@@ -407,208 +233,367 @@ impl<
         assert!(self.curr != self.from as i64 - 1);
         self.window[self.curr as usize % self.cyclic_buffer_size].as_slice()
     }
+
+    /// Moves the iterator to the start of a range of nodes, filling the window with the lists preceding it.
+    pub(crate) fn seek(&mut self, start: RangeStart) {
+        self.ibs.position(start.position as u64);
+
+        let first = start.nodes.start - start.window.len();
+        for (i, list) in start.window.into_iter().enumerate() {
+            let index = (first + i) % self.cyclic_buffer_size;
+            self.outd[index] = list.len();
+            self.window[index] = list.into_vec();
+        }
+
+        self.from = start.nodes.start;
+        self.curr = start.nodes.start as i64 - 1;
+        self.n = start.nodes.end;
+    }
+
+    /// Turns the iterator into one over the nodes, each paired with its successors.
+    pub(crate) fn into_lists(mut self) -> impl Iterator<Item = (usize, Box<[usize]>)> {
+        std::iter::from_fn(move || {
+            let node = self.next()?;
+            Some((node, self.successor_array()[..self.outdegree()].into()))
+        })
+    }
+}
+
+/// A cursor for random access on a [`BVGraph`].
+/// 
+/// The cursor owns the bit reader used for decoding and the outdegree of the last queried node,
+/// so that several threads can query the same graph at once, each through its own cursor.
+pub struct BVGraphReader<'a, O = Box<[usize]>> {
+    // The graph on which we query
+    graph: &'a BVGraph<O>,
+    // The input bit stream
+    ibs: BinaryReader,
+    // The last node whose outdegree has been read, together with the outdegree
+    cached_outdegree: Option<(usize, usize)>,
+    // The buffers for decoding the lists
+    buffers: DecodeBuffers,
+}
+
+impl<'a, O: Offsets> BVGraphReader<'a, O> {
+    /// Returns the outdegree of a given node or `None` otherwise.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The node number
+    pub fn outdegree(&mut self, x: usize) -> Option<usize> {
+        if let Some((node, outd)) = self.cached_outdegree {
+            if node == x {
+                return Some(outd);
+            }
+        }
+
+        if x >= self.graph.n {
+            return None;
+        }
+
+        self.ibs.position(self.graph.offsets.get(x) as u64);
+        let outd = self.graph.in_outdegree_coding.read_next(&mut self.ibs, self.graph.in_zeta_k) as usize;
+        self.cached_outdegree = Some((x, outd));
+
+        Some(outd)
+    }
+
+    /// Returns the list of successors of a given node.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The node number
+    pub fn successors(&mut self, x: usize) -> Box<[usize]> {
+        let mut successors = Vec::new();
+        self.successors_into(x, &mut successors);
+
+        successors.into_boxed_slice()
+    }
+
+    /// Writes the successors of a given node into `successors`, replacing its content.
+    /// 
+    /// The cursor decodes the lists through buffers of its own, so that, as by
+    /// [`BVGraph::successors_into()`], a steady stream of queries allocates no memory.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The node number
+    /// * `successors` - The vector receiving the successors
+    pub fn successors_into(&mut self, x: usize, successors: &mut Vec<usize>) {
+        assert!(x < self.graph.n, "Node index out of range {}", x);
+        self.graph.decode_list_at(x, &mut self.ibs, &mut self.buffers, successors);
+        self.cached_outdegree = Some((x, successors.len()));
+    }
+}
+
+/// A lazy iterator over the successors of a node of a [`BVGraph`].
+/// 
+/// The successors copied from the reference list, the ones belonging to intervals and the residuals
+/// are merged on the fly, so that the residuals and the reference list are decoded only as far as needed.
+pub struct BVGraphSuccessors<'a, O = Box<[usize]>> {
+    // The graph on which we iterate
+    graph: &'a BVGraph<O>,
+    // The input bit stream, positioned at the next residual
+    ibs: BinaryReader,
+    // The node whose successors are returned
+    x: usize,
+    // The number of successors not returned yet
+    remaining: usize,
+    // The successors of the reference node, if any
+    reference: Option<Box<BVGraphSuccessors<'a, O>>>,
+    // The copy blocks, alternately copying and skipping successors of the reference
+    blocks: Vec<usize>,
+    // The index of the current copy block
+    curr_block: usize,
+    // The successors of the reference left in the current copy block
+    block_left: usize,
+    // The intervals, as pairs of left extreme and length
+    intervals: Vec<(usize, usize)>,
+    // The index of the current interval
+    curr_interval: usize,
+    // The index of the next successor inside the current interval
+    interval_idx: usize,
+    // The number of residuals not decoded yet
+    residuals_left: usize,
+    // The next successor copied from the reference and the next residual
+    next_copied: Option<usize>,
+    next_residual: Option<usize>,
+}
+
+impl<'a, O: Offsets> BVGraphSuccessors<'a, O> {
+    fn new(graph: &'a BVGraph<O>, x: usize) -> Self {
+        let mut ibs = BinaryReader::new(graph.graph_memory.clone());
+        ibs.position(graph.offsets.get(x) as u64);
+
+        let degree = graph.in_outdegree_coding.read_next(&mut ibs, graph.in_zeta_k) as usize;
+
+        let mut successors = BVGraphSuccessors {
+            graph,
+            ibs,
+            x,
+            remaining: degree,
+            reference: None,
+            blocks: Vec::new(),
+            curr_block: 0,
+            block_left: 0,
+            intervals: Vec::new(),
+            curr_interval: 0,
+            interval_idx: 0,
+            residuals_left: 0,
+            next_copied: None,
+            next_residual: None,
+        };
+
+        if degree == 0 {
+            return successors;
+        }
+
+        let mut reference = 0;
+        if graph.in_window_size > 0 {
+            reference = graph.in_reference_coding.read_next(&mut successors.ibs, graph.in_zeta_k) as usize;
+        }
+
+        let mut extra_count = degree;
+
+        if reference > 0 {
+            let block_count = graph.in_block_count_coding.read_next(&mut successors.ibs, graph.in_zeta_k) as usize;
+            successors.blocks = Vec::with_capacity(block_count);
+
+            let mut copied = 0; // # of copied successors
+            let mut total = 0; // total # of successors specified in some copy block
+
+            for i in 0..block_count {
+                let block = graph.in_block_coding.read_next(&mut successors.ibs, graph.in_zeta_k) as usize + 1 - (i == 0) as usize;
+                successors.blocks.push(block);
+                total += block;
+                copied += ((i & 1) == 0) as usize * block; // Alternate, count only even blocks
+            }
+
+            let reference_successors = BVGraphSuccessors::new(graph, x - reference);
+
+            // If the block count is even, we must compute the number of successors copied implicitly
+            if (block_count & 1) == 0 {
+                copied += reference_successors.remaining - total;
+            }
+
+            extra_count -= copied;
+
+            successors.reference = Some(Box::new(reference_successors));
+            successors.block_left = successors.blocks.first().copied().unwrap_or(usize::MAX);
+            successors.next_copied = successors.advance_copied();
+        }
+
+        if extra_count > 0 && graph.in_min_interval_len != 0 {
+            let interval_count = GammaCode::read_next(&mut successors.ibs, graph.in_zeta_k) as usize;
+
+            if interval_count != 0 {
+                successors.intervals = Vec::with_capacity(interval_count);
+
+                let left = nat2int(graph.in_interval_coding.read_next(&mut successors.ibs, graph.in_zeta_k)) + x as i64;
+                let len = graph.in_interval_coding.read_next(&mut successors.ibs, graph.in_zeta_k) as usize + graph.in_min_interval_len;
+                successors.intervals.push((left as usize, len));
+                let mut prev = left as usize + len; // Holds the last integer in the last interval
+                extra_count -= len;
+
+                for _ in 1..interval_count {
+                    prev += graph.in_interval_coding.read_next(&mut successors.ibs, graph.in_zeta_k) as usize + 1;
+
+                    let len = graph.in_interval_coding.read_next(&mut successors.ibs, graph.in_zeta_k) as usize + graph.in_min_interval_len;
+                    successors.intervals.push((prev, len));
+
+                    prev += len;
+                    extra_count -= len;
+                }
+            }
+        }
+
+        successors.residuals_left = extra_count;
+        successors.next_residual = successors.advance_residual(None);
+
+        successors
+    }
+
+    #[inline(always)]
+    fn advance_copied(&mut self) -> Option<usize> {
+        let reference = self.reference.as_mut()?;
+
+        loop {
+            while self.block_left == 0 {
+                self.curr_block += 1;
+                self.block_left = match self.curr_block.cmp(&self.blocks.len()) {
+                    Ordering::Less => self.blocks[self.curr_block],
+                    // The implicit last block extends up to the end of the reference list
+                    Ordering::Equal if (self.curr_block & 1) == 0 => usize::MAX,
+                    _ => return None,
+                };
+            }
+
+            let succ = reference.next()?;
+            self.block_left -= 1;
+
+            if (self.curr_block & 1) == 0 {
+                return Some(succ);
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn next_interval(&self) -> Option<usize> {
+        self.intervals.get(self.curr_interval).map(|&(left, _)| left + self.interval_idx)
+    }
+
+    #[inline(always)]
+    fn advance_interval(&mut self) {
+        self.interval_idx += 1;
+        if self.interval_idx == self.intervals[self.curr_interval].1 {
+            self.curr_interval += 1;
+            self.interval_idx = 0;
+        }
+    }
+
+    #[inline(always)]
+    fn advance_residual(&mut self, prev: Option<usize>) -> Option<usize> {
+        if self.residuals_left == 0 {
+            return None;
+        }
+
+        self.residuals_left -= 1;
+        let residual = self.graph.in_residual_coding.read_next(&mut self.ibs, self.graph.in_zeta_k);
+
+        Some(match prev {
+            Some(prev) => prev + residual as usize + 1,
+            None => (self.x as i64 + nat2int(residual)) as usize,
+        })
+    }
+
+    /// Skips the successors smaller than `lo`.
+    /// 
+    /// Whole intervals are skipped at once, while the copied successors and the residuals
+    /// are decoded only up to the first one not smaller than `lo`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `lo` - The smallest successor to keep
+    pub fn skip_to(&mut self, lo: usize) {
+        let mut skipped = 0;
+
+        while let Some(&(left, len)) = self.intervals.get(self.curr_interval) {
+            if left + len <= lo {
+                skipped += len - self.interval_idx;
+                self.curr_interval += 1;
+                self.interval_idx = 0;
+            } else {
+                if left + self.interval_idx < lo {
+                    skipped += lo - left - self.interval_idx;
+                    self.interval_idx = lo - left;
+                }
+                break;
+            }
+        }
+
+        while self.next_copied.is_some_and(|succ| succ < lo) {
+            self.next_copied = self.advance_copied();
+            skipped += 1;
+        }
+
+        while let Some(residual) = self.next_residual.filter(|&succ| succ < lo) {
+            self.next_residual = self.advance_residual(Some(residual));
+            skipped += 1;
+        }
+
+        self.remaining -= skipped;
+    }
+}
+
+impl<'a, O: Offsets> Iterator for BVGraphSuccessors<'a, O> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        self.remaining -= 1;
+
+        let copied = self.next_copied.unwrap_or(usize::MAX);
+        let interval = self.next_interval().unwrap_or(usize::MAX);
+        let residual = self.next_residual.unwrap_or(usize::MAX);
+
+        if copied < interval && copied < residual {
+            self.next_copied = self.advance_copied();
+            Some(copied)
+        } else if interval < residual {
+            self.advance_interval();
+            Some(interval)
+        } else {
+            self.next_residual = self.advance_residual(Some(residual));
+            Some(residual)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
 }
 
-impl<
-    InBlockCoding: UniversalCode,
-    InBlockCountCoding: UniversalCode,
-    InOutdegreeCoding: UniversalCode,
-    InOffsetCoding: UniversalCode,
-    InReferenceCoding: UniversalCode,
-    InIntervalCoding: UniversalCode,
-    InResidualCoding: UniversalCode,
-    OutBlockCoding: UniversalCode,
-    OutBlockCountCoding: UniversalCode,
-    OutOutdegreeCoding: UniversalCode,
-    OutOffsetCoding: UniversalCode,
-    OutReferenceCoding: UniversalCode,
-    OutIntervalCoding: UniversalCode,
-    OutResidualCoding: UniversalCode,
-> AsMut<BVGraph<
-    InBlockCoding,
-    InBlockCountCoding,
-    InOutdegreeCoding,
-    InOffsetCoding,
-    InReferenceCoding,
-    InIntervalCoding,
-    InResidualCoding,
-    OutBlockCoding,
-    OutBlockCountCoding,
-    OutOutdegreeCoding,
-    OutOffsetCoding,
-    OutReferenceCoding,
-    OutIntervalCoding,
-    OutResidualCoding,
->> for BVGraph<
-    InBlockCoding,
-    InBlockCountCoding,
-    InOutdegreeCoding,
-    InOffsetCoding,
-    InReferenceCoding,
-    InIntervalCoding,
-    InResidualCoding,
-    OutBlockCoding,
-    OutBlockCountCoding,
-    OutOutdegreeCoding,
-    OutOffsetCoding,
-    OutReferenceCoding,
-    OutIntervalCoding,
-    OutResidualCoding,
-> {
-
-    fn as_mut(&mut self) -> &mut BVGraph<
-        InBlockCoding,
-        InBlockCountCoding,
-        InOutdegreeCoding,
-        InOffsetCoding,
-        InReferenceCoding,
-        InIntervalCoding,
-        InResidualCoding,
-        OutBlockCoding,
-        OutBlockCountCoding,
-        OutOutdegreeCoding,
-        OutOffsetCoding,
-        OutReferenceCoding,
-        OutIntervalCoding,
-        OutResidualCoding,
-    > {
+impl<'a, O: Offsets> ExactSizeIterator for BVGraphSuccessors<'a, O> {}
+
+impl<O> AsMut<BVGraph<O>> for BVGraph<O> {
+    fn as_mut(&mut self) -> &mut BVGraph<O> {
         self
     }
 }
 
-impl<
-    InBlockCoding: UniversalCode,
-    InBlockCountCoding: UniversalCode,
-    InOutdegreeCoding: UniversalCode,
-    InOffsetCoding: UniversalCode,
-    InReferenceCoding: UniversalCode,
-    InIntervalCoding: UniversalCode,
-    InResidualCoding: UniversalCode,
-    OutBlockCoding: UniversalCode,
-    OutBlockCountCoding: UniversalCode,
-    OutOutdegreeCoding: UniversalCode,
-    OutOffsetCoding: UniversalCode,
-    OutReferenceCoding: UniversalCode,
-    OutIntervalCoding: UniversalCode,
-    OutResidualCoding: UniversalCode,
-> AsRef<BVGraph<
-    InBlockCoding,
-    InBlockCountCoding,
-    InOutdegreeCoding,
-    InOffsetCoding,
-    InReferenceCoding,
-    InIntervalCoding,
-    InResidualCoding,
-    OutBlockCoding,
-    OutBlockCountCoding,
-    OutOutdegreeCoding,
-    OutOffsetCoding,
-    OutReferenceCoding,
-    OutIntervalCoding,
-    OutResidualCoding,
->> for BVGraph<
-    InBlockCoding,
-    InBlockCountCoding,
-    InOutdegreeCoding,
-    InOffsetCoding,
-    InReferenceCoding,
-    InIntervalCoding,
-    InResidualCoding,
-    OutBlockCoding,
-    OutBlockCountCoding,
-    OutOutdegreeCoding,
-    OutOffsetCoding,
-    OutReferenceCoding,
-    OutIntervalCoding,
-    OutResidualCoding,
->
-{
-    fn as_ref(&self) -> &BVGraph<
-        InBlockCoding, 
-        InBlockCountCoding, 
-        InOutdegreeCoding, 
-        InOffsetCoding, 
-        InReferenceCoding,
-        InIntervalCoding, 
-        InResidualCoding, 
-        OutBlockCoding, 
-        OutBlockCountCoding, 
-        OutOutdegreeCoding, 
-        OutOffsetCoding, 
-        OutReferenceCoding,
-        OutIntervalCoding, 
-        OutResidualCoding
-    > {
+impl<O> AsRef<BVGraph<O>> for BVGraph<O> {
+    fn as_ref(&self) -> &BVGraph<O> {
         self
     }
 }
 
-impl<
-    InBlockCoding: UniversalCode,
-    InBlockCountCoding: UniversalCode,
-    InOutdegreeCoding: UniversalCode,
-    InOffsetCoding: UniversalCode,
-    InReferenceCoding: UniversalCode,
-    InIntervalCoding: UniversalCode,
-    InResidualCoding: UniversalCode,
-    OutBlockCoding: UniversalCode,
-    OutBlockCountCoding: UniversalCode,
-    OutOutdegreeCoding: UniversalCode,
-    OutOffsetCoding: UniversalCode,
-    OutReferenceCoding: UniversalCode,
-    OutIntervalCoding: UniversalCode,
-    OutResidualCoding: UniversalCode,
-> IntoIterator for BVGraph<
-    InBlockCoding,
-    InBlockCountCoding,
-    InOutdegreeCoding,
-    InOffsetCoding,
-    InReferenceCoding,
-    InIntervalCoding,
-    InResidualCoding,
-    OutBlockCoding,
-    OutBlockCountCoding,
-    OutOutdegreeCoding,
-    OutOffsetCoding,
-    OutReferenceCoding,
-    OutIntervalCoding,
-    OutResidualCoding,
-> 
-{
+impl<O> IntoIterator for BVGraph<O> {
     type Item = usize;
 
-    type IntoIter = BVGraphNodeIterator<
-        InBlockCoding,
-        InBlockCountCoding,
-        InOutdegreeCoding,
-        InOffsetCoding,
-        InReferenceCoding,
-        InIntervalCoding,
-        InResidualCoding,
-        OutBlockCoding,
-        OutBlockCountCoding,
-        OutOutdegreeCoding,
-        OutOffsetCoding,
-        OutReferenceCoding,
-        OutIntervalCoding,
-        OutResidualCoding,
-        BVGraph<
-            InBlockCoding, 
-            InBlockCountCoding, 
-            InOutdegreeCoding, 
-            InOffsetCoding, 
-            InReferenceCoding,
-            InIntervalCoding, 
-            InResidualCoding, 
-            OutBlockCoding, 
-            OutBlockCountCoding, 
-            OutOutdegreeCoding, 
-            OutOffsetCoding, 
-            OutReferenceCoding,
-            OutIntervalCoding, 
-            OutResidualCoding
-        >
-    >;
+    type IntoIter = BVGraphNodeIterator<O, BVGraph<O>>;
 
     fn into_iter(self) -> Self::IntoIter {
         BVGraphNodeIterator {
@@ -617,75 +602,18 @@ impl<
             cyclic_buffer_size: self.in_window_size + 1,
             window: vec![vec![0usize; self.in_window_size + 1]; 1024],
             outd: vec![0usize; self.in_window_size + 1],
+            buffers: DecodeBuffers::default(),
+            list: Vec::new(),
             graph: self,
             from: 0,
             curr: -1,
-            _phantom_in_block_coding: PhantomData,
-            _phantom_in_block_count_coding: PhantomData,
-            _phantom_in_outdegree_coding: PhantomData,
-            _phantom_in_offset_coding: PhantomData,
-            _phantom_in_reference_coding: PhantomData,
-            _phantom_in_interval_coding: PhantomData,
-            _phantom_in_residual_coding: PhantomData,
-            _phantom_out_block_coding: PhantomData,
-            _phantom_out_block_count_coding: PhantomData,
-            _phantom_out_outdegree_coding: PhantomData,
-            _phantom_out_offset_coding: PhantomData,
-            _phantom_out_reference_coding: PhantomData,
-            _phantom_out_interval_coding: PhantomData,
-            _phantom_out_residual_coding: PhantomData,
+            _phantom: PhantomData,
         }
     }
 }
 
-impl<
-    InBlockCoding: UniversalCode,
-    InBlockCountCoding: UniversalCode,
-    InOutdegreeCoding: UniversalCode,
-    InOffsetCoding: UniversalCode,
-    InReferenceCoding: UniversalCode,
-    InIntervalCoding: UniversalCode,
-    InResidualCoding: UniversalCode,
-    OutBlockCoding: UniversalCode,
-    OutBlockCountCoding: UniversalCode,
-    OutOutdegreeCoding: UniversalCode,
-    OutOffsetCoding: UniversalCode,
-    OutReferenceCoding: UniversalCode,
-    OutIntervalCoding: UniversalCode,
-    OutResidualCoding: UniversalCode,
-> BVGraph<
-    InBlockCoding,
-    InBlockCountCoding,
-    InOutdegreeCoding,
-    InOffsetCoding,
-    InReferenceCoding,
-    InIntervalCoding,
-    InResidualCoding,
-    OutBlockCoding,
-    OutBlockCountCoding,
-    OutOutdegreeCoding,
-    OutOffsetCoding,
-    OutReferenceCoding,
-    OutIntervalCoding,
-    OutResidualCoding,
-> {
-    pub fn iter(&self) -> BVGraphNodeIterator<
-        InBlockCoding,
-        InBlockCountCoding,
-        InOutdegreeCoding,
-        InOffsetCoding,
-        InReferenceCoding,
-        InIntervalCoding,
-        InResidualCoding,
-        OutBlockCoding,
-        OutBlockCountCoding,
-        OutOutdegreeCoding,
-        OutOffsetCoding,
-        OutReferenceCoding,
-        OutIntervalCoding,
-        OutResidualCoding,
-        &Self
-    > {
+impl<O> BVGraph<O> {
+    pub fn iter(&self) -> BVGraphNodeIterator<O, &Self> {
         BVGraphNodeIterator {
             n: self.n,
             graph: self,
@@ -693,86 +621,140 @@ impl<
             cyclic_buffer_size: self.in_window_size + 1,
             window: vec![vec![0usize; self.in_window_size + 1]; 1024],
             outd: vec![0usize; self.in_window_size + 1],
+            buffers: DecodeBuffers::default(),
+            list: Vec::new(),
             from: 0,
             curr: -1,
-            _phantom_in_block_coding: PhantomData,
-            _phantom_in_block_count_coding: PhantomData,
-            _phantom_in_outdegree_coding: PhantomData,
-            _phantom_in_offset_coding: PhantomData,
-            _phantom_in_reference_coding: PhantomData,
-            _phantom_in_interval_coding: PhantomData,
-            _phantom_in_residual_coding: PhantomData,
-            _phantom_out_block_coding: PhantomData,
-            _phantom_out_block_count_coding: PhantomData,
-            _phantom_out_outdegree_coding: PhantomData,
-            _phantom_out_offset_coding: PhantomData,
-            _phantom_out_reference_coding: PhantomData,
-            _phantom_out_interval_coding: PhantomData,
-            _phantom_out_residual_coding: PhantomData,
+            _phantom: PhantomData,
         }
     }
 
-    #[inline(always)]
-    fn outdegree_internal(&self, x: usize) -> usize {
-        if self.cached_node.get().is_some() && x == self.cached_node.get().unwrap() {
-            return self.cached_outdegree.get().unwrap();
+    /// Returns an iterator over the nodes from `x` on, each paired with its successors, without using the offsets.
+    /// 
+    /// The iteration starts with an empty window at the last sync point not after `x`, skipping the lists before `x`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The first node of the iteration
+    /// * `sync_points` - The sync points of the graph
+    pub fn iter_from_sync(&self, x: usize, sync_points: &SyncPoints) -> impl Iterator<Item = (usize, Box<[usize]>)> + '_ {
+        assert!(x <= self.n, "Node index out of range {}", x);
+        let (sync_point, position) = sync_points.get(x);
+
+        let mut iter = self.iter();
+        iter.seek(RangeStart { nodes: sync_point..self.n, position, window: Vec::new() });
+        for _ in sync_point..x {
+            iter.next();
         }
-        
-        self.outdegrees_binary_wrapper.borrow_mut().position(self.offsets[x] as u64);
-        let d = InOutdegreeCoding::read_next(&mut self.outdegrees_binary_wrapper.borrow_mut(), self.in_zeta_k) as usize;
 
-        self.cached_node.set(Some(x));
-        self.cached_outdegree.set(Some(d));
-        self.cached_ptr.set(Some(self.outdegrees_binary_wrapper.borrow().get_position()));
+        iter.into_lists()
+    }
+
+    /// Decodes the successor list of `x` during a sequential scan, taking the reference lists
+    /// from `window` and their outdegrees from `outd`, and records the outdegree of `x` in `outd`.
+    #[inline(always)]
+    pub fn decode_list(&self, x: usize, decoder: &mut BinaryReader, window: &[Vec<usize>], outd: &mut [usize]) -> Vec<usize> {
+        let mut successors = Vec::new();
+        self.decode_window_list_into(x, decoder, window, outd, &mut DecodeBuffers::default(), &mut successors);
 
-        d
+        successors
     }
-    
+
+    /// Decodes the successor list of `x` into `successors` as [`Self::decode_list()`], using `buffers`
+    /// for the intermediate values.
     #[inline(always)]
-    pub fn decode_list(&self, x: usize, decoder: &mut BinaryReader, window: Option<&mut Vec<Vec<usize>>>, outd: &mut [usize]) -> Vec<usize> {
+    fn decode_window_list_into(
+        &self, 
+        x: usize, 
+        decoder: &mut BinaryReader, 
+        window: &[Vec<usize>], 
+        outd: &mut [usize], 
+        buffers: &mut DecodeBuffers, 
+        successors: &mut Vec<usize>,
+    ) {
         let cyclic_buffer_size = self.in_window_size + 1;
-        let degree;
-        if window.is_none() {
-            degree = self.outdegree_internal(x);
-            decoder.position(self.cached_ptr.get().unwrap() as u64);
-        } else {
-            degree = InOutdegreeCoding::read_next(decoder, self.in_zeta_k) as usize;
-            outd[x % cyclic_buffer_size] = degree;
-        }
+
+        self.decode_list_into(x, decoder, buffers, successors, |_, reference, _, _| {
+            let reference_index = reference % cyclic_buffer_size;
+            Some(&window[reference_index][0..outd[reference_index]])
+        });
+
+        outd[x % cyclic_buffer_size] = successors.len();
+    }
+
+    /// Decodes the successor list of `x` into `successors`, reading from the current position of `decoder`
+    /// and using `buffers` for the intermediate values.
+    /// 
+    /// The successor list of the reference node, if any, is retrieved through `reference_list` at most once:
+    /// given the reference node, it either returns its list, if at hand, or decodes it into the given vector
+    /// through the given buffers and returns `None`.
+    #[inline(always)]
+    fn decode_list_into<'r>(
+        &self, 
+        x: usize, 
+        decoder: &mut BinaryReader, 
+        buffers: &mut DecodeBuffers,
+        successors: &mut Vec<usize>,
+        mut reference_list: impl FnMut(&mut BinaryReader, usize, &mut Vec<usize>, &mut DecodeBuffers) -> Option<&'r [usize]>,
+    ) {
+        successors.clear();
+
+        let degree = self.in_outdegree_coding.read_next(decoder, self.in_zeta_k) as usize;
 
         if degree == 0 {
-            return Vec::new();
+            return;
         }
 
+        let DecodeBuffers { 
+            blocks: block, 
+            left, 
+            len, 
+            intervals, 
+            residuals: residual_list, 
+            extra, 
+            copied: block_list, 
+            reference: reference_buffer, 
+            next,
+        } = buffers;
+
+        successors.reserve_exact(degree);
+
         let mut reference = -1;
         if self.in_window_size > 0 {
-            reference = InReferenceCoding::read_next(decoder, self.in_zeta_k) as i64;
+            reference = self.in_reference_coding.read_next(decoder, self.in_zeta_k) as i64;
         }
+        let reference_node = (x as i64 - reference) as usize;
 
-        // Position in the circular buffer of the reference of the current node
-        let reference_index = ((x as i64 - reference + cyclic_buffer_size as i64) as usize) % cyclic_buffer_size;
+        block.clear();
 
-        let mut block = Vec::default();
+        // The reference list is retrieved as soon as its outdegree is needed; if not at hand,
+        // it is decoded into the reference buffer
+        let mut reference_retrieved = false;
+        let mut given_reference = None;
 
         let mut extra_count;
 
         if reference > 0 {
-            let block_count = InBlockCountCoding::read_next(decoder, self.in_zeta_k) as usize;
-            block = Vec::with_capacity(block_count);
+            let block_count = self.in_block_count_coding.read_next(decoder, self.in_zeta_k) as usize;
+            block.reserve(block_count);
 
             let mut copied = 0; // # of copied successors
             let mut total = 0; // total # of successors specified in some copy block
 
             for i in 0..block_count {
-                block.push(InBlockCoding::read_next(decoder, self.in_zeta_k) as usize + 1 - (i == 0) as usize);
+                block.push(self.in_block_coding.read_next(decoder, self.in_zeta_k) as usize + 1 - (i == 0) as usize);
                 total += block[i];
                 copied += ((i & 1) == 0) as usize * block[i]; // Alternate, count only even blocks
             }
 
             // If the block count is even, we must compute the number of successors copied implicitly
-            copied += ((block_count & 1) == 0) as usize * ((
-                if window.is_some() {outd[reference_index]} 
-                else {self.outdegree_internal((x as i64 - reference) as usize)}) - total);
+            if (block_count & 1) == 0 {
+                given_reference = reference_list(decoder, reference_node, reference_buffer, next.get_or_insert_with(Box::default));
+                reference_retrieved = true;
+                let reference_outdegree = given_reference.map_or(reference_buffer.len(), <[usize]>::len);
+
+                copied += reference_outdegree - total;
+            }
             
             extra_count = degree - copied;
         } else {
@@ -781,26 +763,26 @@ impl<
 
         let mut interval_count = 0; // Number of intervals
 
-        let mut left = Vec::default();
-        let mut len = Vec::default();
+        left.clear();
+        len.clear();
 
         if extra_count > 0 && self.in_min_interval_len != 0 {
             interval_count = GammaCode::read_next(decoder, self.in_zeta_k) as usize;
             
             if interval_count != 0 {
-                left = Vec::with_capacity(interval_count);
-                len = Vec::with_capacity(interval_count);
-                
-                left.push(nat2int(InIntervalCoding::read_next(decoder, self.in_zeta_k)) + x as i64);
-                len.push(InIntervalCoding::read_next(decoder, self.in_zeta_k) as usize + self.in_min_interval_len);
+                left.reserve(interval_count);
+                len.reserve(interval_count);
+
+                left.push(nat2int(self.in_interval_coding.read_next(decoder, self.in_zeta_k)) + x as i64);
+                len.push(self.in_interval_coding.read_next(decoder, self.in_zeta_k) as usize + self.in_min_interval_len);
                 let mut prev = left[0] + len[0] as i64;  // Holds the last integer in the last interval
                 extra_count -= len[0];
 
                 for i in 1..interval_count {
-                    prev += InIntervalCoding::read_next(decoder, self.in_zeta_k) as i64 + 1;
+                    prev += self.in_interval_coding.read_next(decoder, self.in_zeta_k) as i64 + 1;
                     
                     left.push(prev);
-                    len.push(InIntervalCoding::read_next(decoder, self.in_zeta_k) as usize + self.in_min_interval_len);
+                    len.push(self.in_interval_coding.read_next(decoder, self.in_zeta_k) as usize + self.in_min_interval_len);
 
                     prev += len[i] as i64;
                     extra_count -= len[i];
@@ -808,14 +790,15 @@ impl<
             }
         }
 
-        let mut residual_list = Vec::with_capacity(extra_count);
+        residual_list.clear();
+        residual_list.reserve(extra_count);
         if extra_count > 0 {
-            residual_list.push(x as i64 + nat2int(InResidualCoding::read_next(decoder, self.in_zeta_k)));
+            residual_list.push(x as i64 + nat2int(self.in_residual_coding.read_next(decoder, self.in_zeta_k)));
             let mut remaining = extra_count - 1;
             let mut curr_len = 1;
 
             while remaining > 0 {
-                residual_list.push(residual_list[curr_len - 1] + InResidualCoding::read_next(decoder, self.in_zeta_k) as i64 + 1);
+                residual_list.push(residual_list[curr_len - 1] + self.in_residual_coding.read_next(decoder, self.in_zeta_k) as i64 + 1);
                 curr_len += 1;
 
                 remaining -= 1;
@@ -823,18 +806,17 @@ impl<
         }
 
         // The extra part is made by the contribution of intervals, if any, and by the residuals list.
-        let mut extra_list;
+        let extra_list: &[i64];
         if interval_count > 0 {
-            let total_lenght = len.iter().sum();
-
-            extra_list = Vec::with_capacity(total_lenght);
+            intervals.clear();
+            intervals.reserve(len.iter().sum());
             let mut curr_left = if !left.is_empty() {left[0]} else {0};
             let mut curr_index = 0;
             let mut curr_interval = 0;
             let mut remaining = left.len();
 
             while remaining > 0 {
-                extra_list.push(curr_left + curr_index as i64);
+                intervals.push(curr_left + curr_index as i64);
                 curr_index += 1;
 
                 if curr_index == len[curr_interval] {
@@ -849,137 +831,402 @@ impl<
         
             if extra_count > 0 {
                 let len_residual = residual_list.len();
-                let len_extra = extra_list.len();
+                let len_extra = intervals.len();
 
-                let mut temp_list = Vec::with_capacity(len_residual + len_extra);
+                extra.clear();
+                extra.reserve(len_residual + len_extra);
                 let mut idx0 = 0;
                 let mut idx1 = 0;
                 while idx0 < len_residual && idx1 < len_extra {
-                    if residual_list[idx0] <= extra_list[idx1] {
-                        temp_list.push(residual_list[idx0]);
+                    if residual_list[idx0] <= intervals[idx1] {
+                        extra.push(residual_list[idx0]);
                         idx0 += 1;
                     } else {
-                        temp_list.push(extra_list[idx1]);
+                        extra.push(intervals[idx1]);
                         idx1 += 1;
                         
                     }
                 }
 
-                while idx0 < len_residual {
-                    temp_list.push(residual_list[idx0]);
-                    idx0 += 1;
-                }
-
-                while idx1 < len_extra {
-                    temp_list.push(extra_list[idx1]);
-                    idx1 += 1;
-                }
+                extra.extend_from_slice(&residual_list[idx0..]);
+                extra.extend_from_slice(&intervals[idx1..]);
 
-                extra_list = temp_list;
+                extra_list = extra;
+            } else {
+                extra_list = intervals;
             }
         } else {
             extra_list = residual_list;
         }
 
-        let mut block_list = Vec::default();
-        if reference > 0 {
-            let decoded_reference;
-
-            let mut reference_it = 
-                if let Some(window) = window {
-                    window[reference_index][0..outd[reference_index]].iter()
-                } else {
-                    decoded_reference = self.decode_list(
-                        (x as i64 - reference) as usize, 
-                        decoder,
-                        None, 
-                        &mut []
-                    );
-                    decoded_reference.iter()
-                };
-            
-            let mask_len = block.len();
-            let mut curr_mask = 0;
-            let mut left;
+        if reference <= 0 {
+            successors.extend(extra_list.iter().map(|&x| x as usize));
+            return;
+        }
 
-            if mask_len != 0 {
-                left = block[curr_mask] as i64;
+        if !reference_retrieved {
+            given_reference = reference_list(decoder, reference_node, reference_buffer, next.get_or_insert_with(Box::default));
+        }
+        let mut reference_it = given_reference.unwrap_or(reference_buffer).iter();
+        
+        let mask_len = block.len();
+        let mut curr_mask = 0;
+        let mut left;
+
+        if mask_len != 0 {
+            left = block[curr_mask] as i64;
+            curr_mask += 1;
+            if left == 0 && curr_mask < mask_len {
+                reference_it.nth(block[curr_mask] - 1);
                 curr_mask += 1;
-                if left == 0 && curr_mask < mask_len {
-                    reference_it.nth(block[curr_mask] - 1);
-                    curr_mask += 1;
 
-                    left = if curr_mask < mask_len {curr_mask += 1; block[curr_mask - 1] as i64} else {-1};
-                }
-            } else {
-                left = -1;
+                left = if curr_mask < mask_len {curr_mask += 1; block[curr_mask - 1] as i64} else {-1};
             }
+        } else {
+            left = -1;
+        }
 
-            block_list = Vec::with_capacity(reference_it.len());
-
-            while left != 0 {
-                let next = reference_it.next();
+        // Without extra successors the copied ones are the whole list, so they are copied into `successors` itself
+        let copied_only = extra_list.is_empty();
+        if copied_only {
+            std::mem::swap(block_list, successors);
+        }
 
-                if next.is_none() {
-                    break;
-                }
+        block_list.clear();
+        block_list.reserve(reference_it.len());
 
-                if left == -1 {
-                    block_list.push(*next.unwrap());
-                }
-                
-                if left > 0 {
-                    left -= 1;
-                    if left == 0 && curr_mask < mask_len {
-                        reference_it.nth(block[curr_mask] - 1);
-                        curr_mask += 1;
+        while left != 0 {
+            let next = reference_it.next();
 
-                        left = if curr_mask < mask_len {curr_mask += 1; block[curr_mask - 1] as i64} else {-1};
-                    }
-                    block_list.push(*next.unwrap());
-                }                
+            if next.is_none() {
+                break;
             }
-        }
 
-        if reference <= 0 {
-            let extra_list: Vec<usize> = extra_list.into_iter().map(|x| x as usize).collect();
-            return extra_list;
-        } else if extra_list.is_empty() {
-            return block_list;
-        };
+            if left == -1 {
+                block_list.push(*next.unwrap());
+            }
+            
+            if left > 0 {
+                left -= 1;
+                if left == 0 && curr_mask < mask_len {
+                    reference_it.nth(block[curr_mask] - 1);
+                    curr_mask += 1;
+
+                    left = if curr_mask < mask_len {curr_mask += 1; block[curr_mask - 1] as i64} else {-1};
+                }
+                block_list.push(*next.unwrap());
+            }                
+        }
+
+        if copied_only {
+            std::mem::swap(block_list, successors);
+            return;
+        }
 
         let len_block = block_list.len();
         let len_extra = extra_list.len();
 
-        let mut temp_list = Vec::with_capacity(len_block + len_extra);
         let mut idx0 = 0;
         let mut idx1 = 0;
         while idx0 < len_block && idx1 < len_extra {
             if block_list[idx0] < extra_list[idx1] as usize {
-                temp_list.push(block_list[idx0]);
+                successors.push(block_list[idx0]);
                 idx0 += 1;
             } else {
-                temp_list.push(extra_list[idx1] as usize);
+                successors.push(extra_list[idx1] as usize);
                 idx1 += 1;
             }
         }
 
-        while idx0 < len_block {
-            temp_list.push(block_list[idx0]);
-            idx0 += 1;
+        successors.extend_from_slice(&block_list[idx0..]);
+        successors.extend(extra_list[idx1..].iter().map(|&x| x as usize));
+    }
+
+    /// Returns an iterator over the nodes of the graph, each paired with its successors, decoded in checked mode.
+    /// 
+    /// Every list is validated as by [`Self::try_successors()`]: the iteration stops after the first error,
+    /// so that the graph can be safely scanned even if it comes from an untrusted or corrupted file.
+    pub fn try_iter_nodes(&self) -> impl Iterator<Item = crate::Result<(usize, Box<[usize]>)>> + '_ {
+        let mut ibs = BinaryReader::new(self.graph_memory.clone());
+        let mut window: Vec<Box<[usize]>> = vec![Box::default(); self.in_window_size.min(self.n) + 1];
+        let mut x = 0;
+        let mut failed = false;
+
+        std::iter::from_fn(move || {
+            if failed || x >= self.n {
+                return None;
+            }
+
+            let cyclic_buffer_size = window.len();
+            let successors = self.try_decode_list_with(x, &mut ibs, |_, reference| {
+                Ok(Cow::Borrowed(&window[reference % cyclic_buffer_size]))
+            });
+
+            match successors {
+                Ok(successors) => {
+                    let successors = successors.into_boxed_slice();
+                    window[x % cyclic_buffer_size] = successors.clone();
+                    x += 1;
+
+                    Some(Ok((x - 1, successors)))
+                },
+                Err(e) => {
+                    failed = true;
+                    Some(Err(e))
+                },
+            }
+        })
+    }
+
+    /// Decodes the successor list of `x` like [`Self::decode_list_into()`], validating every decoded value.
+    /// 
+    /// The reference has to fall inside the window, the copy blocks inside the reference list, and the copied
+    /// successors, the intervals and the residuals cannot exceed the outdegree; the resulting list has to be
+    /// strictly increasing and made of nodes of the graph. Any violation, as well as the end of the bitstream,
+    /// is reported as an error.
+    fn try_decode_list_with<'r>(
+        &self, 
+        x: usize, 
+        decoder: &mut BinaryReader, 
+        mut reference_list: impl FnMut(&mut BinaryReader, usize) -> crate::Result<Cow<'r, [usize]>>
+    ) -> crate::Result<Vec<usize>> {
+        // Values are checked against their bounds as `u64`, so that saturated sums always fail the check
+        let check = |value: u64, limit: usize, what: &str| match usize::try_from(value) {
+            Ok(value) if value <= limit => Ok(value),
+            _ => Err(Error::Malformed(format!("node {}: {} {} exceeds {}", x, what, value, limit))),
+        };
+        // The first residual and the first interval are coded as a signed gap from `x`
+        let from_x = |value: u64| (x as i64).checked_add(nat2int(value))
+            .and_then(|node| u64::try_from(node).ok())
+            .unwrap_or(u64::MAX);
+
+        let degree = self.in_outdegree_coding.try_read_next(decoder, self.in_zeta_k)?;
+        let degree = check(degree, self.n, "outdegree")?;
+
+        if degree == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut reference = 0;
+        if self.in_window_size > 0 {
+            let value = self.in_reference_coding.try_read_next(decoder, self.in_zeta_k)?;
+            reference = check(value, self.in_window_size.min(x), "reference")?;
+        }
+
+        let mut successors = Vec::new();
+
+        if reference > 0 {
+            let reference_successors = reference_list(decoder, x - reference)?;
+
+            let block_count = self.in_block_count_coding.try_read_next(decoder, self.in_zeta_k)?;
+            let block_count = check(block_count, reference_successors.len() + 1, "block count")?;
+
+            let mut pos = 0;
+            for i in 0..block_count {
+                let block = self.in_block_coding.try_read_next(decoder, self.in_zeta_k)?.saturating_add((i != 0) as u64);
+                let block = check(block, reference_successors.len() - pos, "copy block")?;
+
+                if (i & 1) == 0 { // Alternate, copy only even blocks
+                    successors.extend_from_slice(&reference_successors[pos..pos + block]);
+                }
+                pos += block;
+            }
+
+            // If the block count is even, the last block implicitly extends up to the end of the reference list
+            if (block_count & 1) == 0 {
+                successors.extend_from_slice(&reference_successors[pos..]);
+            }
+
+            check(successors.len() as u64, degree, "number of copied successors")?;
+        }
+
+        let mut extra_count = degree - successors.len();
+
+        if extra_count > 0 && self.in_min_interval_len != 0 {
+            let interval_count = GammaCode::try_read_next(decoder, self.in_zeta_k)?;
+            let interval_count = check(interval_count, extra_count, "interval count")?;
+
+            let mut prev = 0; // Holds the integer following the last interval
+            for i in 0..interval_count {
+                let value = self.in_interval_coding.try_read_next(decoder, self.in_zeta_k)?;
+                let left = if i == 0 { from_x(value) } else { (prev as u64).saturating_add(value).saturating_add(1) };
+
+                let len = self.in_interval_coding.try_read_next(decoder, self.in_zeta_k)?.saturating_add(self.in_min_interval_len as u64);
+                let len = check(len, extra_count, "interval length")?;
+
+                prev = check(left.saturating_add(len as u64), self.n, "interval end")?;
+                successors.extend(prev - len..prev);
+                extra_count -= len;
+            }
+        }
+
+        let mut prev = None;
+        for _ in 0..extra_count {
+            let value = self.in_residual_coding.try_read_next(decoder, self.in_zeta_k)?;
+            let residual = match prev {
+                None => from_x(value),
+                Some(prev) => (prev as u64).saturating_add(value).saturating_add(1),
+            };
+            let residual = check(residual, self.n - 1, "residual")?;
+
+            successors.push(residual);
+            prev = Some(residual);
         }
 
-        while idx1 < len_extra {
-            temp_list.push(extra_list[idx1] as usize);
-            idx1 += 1;
+        successors.sort_unstable();
+        if successors.windows(2).any(|pair| pair[0] == pair[1]) || successors.last().is_some_and(|&last| last >= self.n) {
+            return Err(Error::Malformed(format!("node {}: successors are not distinct nodes of the graph", x)));
         }
 
-        temp_list
+        Ok(successors)
+    }
+
+    /// Compresses any sequential graph and stores it under the given basename, using the
+    /// output parameters of this graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The graph to compress
+    /// * `basename` - The basename of the output files
+    pub fn store_graph<G: SequentialGraph<NodeT = usize>>(&self, graph: &G, basename: &str) -> crate::Result<()> where O: Sync {
+        self.store_nodes(graph.iter_nodes(), basename)
     }
 
+    /// Compresses a sequence of nodes, each paired with its successors, and stores it under
+    /// the given basename, using the output parameters of this graph.
+    ///
+    /// The nodes must be consecutive, starting from 0, and each successor list must be sorted.
+    /// At the first node out of place, [`Error::MisplacedNode`] is returned, and the files of any graph
+    /// with the same basename are left as they were.
+    /// The number of nodes and arcs are taken from the sequence itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `nodes` - The `(node, successors)` pairs to compress
+    /// * `basename` - The basename of the output files
+    pub fn store_nodes<L: AsRef<[usize]>>(&self, nodes: impl IntoIterator<Item = (usize, L)>, basename: &str) -> crate::Result<()> where O: Sync {
+        let graph_path = format!("{}.graph", basename);
+        let mut graph_obs = BinaryWriter::with_sink(container::create(&graph_path)?);
+        let mut offsets_values = Vec::new();
+
+        let mut stats = self.stats.then(CompressionStats::default);
+
+        let check = NodeCheck::default();
+        let nodes = check.check(nodes);
+        let (nodes, arcs) = if self.threads > 1 || self.sync_interval > 0 {
+            self.compress_parallel(nodes, &mut graph_obs, &mut offsets_values, stats.as_mut())
+        } else {
+            self.compress(nodes, &mut graph_obs, &mut offsets_values, stats.as_mut())
+        };
+
+        if let Err(e) = check.result() {
+            drop(graph_obs);
+            container::discard(&graph_path)?;
+            return Err(e);
+        }
+        graph_obs.finish().map_err(Error::io(&graph_path))?;
+        let props = Properties {
+            format: GraphFormat::BV,
+            nodes,
+            arcs,
+            window_size: self.out_window_size,
+            max_ref_count: self.out_max_ref_count,
+            min_interval_len: self.out_min_interval_len,
+            zeta_k: self.out_zeta_k,
+            outdegree_coding: self.out_outdegree_coding.to_encoding_type(),
+            block_coding: self.out_block_coding.to_encoding_type(),
+            interval_coding: self.out_interval_coding.to_encoding_type(),
+            residual_coding: self.out_residual_coding.to_encoding_type(),
+            reference_coding: self.out_reference_coding.to_encoding_type(),
+            block_count_coding: self.out_block_count_coding.to_encoding_type(),
+            offset_coding: self.out_offset_coding.to_encoding_type()
+        };
+
+        let container = self.container.then_some(&props);
+
+        container::seal(&graph_path, FileKind::Graph, container)?;
+
+        if self.sync_interval > 0 {
+            SyncPoints::from_offsets(self.sync_interval, &offsets_values).store(basename, container)?;
+        }
+
+        if self.elias_fano {
+            store_elias_fano(&offsets_values, format!("{}.offsets.ef", basename), container)?;
+        } else {
+            let mut prev = 0;
+
+            for offset in offsets_values.iter_mut() {
+                let old = *offset;
+                *offset -= prev;
+                prev = old;
+            }
+
+            let offsets_path = format!("{}.offsets", basename);
+            let mut offsets_obs = BinaryWriter::with_sink(container::create(&offsets_path)?);
+            for offset in offsets_values {
+                self.write_offset(&mut offsets_obs, offset).unwrap();
+            }
+
+            offsets_obs.finish().map_err(Error::io(&offsets_path))?;
+            container::seal(&offsets_path, FileKind::Offsets, container)?;
+        }
+
+        let properties_path = format!("{}.properties", basename);
+        fs::write(&properties_path, Into::<String>::into(props)).map_err(Error::io(&properties_path))?;
+
+        if let Some(stats) = stats {
+            stats.store(format!("{}.stats", basename))?;
+        }
+
+        Ok(())
+    }
+
+    /// Compresses a sequence of nodes as [`Self::compress()`] does, but splitting it in chunks of
+    /// [`CHUNK_SIZE`](super::parallel::CHUNK_SIZE) nodes (or of the sync interval, if any) that are compressed in parallel
+    /// and then concatenated.
+    ///
+    /// Returns the number of nodes and arcs that were compressed.
+    pub fn compress_parallel<L: AsRef<[usize]>>(
+        &self,
+        nodes: impl IntoIterator<Item = (usize, L)>,
+        graph_obs: &mut BinaryWriter,
+        offsets_values: &mut Vec<usize>,
+        stats: Option<&mut CompressionStats>
+    ) -> (usize, usize) where O: Sync {
+        let gather_stats = stats.is_some();
+        let mut concatenation = Concatenation::new(graph_obs, offsets_values, stats);
+
+        parallel::for_each_chunk(nodes, parallel::chunk_size(self.sync_interval), self.threads, || (), |_, _, chunk| {
+            let mut compressed = CompressedChunk {
+                graph_obs: BinaryWriter::new(),
+                offsets: Vec::with_capacity(chunk.len() + 1),
+                stats: gather_stats.then(CompressionStats::default),
+                arcs: 0,
+            };
+
+            (_, compressed.arcs) = self.compress(chunk.iter(), &mut compressed.graph_obs, &mut compressed.offsets, compressed.stats.as_mut());
+
+            compressed
+        }, |chunk| concatenation.push(chunk));
+
+        concatenation.finish()
+    }
+
+    /// Compresses a sequence of nodes, each paired with its successors, writing the lists to
+    /// `graph_obs` and the bit offset of each of them to `offsets_values`, and gathering the
+    /// statistics of the compression in `stats`, if given.
+    ///
+    /// Returns the number of nodes and arcs that were compressed.
     #[inline(always)]
-    pub fn compress(&mut self, graph_obs: &mut BinaryWriter, offsets_values: &mut Vec<usize>) {        
+    pub fn compress<L: AsRef<[usize]>>(
+        &self,
+        nodes: impl IntoIterator<Item = (usize, L)>,
+        graph_obs: &mut BinaryWriter,
+        offsets_values: &mut Vec<usize>,
+        mut stats: Option<&mut CompressionStats>
+    ) -> (usize, usize) {
         let mut bit_count = BinaryWriter::new();
+        let mut vectors = CompressionVectors::default();
         
         let cyclic_buffer_size = self.out_window_size + 1;
         // Cyclic array of previous lists
@@ -989,11 +1236,12 @@ impl<
         // The depth of the references of each list
         let mut ref_count: Vec<i32> = vec![0; cyclic_buffer_size];
         
-        let mut node_iter = self.iter();
+        let mut num_nodes = 0;
+        let mut num_arcs = 0;
         
-        while node_iter.has_next() {
-            let curr_node = node_iter.next().unwrap();
-            let outd = node_iter.outdegree();
+        for (curr_node, successors) in nodes {
+            let successors = successors.as_ref();
+            let outd = successors.len();
             let curr_idx = curr_node % cyclic_buffer_size;
             
             // println!("Curr node: {}, outdegree: {}", curr_node, outd);
@@ -1002,13 +1250,17 @@ impl<
             offsets_values.push(graph_obs.written_bits);
             
             self.write_outdegree(graph_obs, outd).unwrap();
-            
-            if outd > list[curr_idx].len() {
-                list[curr_idx].resize(outd, 0);
+
+            if let Some(stats) = stats.as_deref_mut() {
+                stats.outdegree_bits += (graph_obs.written_bits - offsets_values[offsets_values.len() - 1]) as u64;
             }
             
-            list[curr_idx] = Vec::from(&node_iter.successor_array()[..outd]);
+            list[curr_idx].clear();
+            list[curr_idx].extend_from_slice(successors);
             list_len[curr_idx] = outd;
+
+            num_nodes += 1;
+            num_arcs += outd;
             
             if outd > 0 {
                 let mut best_comp = i64::MAX;
@@ -1023,6 +1275,7 @@ impl<
                     if ref_count[cand as usize] < (self.out_max_ref_count as i32) && list_len[cand as usize] != 0 {
                         let diff_comp = 
                             self.diff_comp(&mut bit_count, 
+                                            &mut vectors,
                                             curr_node, 
                                             r, 
                                             list[cand as usize].as_slice(), 
@@ -1039,17 +1292,36 @@ impl<
                 debug_assert!(best_cand >= 0);
                 
                 ref_count[curr_idx] = ref_count[best_cand as usize] + 1;
+
+                let list_start = graph_obs.written_bits;
                 self.diff_comp(
                     graph_obs, 
+                    &mut vectors,
                     curr_node, 
                     best_ref as usize, 
                     list[best_cand as usize].as_slice(), 
                     list[curr_idx].as_slice(),
                 ).unwrap();
+
+                if let Some(stats) = stats.as_deref_mut() {
+                    stats.add_reference(best_ref as usize, ref_count[curr_idx] as usize);
+                    stats.add_sections(list_start, vectors.section_ends, graph_obs.written_bits);
+
+                    let residuals = if self.out_min_interval_len != 0 { &vectors.residuals } else { &vectors.extras };
+                    stats.add_list(curr_node, &list[curr_idx], vectors.len.iter().sum(), residuals);
+                }
             }
         }
 
         offsets_values.push(graph_obs.written_bits);
+
+        if let Some(stats) = stats {
+            stats.nodes = num_nodes;
+            stats.arcs = num_arcs;
+            stats.graph_bits = graph_obs.written_bits as u64;
+        }
+
+        (num_nodes, num_arcs)
     }
 
     #[inline(always)]
@@ -1103,6 +1375,7 @@ impl<
     fn diff_comp(
         &self,
         graph_obs: &mut BinaryWriter,
+        vectors: &mut CompressionVectors,
         curr_node: usize,  
         reference: usize,
         ref_list: &[usize],
@@ -1111,11 +1384,11 @@ impl<
         let curr_len = curr_list.len();
         let mut ref_len = ref_list.len();
         
-        self.compression_vectors.blocks.borrow_mut().clear();
-        self.compression_vectors.extras.borrow_mut().clear();
-        self.compression_vectors.left.borrow_mut().clear();
-        self.compression_vectors.len.borrow_mut().clear();
-        self.compression_vectors.residuals.borrow_mut().clear();
+        vectors.blocks.clear();
+        vectors.extras.clear();
+        vectors.left.clear();
+        vectors.len.clear();
+        vectors.residuals.clear();
 
         // let written_data_at_start = graph_obs.len();
         let written_data_at_start = graph_obs.written_bits;
@@ -1137,7 +1410,7 @@ impl<
                 match curr_list[j].cmp(&ref_list[k]) {
                     Ordering::Greater => {
                         // If while copying we go beyond the current element of the ref list, then we must stop
-                        self.compression_vectors.blocks.borrow_mut().push(curr_block_len);
+                        vectors.blocks.push(curr_block_len);
                         copying = false;
                         curr_block_len = 0; 
                     },
@@ -1146,7 +1419,7 @@ impl<
                         larger than us, then we can just add the current element to the extra list and move on,
                         increasing j.
                         */
-                        self.compression_vectors.extras.borrow_mut().push(curr_list[j]);
+                        vectors.extras.push(curr_list[j]);
                         j += 1;
                     },
                     Ordering::Equal => {
@@ -1159,14 +1432,14 @@ impl<
                 }
             } else if curr_list[j] < ref_list[k] { /* If we did not go beyond the current element of the ref list, 
                 we just add the current element to the extra list and move on, increasing j */
-                self.compression_vectors.extras.borrow_mut().push(curr_list[j]);
+                vectors.extras.push(curr_list[j]);
                 j += 1;
             } else if curr_list[j] > ref_list[k] { /* If we went beyond the current elem of the reference list,
                 we increase the block len and k */
                 k += 1;
                 curr_block_len += 1;
             } else { /* If we found a match, we flush the current block and start a new copying phase */
-                self.compression_vectors.blocks.borrow_mut().push(curr_block_len);
+                vectors.blocks.push(curr_block_len);
                 copying = true;
                 curr_block_len = 0;
             }
@@ -1175,22 +1448,23 @@ impl<
         /* We only enqueue the last block's len when we were copying 
         and did not copy up to the end of the ref list */
         if copying && k < ref_len {
-            self.compression_vectors.blocks.borrow_mut().push(curr_block_len);
+            vectors.blocks.push(curr_block_len);
         }
 
         // If there are still missing elements add them to the extra list
         while j < curr_len {
-            self.compression_vectors.extras.borrow_mut().push(curr_list[j]);
+            vectors.extras.push(curr_list[j]);
             j += 1;
         }
 
-        let block_count = self.compression_vectors.blocks.borrow().len();
-        let extra_count = self.compression_vectors.extras.borrow().len();
+        let block_count = vectors.blocks.len();
+        let extra_count = vectors.extras.len();
 
         // If we have a nontrivial reference window we write the reference to the reference list
         if self.out_window_size > 0 {
             _t = self.write_reference(graph_obs, reference)?;
         }
+        let reference_end = graph_obs.written_bits;
 
         // Then, if the reference is not void we write the length of the copy list
         if reference != 0 {
@@ -1198,12 +1472,14 @@ impl<
 
             // Then, we write the copy list; all lengths except the first one are decremented
             if block_count > 0 {
-                _t = self.write_block(graph_obs, self.compression_vectors.blocks.borrow()[0])?;
-                for blk in self.compression_vectors.blocks.borrow().iter().skip(1) {
+                _t = self.write_block(graph_obs, vectors.blocks[0])?;
+                for blk in vectors.blocks.iter().skip(1) {
                     _t = self.write_block(graph_obs, blk - 1)?;
                 }
             }
         }
+        let blocks_end = graph_obs.written_bits;
+        let mut intervals_end = blocks_end;
 
         // Finally, we write the extra list
         if extra_count > 0 {
@@ -1213,10 +1489,10 @@ impl<
             if self.out_min_interval_len != 0 {
                 // If we are to produce intervals, we first compute them
                 let interval_count = self.intervalize(
-                    &self.compression_vectors.extras.borrow(), 
-                    &mut self.compression_vectors.left.borrow_mut(), 
-                    &mut self.compression_vectors.len.borrow_mut(), 
-                    &mut self.compression_vectors.residuals.borrow_mut()
+                    &vectors.extras, 
+                    &mut vectors.left, 
+                    &mut vectors.len, 
+                    &mut vectors.residuals
                 );
 
                 _t = GammaCode::write_next(graph_obs, interval_count as u64, self.out_zeta_k) as usize;
@@ -1225,25 +1501,26 @@ impl<
 
                 for i in 0..interval_count {
                     if i == 0 {
-                        prev = self.compression_vectors.left.borrow()[i];
-                        _t = OutIntervalCoding::write_next(graph_obs, int2nat(prev as i64 - curr_node as i64), self.out_zeta_k) as usize;
+                        prev = vectors.left[i];
+                        _t = self.out_interval_coding.write_next(graph_obs, int2nat(prev as i64 - curr_node as i64), self.out_zeta_k) as usize;
                     } else {
-                        _t = OutIntervalCoding::write_next(graph_obs, (self.compression_vectors.left.borrow()[i] - prev - 1) as u64, self.out_zeta_k) as usize;
+                        _t = self.out_interval_coding.write_next(graph_obs, (vectors.left[i] - prev - 1) as u64, self.out_zeta_k) as usize;
                     }
                     
-                    curr_int_len = self.compression_vectors.len.borrow()[i];
+                    curr_int_len = vectors.len[i];
                     
-                    prev = self.compression_vectors.left.borrow()[i] + curr_int_len;
+                    prev = vectors.left[i] + curr_int_len;
                     
-                    _t = OutIntervalCoding::write_next(graph_obs, (curr_int_len - self.out_min_interval_len) as u64, self.out_zeta_k) as usize;
+                    _t = self.out_interval_coding.write_next(graph_obs, (curr_int_len - self.out_min_interval_len) as u64, self.out_zeta_k) as usize;
                 }
                 
-                residual_count = self.compression_vectors.residuals.borrow().len();
-                residual = self.compression_vectors.residuals.borrow();
+                residual_count = vectors.residuals.len();
+                residual = &vectors.residuals;
             } else {
-                residual_count = self.compression_vectors.extras.borrow().len();
-                residual = self.compression_vectors.extras.borrow();
+                residual_count = vectors.extras.len();
+                residual = &vectors.extras;
             }
+            intervals_end = graph_obs.written_bits;
 
             // Now we write out the residuals, if any
             if residual_count != 0 {
@@ -1260,6 +1537,8 @@ impl<
             }
         }
 
+        vectors.section_ends = [reference_end, blocks_end, intervals_end];
+
         Ok(graph_obs.written_bits /* graph_obs.len() */ - written_data_at_start)
     }
 
@@ -1269,199 +1548,258 @@ impl<
             return Err("The required reference is incompatible with the window size".to_string());
         }
 
-        OutReferenceCoding::write_next(graph_obs, reference as u64, self.out_zeta_k);
+        self.out_reference_coding.write_next(graph_obs, reference as u64, self.out_zeta_k);
         Ok(reference)
     }
 
     #[inline(always)]
     fn write_outdegree(&self, graph_obs: &mut BinaryWriter, outdegree: usize) -> Result<usize, String> {
-        OutOutdegreeCoding::write_next(graph_obs, outdegree as u64, self.out_zeta_k);
+        self.out_outdegree_coding.write_next(graph_obs, outdegree as u64, self.out_zeta_k);
         Ok(outdegree)
     }
 
     #[inline(always)]
     fn write_block_count(&self, graph_obs: &mut BinaryWriter, block_count: usize) -> Result<usize, String> {
-        OutBlockCountCoding::write_next(graph_obs, block_count as u64, self.out_zeta_k);
+        self.out_block_count_coding.write_next(graph_obs, block_count as u64, self.out_zeta_k);
         Ok(block_count)
     }
 
     #[inline(always)]
     fn write_block(&self, graph_obs: &mut BinaryWriter, block: usize) -> Result<usize, String> {
-        OutBlockCoding::write_next(graph_obs, block as u64, self.out_zeta_k);
+        self.out_block_coding.write_next(graph_obs, block as u64, self.out_zeta_k);
         Ok(block)
     }
 
     #[inline(always)]
     fn write_residual(&self, graph_obs: &mut BinaryWriter, residual: usize) -> Result<usize, String> {
-        OutResidualCoding::write_next(graph_obs, residual as u64, self.out_zeta_k);
+        self.out_residual_coding.write_next(graph_obs, residual as u64, self.out_zeta_k);
         Ok(residual)
     }
 
     #[inline(always)]
     fn write_offset(&self, offset_obs: &mut BinaryWriter, offset: usize) -> Result<usize, String> {
-        OutOffsetCoding::write_next(offset_obs, offset as u64, self.out_zeta_k);
+        self.out_offset_coding.write_next(offset_obs, offset as u64, self.out_zeta_k);
         Ok(offset)
     }
+}
 
-    pub fn store_plain<T>(&self, plain_graph: &AsciiGraph<T>, basename: &str) -> std::io::Result<()>  
-    where T: 
-        num_traits::Num
-        + PartialOrd 
-        + num_traits::ToPrimitive
-        + serde::Serialize
-        + Copy
-    {
-        let mut graph_obs = BinaryWriter::new();
-        let mut offsets_obs = BinaryWriter::new();
+impl<O: Offsets> BVGraph<O> {
+    /// Returns a new cursor for random access on the graph.
+    /// 
+    /// Each thread querying the graph should use its own cursor.
+    pub fn reader(&self) -> BVGraphReader<'_, O> {
+        BVGraphReader {
+            graph: self,
+            ibs: BinaryReader::new(self.graph_memory.clone()),
+            cached_outdegree: None,
+            buffers: DecodeBuffers::default(),
+        }
+    }
 
-        self.compress_plain(plain_graph, &mut graph_obs, &mut offsets_obs);
-        
-        let graph = graph_obs.build();
-        let offsets = offsets_obs.build();
-        let props = Properties {
-            nodes: self.n,
-            arcs: self.m,
-            window_size: self.out_window_size,
-            max_ref_count: self.out_max_ref_count,
-            min_interval_len: self.out_min_interval_len,
-            zeta_k: self.out_zeta_k,
-            outdegree_coding: OutOutdegreeCoding::to_encoding_type(),
-            block_coding: OutBlockCoding::to_encoding_type(),
-            interval_coding: OutIntervalCoding::to_encoding_type(),
-            residual_coding: OutResidualCoding::to_encoding_type(),
-            reference_coding: OutReferenceCoding::to_encoding_type(),
-            block_count_coding: OutBlockCountCoding::to_encoding_type(),
-            offset_coding: OutOffsetCoding::to_encoding_type()
-        };
+    /// Returns an iterator over the nodes of a range, each paired with its successors, starting at its first node.
+    /// 
+    /// The lists preceding the range within the window are decoded by random access, so that the lists
+    /// of the range can refer to them as in a scan starting from node 0.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `nodes` - The range of nodes to iterate on
+    pub fn iter_range(&self, nodes: Range<usize>) -> impl Iterator<Item = (usize, Box<[usize]>)> + '_ {
+        let mut iter = self.iter();
+        iter.seek(self.range_start(nodes));
 
-        fs::write(format!("{}.graph", basename), graph.os).unwrap();
-        fs::write(format!("{}.offsets", basename), offsets.os).unwrap();
-        fs::write(format!("{}.properties", basename), Into::<String>::into(props))?;
+        iter.into_lists()
+    }
 
-        Ok(())
+    /// Returns an iterator over the nodes from `x` on, each paired with its successors, as by [`Self::iter_range()`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The first node of the iteration
+    pub fn iter_from(&self, x: usize) -> impl Iterator<Item = (usize, Box<[usize]>)> + '_ {
+        self.iter_range(x..self.n)
+    }
+
+    /// Splits the nodes in at most `parts` ranges of consecutive nodes taking about the same number of bits,
+    /// and returns an iterator on each of them, as by [`Self::iter_range()`].
+    /// 
+    /// The iterators can be moved to different threads if the offsets can.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `parts` - The number of ranges
+    pub fn split_iter(&self, parts: usize) -> Vec<impl Iterator<Item = (usize, Box<[usize]>)> + '_> {
+        parallel::split_nodes(self.n, parts, |x| self.offsets.get(x))
+            .into_iter()
+            .map(|nodes| self.iter_range(nodes))
+            .collect()
+    }
+
+    /// Scans the graph on the given number of threads, each mapping the nodes of one of the ranges
+    /// of [`Self::split_iter()`], paired with their successors.
+    /// 
+    /// Returns the results in the order of the ranges.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `threads` - The number of threads
+    /// * `map` - The function mapping the nodes of a range
+    pub fn par_scan<R: Send>(
+        &self, 
+        threads: usize, 
+        map: impl Fn(&mut dyn Iterator<Item = (usize, Box<[usize]>)>) -> R + Sync,
+    ) -> Vec<R> where O: Send + Sync {
+        parallel::map_ranges(self.split_iter(threads), |mut iter| map(&mut iter))
+    }
+
+    /// Returns where the iteration over a range of nodes starts, decoding the lists preceding it by random access.
+    fn range_start(&self, nodes: Range<usize>) -> RangeStart {
+        assert!(nodes.start <= nodes.end && nodes.end <= self.n, "Node range out of bounds {:?}", nodes);
+
+        RangeStart::new(nodes, self.in_window_size, |x| self.offsets.get(x), |x| self.successors(x))
+    }
+
+    /// Returns the counters of the cache of decoded lists, if the graph has one
+    /// (see [`BVGraphBuilder::set_cache_size()`] and [`Self::pin()`]).
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache.stats()
+    }
+
+    /// Pins the lists of the nodes chosen by `pinning` in the cache of decoded lists, creating it if absent,
+    /// so that they are never decoded again.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `pinning` - The lists to pin
+    pub fn pin(&mut self, pinning: Pinning) {
+        let mut decoder = BinaryReader::new(self.graph_memory.clone());
+        let mut reader = self.reader();
+        let nodes = pinning.select(
+            self.n, 
+            |x| reader.outdegree(x).unwrap(), 
+            |x| self.reference_at(x, &mut decoder),
+        );
+        let lists: Vec<_> = nodes.into_iter().map(|x| (x, self.successors(x))).collect();
+
+        self.cache.pin(lists);
+    }
+
+    /// Returns the reference of the list of `x`, or 0 if it does not refer to any list.
+    fn reference_at(&self, x: usize, decoder: &mut BinaryReader) -> usize {
+        decoder.position(self.offsets.get(x) as u64);
+        let degree = self.in_outdegree_coding.read_next(decoder, self.in_zeta_k);
+
+        if degree == 0 || self.in_window_size == 0 {
+            return 0;
+        }
+        self.in_reference_coding.read_next(decoder, self.in_zeta_k) as usize
+    }
+
+    /// Writes the successors of a given node into `successors`, replacing its content, decoding them
+    /// through the given buffers.
+    /// 
+    /// Once the buffers and `successors` have grown to the size of the lists decoded, no memory is allocated,
+    /// but for the lists added to the cache of decoded lists, if any.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The node number
+    /// * `buffers` - The buffers for the intermediate values
+    /// * `successors` - The vector receiving the successors
+    pub fn successors_into(&self, x: usize, buffers: &mut DecodeBuffers, successors: &mut Vec<usize>) {
+        assert!(x < self.n, "Node index out of range {}", x);
+        let mut decoder = BinaryReader::new(self.graph_memory.clone());
+
+        self.decode_list_at(x, &mut decoder, buffers, successors);
     }
 
+    /// Decodes the successor list of `x` into `successors`, positioning `decoder` through the offsets,
+    /// unless the list is in the cache.
     #[inline(always)]
-    fn compress_plain<T>(&self, plain_graph: &AsciiGraph<T>, graph_obs: &mut BinaryWriter, offsets_obs: &mut BinaryWriter) 
-    where T: 
-        num_traits::Num
-        + PartialOrd 
-        + num_traits::ToPrimitive
-        + serde::Serialize
-        + Copy
-    {
-        let mut bit_offset: usize = 0;
-        
-        let mut bit_count = BinaryWriter::new();
-        
-        let cyclic_buffer_size = self.out_window_size + 1;
-        // Cyclic array of previous lists
-        let mut list = vec![vec![0; 1024]; cyclic_buffer_size];
-        // The length of each list
-        let mut list_len = vec![0; cyclic_buffer_size];
-        // The depth of the references of each list
-        let mut ref_count: Vec<i32> = vec![0; cyclic_buffer_size];
-        
-        let mut node_iter = plain_graph.iter();
-        let mut idx = 0;
-        
-        while node_iter.has_next() {
-            let curr_node = node_iter.next().unwrap().to_usize().unwrap();
-            let outd = node_iter.next().unwrap().to_usize().unwrap();
-            let curr_idx = idx % cyclic_buffer_size;
-            
-            // dbg!("Curr node: {}, outdegree: {}", curr_node, outd);
-            
-            // We write the final offset to the offsets stream
-            self.write_offset(offsets_obs, graph_obs.written_bits - bit_offset).unwrap();
-            
-            bit_offset = graph_obs.written_bits;
-            
-            self.write_outdegree(graph_obs, outd).unwrap();
-            
-            if outd > list[curr_idx].len() {
-                list[curr_idx].resize(outd, 0);
-            }
+    fn decode_list_at(&self, x: usize, decoder: &mut BinaryReader, buffers: &mut DecodeBuffers, successors: &mut Vec<usize>) {
+        if self.cache.get_into(x, successors) {
+            return;
+        }
 
-            let mut successors = Vec::new();
+        decoder.position(self.offsets.get(x) as u64);
 
-            for _ in 0..outd {
-                successors.push(node_iter.next().unwrap().to_usize().unwrap());
-            }
-            
-            list[curr_idx] = successors;
-            list_len[curr_idx] = outd;            
-            
-            if outd > 0 {
-                let mut best_comp = i64::MAX;
-                let mut best_cand = -1;
-                let mut best_ref: i32 = -1;
-                let mut cand;
-                
-                ref_count[curr_idx] = -1;
+        self.decode_list_into(x, decoder, buffers, successors, |decoder, reference, reference_list, buffers| {
+            let position = decoder.get_position();
+            self.decode_list_at(reference, decoder, buffers, reference_list);
+            decoder.position(position as u64);
 
-                for r in 0..cyclic_buffer_size {
-                    cand = ((idx + cyclic_buffer_size - r) % cyclic_buffer_size) as i32;
-                    if ref_count[cand as usize] < (self.out_max_ref_count as i32) && list_len[cand as usize] != 0 {
-                        let diff_comp = 
-                            self.diff_comp(&mut bit_count, 
-                                            curr_node, 
-                                            r, 
-                                            list[cand as usize].as_slice(), 
-                                            list[curr_idx].as_slice()
-                            ).unwrap();
-                        if (diff_comp as i64) < best_comp {
-                            best_comp = diff_comp as i64;
-                            best_cand = cand;
-                            best_ref = r as i32;
-                        }
-                    }
-                }
-                                    
-                assert!(best_cand >= 0);
-                
-                ref_count[curr_idx] = ref_count[best_cand as usize] + 1;
-                self.diff_comp(
-                    graph_obs, 
-                    curr_node, 
-                    best_ref as usize, 
-                    list[best_cand as usize].as_slice(), 
-                    list[curr_idx].as_slice(),
-                ).unwrap();
-            }
+            None
+        });
+        self.cache.insert(x, successors);
+    }
+
+    /// Returns the list of successors of a given node, decoded in checked mode.
+    /// 
+    /// The reference has to fall inside the window, the copy blocks inside the reference list, and the copied
+    /// successors, the intervals and the residuals cannot exceed the outdegree; the resulting list has to be
+    /// strictly increasing and made of nodes of the graph. Any violation, as well as the end of the bitstream,
+    /// is reported as an error.
+    /// The chain of references followed to decode the list cannot be longer than the maximum reference count
+    /// of the graph.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The node number
+    pub fn try_successors(&self, x: usize) -> crate::Result<Box<[usize]>> {
+        assert!(x < self.n, "Node index out of range {}", x);
+        let mut decoder = BinaryReader::new(self.graph_memory.clone());
+
+        self.try_decode_list_at(x, &mut decoder, self.in_max_ref_count, &mut 0).map(Vec::into_boxed_slice)
+    }
+
+    /// Returns the cost of decoding the successors of a given node by random access, that is the bits read
+    /// and the number of references followed, decoding the list in checked mode as [`Self::try_successors()`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The node number
+    pub fn try_access_cost(&self, x: usize) -> crate::Result<AccessCost> {
+        assert!(x < self.n, "Node index out of range {}", x);
+        let mut decoder = BinaryReader::new(self.graph_memory.clone());
+        let mut chain_depth = 0;
+
+        self.try_decode_list_at(x, &mut decoder, self.in_max_ref_count, &mut chain_depth)?;
 
-            idx += 1;
+        Ok(AccessCost { bits: decoder.read_bits as u64, chain_depth })
+    }
+
+    /// Decodes the successor list of `x` in checked mode, following at most `max_ref_count` references,
+    /// whose number is added to `chain_depth`.
+    fn try_decode_list_at(&self, x: usize, decoder: &mut BinaryReader, max_ref_count: usize, chain_depth: &mut usize) -> crate::Result<Vec<usize>> {
+        let offset = self.offsets.get(x);
+        if offset > self.graph_memory.len() * 8 {
+            return Err(Error::Malformed(format!("node {}: offset {} past the end of the graph", x, offset)));
         }
+        decoder.position(offset as u64);
+
+        self.try_decode_list_with(x, decoder, |decoder, reference| {
+            if max_ref_count == 0 {
+                return Err(Error::Malformed(format!("node {}: reference chain longer than {}", x, self.in_max_ref_count)));
+            }
 
-        self.write_offset(offsets_obs, graph_obs.written_bits - bit_offset).unwrap();
+            *chain_depth += 1;
+            let position = decoder.get_position();
+            let reference_list = self.try_decode_list_at(reference, decoder, max_ref_count - 1, chain_depth)?;
+            decoder.position(position as u64);
+
+            Ok(Cow::Owned(reference_list))
+        })
     }
 }
 
-pub struct BVGraphBuilder<
-    InBlockCoding: UniversalCode,
-    InBlockCountCoding: UniversalCode,
-    InOutdegreeCoding: UniversalCode,
-    InOffsetCoding: UniversalCode,
-    InReferenceCoding: UniversalCode,
-    InIntervalCoding: UniversalCode,
-    InResidualCoding: UniversalCode,
-    OutBlockCoding: UniversalCode,
-    OutBlockCountCoding: UniversalCode,
-    OutOutdegreeCoding: UniversalCode,
-    OutOffsetCoding: UniversalCode,
-    OutReferenceCoding: UniversalCode,
-    OutIntervalCoding: UniversalCode,
-    OutResidualCoding: UniversalCode,
-> {
+/// A builder for [`BVGraph`].
+/// 
+/// The type parameter holds the loaded offsets, which are absent (`()`) until [`Self::load_offsets()`] is called.
+pub struct BVGraphBuilder<O = ()> {
     num_nodes: usize,
     num_edges: usize,
-    loaded_graph: Rc<[u8]>,
-    loaded_offsets: Box<[usize]>,
-    graph_binary_wrapper: BinaryReader,
-    outdegrees_binary_wrapper: BinaryReader,
-    cached_node: Option<usize>,
-    cached_outdegree: Option<usize>,
-    cached_ptr: Option<usize>,
+    loaded_graph: InputStream,
+    loaded_offsets: O,
     in_max_ref_count: usize,
     in_window_size: usize,
     in_min_interval_len: usize,
@@ -1471,64 +1809,35 @@ pub struct BVGraphBuilder<
     in_zeta_k: Option<u64>,
     out_zeta_k: Option<u64>,
     elias_fano: bool,
-    _phantom_in_block_coding: PhantomData<InBlockCoding>,
-    _phantom_in_block_count_coding: PhantomData<InBlockCountCoding>,
-    _phantom_in_outdegree_coding: PhantomData<InOutdegreeCoding>,
-    _phantom_in_offset_coding: PhantomData<InOffsetCoding>,
-    _phantom_in_reference_coding: PhantomData<InReferenceCoding>,
-    _phantom_in_interval_coding: PhantomData<InIntervalCoding>,
-    _phantom_in_residual_coding: PhantomData<InResidualCoding>,
-    _phantom_out_block_coding: PhantomData<OutBlockCoding>,
-    _phantom_out_block_count_coding: PhantomData<OutBlockCountCoding>,
-    _phantom_out_outdegree_coding: PhantomData<OutOutdegreeCoding>,
-    _phantom_out_offset_coding: PhantomData<OutOffsetCoding>,
-    _phantom_out_reference_coding: PhantomData<OutReferenceCoding>,
-    _phantom_out_interval_coding: PhantomData<OutIntervalCoding>,
-    _phantom_out_residual_coding: PhantomData<OutResidualCoding>,
+    container: bool,
+    stats: bool,
+    threads: usize,
+    sync_interval: usize,
+    cache_size: usize,
+    mmap: bool,
+    in_block_coding: Code,
+    in_block_count_coding: Code,
+    in_outdegree_coding: Code,
+    in_offset_coding: Code,
+    in_reference_coding: Code,
+    in_interval_coding: Code,
+    in_residual_coding: Code,
+    out_block_coding: Code,
+    out_block_count_coding: Code,
+    out_outdegree_coding: Code,
+    out_offset_coding: Code,
+    out_reference_coding: Code,
+    out_interval_coding: Code,
+    out_residual_coding: Code,
 }
 
-impl<
-    InBlockCoding: UniversalCode,
-    InBlockCountCoding: UniversalCode,
-    InOutdegreeCoding: UniversalCode,
-    InOffsetCoding: UniversalCode,
-    InReferenceCoding: UniversalCode,
-    InIntervalCoding: UniversalCode,
-    InResidualCoding: UniversalCode,
-    OutBlockCoding: UniversalCode,
-    OutBlockCountCoding: UniversalCode,
-    OutOutdegreeCoding: UniversalCode,
-    OutOffsetCoding: UniversalCode,
-    OutReferenceCoding: UniversalCode,
-    OutIntervalCoding: UniversalCode,
-    OutResidualCoding: UniversalCode,
-> Default for BVGraphBuilder<
-    InBlockCoding,
-    InBlockCountCoding,
-    InOutdegreeCoding,
-    InOffsetCoding,
-    InReferenceCoding,
-    InIntervalCoding,
-    InResidualCoding,
-    OutBlockCoding,
-    OutBlockCountCoding,
-    OutOutdegreeCoding,
-    OutOffsetCoding,
-    OutReferenceCoding,
-    OutIntervalCoding,
-    OutResidualCoding,
-> {
+impl Default for BVGraphBuilder {
     fn default() -> Self {
         Self { 
             num_nodes: 0, 
             num_edges: 0, 
-            loaded_graph: Rc::new([]), 
-            loaded_offsets: Box::default(), 
-            graph_binary_wrapper: BinaryReader::default(),
-            outdegrees_binary_wrapper: BinaryReader::default(),
-            cached_node: None, 
-            cached_outdegree: None, 
-            cached_ptr: None, 
+            loaded_graph: InputStream::default(), 
+            loaded_offsets: (), 
             in_max_ref_count: 0, 
             in_window_size: 0, 
             in_min_interval_len: 0,
@@ -1538,62 +1847,37 @@ impl<
             in_zeta_k: None,
             out_zeta_k: Some(3),
             elias_fano: false,
-            _phantom_in_block_coding: PhantomData,
-            _phantom_in_block_count_coding: PhantomData,
-            _phantom_in_outdegree_coding: PhantomData,
-            _phantom_in_offset_coding: PhantomData,
-            _phantom_in_reference_coding: PhantomData,
-            _phantom_in_interval_coding: PhantomData,
-            _phantom_in_residual_coding: PhantomData,
-            _phantom_out_block_coding: PhantomData,
-            _phantom_out_block_count_coding: PhantomData,
-            _phantom_out_outdegree_coding: PhantomData,
-            _phantom_out_offset_coding: PhantomData,
-            _phantom_out_reference_coding: PhantomData,
-            _phantom_out_interval_coding: PhantomData,
-            _phantom_out_residual_coding: PhantomData,
+            container: false,
+            stats: false,
+            threads: 1,
+            sync_interval: 0,
+            cache_size: 0,
+            mmap: false,
+            in_block_coding: Code::Gamma,
+            in_block_count_coding: Code::Gamma,
+            in_outdegree_coding: Code::Gamma,
+            in_offset_coding: Code::Gamma,
+            in_reference_coding: Code::Unary,
+            in_interval_coding: Code::Gamma,
+            in_residual_coding: Code::Zeta,
+            out_block_coding: Code::Gamma,
+            out_block_count_coding: Code::Gamma,
+            out_outdegree_coding: Code::Gamma,
+            out_offset_coding: Code::Gamma,
+            out_reference_coding: Code::Unary,
+            out_interval_coding: Code::Gamma,
+            out_residual_coding: Code::Zeta,
         }
     }
 }
 
-impl<
-    InBlockCoding: UniversalCode,
-    InBlockCountCoding: UniversalCode,
-    InOutdegreeCoding: UniversalCode,
-    InOffsetCoding: UniversalCode,
-    InReferenceCoding: UniversalCode,
-    InIntervalCoding: UniversalCode,
-    InResidualCoding: UniversalCode,
-    OutBlockCoding: UniversalCode,
-    OutBlockCountCoding: UniversalCode,
-    OutOutdegreeCoding: UniversalCode,
-    OutOffsetCoding: UniversalCode,
-    OutReferenceCoding: UniversalCode,
-    OutIntervalCoding: UniversalCode,
-    OutResidualCoding: UniversalCode,
-> BVGraphBuilder<
-    InBlockCoding,
-    InBlockCountCoding,
-    InOutdegreeCoding,
-    InOffsetCoding,
-    InReferenceCoding,
-    InIntervalCoding,
-    InResidualCoding,
-    OutBlockCoding,
-    OutBlockCountCoding,
-    OutOutdegreeCoding,
-    OutOffsetCoding,
-    OutReferenceCoding,
-    OutIntervalCoding,
-    OutResidualCoding,
-> {
-    pub fn new() -> BVGraphBuilder<
-        InBlockCoding, InBlockCountCoding, InOutdegreeCoding, InOffsetCoding, InReferenceCoding, InIntervalCoding, InResidualCoding, 
-        OutBlockCoding, OutBlockCountCoding, OutOutdegreeCoding, OutOffsetCoding, OutReferenceCoding, OutIntervalCoding, OutResidualCoding
-    > {
+impl BVGraphBuilder {
+    pub fn new() -> BVGraphBuilder {
         Self::default()
     }
+}
 
+impl<O> BVGraphBuilder<O> {
     /// Loads a previously-compressed BVGraph.
     /// 
     /// This method can be called either before or after [`Self::load_offsets()`].
@@ -1605,20 +1889,23 @@ impl<
     /// # Examples
     /// ```
     /// let file_base_name = "graph1";
-    /// let builder = BVGraphBuilder::<
-    ///     GammaCode, GammaCode, UnaryCode, DeltaCode, GammaCode, GammaCode, ZetaCode
-    ///     GammaCode, GammaCode, UnaryCode, DeltaCode, GammaCode, GammaCode, ZetaCode
-    /// >::new()
+    /// let builder = BVGraphBuilder::new()
     ///     .load_properties(file_base_name);
-    ///     .load_graph(file_base_name);
+    ///     .load_graph(file_base_name)?;
     /// ```
-    pub fn load_graph(mut self, basename: &str) -> Self {
-        let graph = fs::read(format!("{}.graph", basename)).unwrap();
+    pub fn load_graph(self, basename: &str) -> crate::Result<Self> {
+        let graph = container::load(format!("{}.graph", basename), self.mmap, FileKind::Graph)?;
 
-        let graph = graph.into_boxed_slice().into();
+        Ok(self.load_graph_from(graph))
+    }
 
-        self.loaded_graph = graph;
-        self.graph_binary_wrapper = BinaryReader::new(self.loaded_graph.clone());
+    /// Uses the given in-memory content as the compressed graph, instead of loading it from a file.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `graph` - The content of the graph file
+    pub fn load_graph_from(mut self, graph: impl Into<InputStream>) -> Self {
+        self.loaded_graph = graph.into();
 
         self
     }
@@ -1634,65 +1921,67 @@ impl<
     /// # Examples
     /// ```
     /// let file_base_name = "graph1";
-    /// let builder = BVGraphBuilder::<
-    ///     GammaCode, GammaCode, UnaryCode, DeltaCode, GammaCode, GammaCode, ZetaCode
-    ///     GammaCode, GammaCode, UnaryCode, DeltaCode, GammaCode, GammaCode, ZetaCode
-    /// >::new()
+    /// let builder = BVGraphBuilder::new()
     ///     .load_properties(file_base_name);
-    ///     .load_graph(file_base_name);
-    ///     .load_offsets(file_base_name);
+    ///     .load_graph(file_base_name)?;
+    ///     .load_offsets(file_base_name)?;
     /// let graph = builder.build();
     /// ```
-    pub fn load_offsets(mut self, basename: &str) -> Self {
-        assert!(self.num_nodes > 0, "The number of nodes has to be >0.");
-
-        let path = format!("{}.offsets", basename);
-        let ef_path = format!("{}.ef", path);
-        let ef_path = Path::new(&ef_path);
-
-        if ef_path.exists() {
-            let content = fs::read(ef_path).unwrap();
-
-            let of = EliasFano::deserialize_from(content.as_slice()).expect("Could not read Elias-Fano encoded offsets");
-
-            let offsets: Vec<usize> = of.iter(0).collect();
-
-            self.loaded_offsets = offsets.into_boxed_slice();
-        } else if Path::new(&path).exists() {
-            let offsets = fs::read(path).unwrap();
-
-            let mut curr = 0;
-
-            let mut offsets_ibs = BinaryReader::new(offsets.into());
-            
-            let mut n = self.num_nodes;
-
-            let mut increasing_offsets = Vec::with_capacity(n);
-
-            while n > 0 {
-                curr += InOffsetCoding::read_next(&mut offsets_ibs, self.in_zeta_k);
-                increasing_offsets.push(curr as usize);
-
-                n -= 1;
-            }
-
-            self.loaded_offsets = increasing_offsets.into_boxed_slice();
-        } else {
-            panic!("Could not find the offsets file");
-        }
-
-        self
+    pub fn load_offsets(self, basename: &str) -> crate::Result<BVGraphBuilder<Box<[usize]>>> {
+        self.load_offsets_as(basename)
     }
 
-    /// Creates a new binary wrapper around the previously-loaded graph.
+    /// Loads a previously-compressed BVGraph's offsets file into the offsets backend `P`.
     /// 
-    /// This wrapper will be used only for operations concerning the outdegree of nodes.
+    /// Loading into [`EliasFano`](sucds::mii_sequences::EliasFano) keeps the offsets succinct in memory, each of them being retrieved through a select query.
+    /// If the offsets are stored Elias-Fano encoded (`.offsets.ef`) they are used as they are, otherwise they are
+    /// decoded from the `.offsets` file.
+    /// 
+    /// # Arguments
     /// 
-    /// This has to be called only after [`Self::load_graph()`].
-    pub fn load_outdegrees(mut self) -> Self {
-        self.outdegrees_binary_wrapper = BinaryReader::new(self.loaded_graph.clone());
+    /// * `basename` - The base name of the compressed graph file
+    pub fn load_offsets_as<P: Offsets>(self, basename: &str) -> crate::Result<BVGraphBuilder<P>> {
+        assert!(self.num_nodes > 0, "The number of nodes has to be >0.");
+
+        let loaded_offsets = load_offsets(basename, self.num_nodes, &self.loaded_graph, self.mmap, |offsets_ibs| {
+            self.in_offset_coding.try_read_next(offsets_ibs, self.in_zeta_k)
+        })?;
 
-        self 
+        Ok(BVGraphBuilder {
+            num_nodes: self.num_nodes,
+            num_edges: self.num_edges,
+            loaded_graph: self.loaded_graph,
+            loaded_offsets,
+            in_max_ref_count: self.in_max_ref_count,
+            in_window_size: self.in_window_size,
+            in_min_interval_len: self.in_min_interval_len,
+            out_max_ref_count: self.out_max_ref_count,
+            out_window_size: self.out_window_size,
+            out_min_interval_len: self.out_min_interval_len,
+            in_zeta_k: self.in_zeta_k,
+            out_zeta_k: self.out_zeta_k,
+            elias_fano: self.elias_fano,
+            container: self.container,
+            stats: self.stats,
+            threads: self.threads,
+            sync_interval: self.sync_interval,
+            cache_size: self.cache_size,
+            mmap: self.mmap,
+            in_block_coding: self.in_block_coding,
+            in_block_count_coding: self.in_block_count_coding,
+            in_outdegree_coding: self.in_outdegree_coding,
+            in_offset_coding: self.in_offset_coding,
+            in_reference_coding: self.in_reference_coding,
+            in_interval_coding: self.in_interval_coding,
+            in_residual_coding: self.in_residual_coding,
+            out_block_coding: self.out_block_coding,
+            out_block_count_coding: self.out_block_count_coding,
+            out_outdegree_coding: self.out_outdegree_coding,
+            out_offset_coding: self.out_offset_coding,
+            out_reference_coding: self.out_reference_coding,
+            out_interval_coding: self.out_interval_coding,
+            out_residual_coding: self.out_residual_coding,
+        })
     }
 
     /// Sets the maximum reference chain length for reading.
@@ -1816,36 +2105,249 @@ impl<
         self
     }
 
+    /// Sets whether the written files have to be preceded by a container header, which identifies them
+    /// and records their checksums (see [`container`]).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `flag` - Flag specifying whether to write the files in containers.
+    pub fn set_container(mut self, flag: bool) -> Self {
+        self.container = flag;
+
+        self
+    }
+
+    /// Sets whether a `.stats` file has to be written together with the graph, reporting where
+    /// the bits of the compressed graph go (see [`CompressionStats`]).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `flag` - Flag specifying whether to write the statistics.
+    pub fn set_stats(mut self, flag: bool) -> Self {
+        self.stats = flag;
+
+        self
+    }
+
+    /// Sets the number of threads compressing the graph.
+    /// 
+    /// With more than one thread, the graph is split in chunks of [`CHUNK_SIZE`](super::parallel::CHUNK_SIZE)
+    /// nodes that are compressed in parallel and then concatenated. Each chunk starts with an empty window,
+    /// so its lists only refer to lists of the same chunk: the output does not depend on the number of threads,
+    /// but its size differs slightly from the one written by a single thread.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `threads` - The number of threads
+    pub fn set_threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+
+        self
+    }
+
+    /// Sets the interval of the sync points written together with the graph, or 0 not to write them.
+    /// 
+    /// With a positive interval `k`, the graph is compressed in chunks of `k` nodes, as by [`Self::set_threads()`],
+    /// so that a scan can start at any multiple of `k` with an empty window, and the offsets of these nodes
+    /// are written to a `.sync` file (see [`SyncPoints`]).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `interval` - The number of nodes between two sync points
+    pub fn set_sync_interval(mut self, interval: usize) -> Self {
+        self.sync_interval = interval;
+
+        self
+    }
+
+    /// Sets the number of decoded successor lists cached for random access, or 0 not to cache them.
+    /// 
+    /// The cache is consulted for every list decoded by random access, including the lists referred to by other
+    /// lists, and it evicts them with the CLOCK policy. Its counters are given by [`BVGraph::cache_stats()`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `capacity` - The maximum number of cached lists
+    pub fn set_cache_size(mut self, capacity: usize) -> Self {
+        self.cache_size = capacity;
+
+        self
+    }
+
+    /// Sets whether the graph and offsets files have to be memory-mapped instead of read in memory.
+    /// 
+    /// This has to be called before [`Self::load_graph()`] and [`Self::load_offsets()`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `flag` - Flag specifying whether to memory-map the files.
+    pub fn set_mmap(mut self, flag: bool) -> Self {
+        self.mmap = flag;
+
+        self
+    }
+
+    /// Sets the code used for the copy blocks when reading.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `code` - The universal code to use.
+    pub fn set_in_block_coding(mut self, code: Code) -> Self {
+        self.in_block_coding = code;
+
+        self
+    }
+
+    /// Sets the code used for the number of copy blocks when reading.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `code` - The universal code to use.
+    pub fn set_in_block_count_coding(mut self, code: Code) -> Self {
+        self.in_block_count_coding = code;
+
+        self
+    }
+
+    /// Sets the code used for the outdegrees when reading.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `code` - The universal code to use.
+    pub fn set_in_outdegree_coding(mut self, code: Code) -> Self {
+        self.in_outdegree_coding = code;
+
+        self
+    }
+
+    /// Sets the code used for the offsets when reading.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `code` - The universal code to use.
+    pub fn set_in_offset_coding(mut self, code: Code) -> Self {
+        self.in_offset_coding = code;
+
+        self
+    }
+
+    /// Sets the code used for the references when reading.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `code` - The universal code to use.
+    pub fn set_in_reference_coding(mut self, code: Code) -> Self {
+        self.in_reference_coding = code;
+
+        self
+    }
+
+    /// Sets the code used for the intervals when reading.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `code` - The universal code to use.
+    pub fn set_in_interval_coding(mut self, code: Code) -> Self {
+        self.in_interval_coding = code;
+
+        self
+    }
+
+    /// Sets the code used for the residuals when reading.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `code` - The universal code to use.
+    pub fn set_in_residual_coding(mut self, code: Code) -> Self {
+        self.in_residual_coding = code;
+
+        self
+    }
+
+    /// Sets the code used for the copy blocks when writing.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `code` - The universal code to use.
+    pub fn set_out_block_coding(mut self, code: Code) -> Self {
+        self.out_block_coding = code;
+
+        self
+    }
+
+    /// Sets the code used for the number of copy blocks when writing.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `code` - The universal code to use.
+    pub fn set_out_block_count_coding(mut self, code: Code) -> Self {
+        self.out_block_count_coding = code;
+
+        self
+    }
+
+    /// Sets the code used for the outdegrees when writing.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `code` - The universal code to use.
+    pub fn set_out_outdegree_coding(mut self, code: Code) -> Self {
+        self.out_outdegree_coding = code;
+
+        self
+    }
+
+    /// Sets the code used for the offsets when writing.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `code` - The universal code to use.
+    pub fn set_out_offset_coding(mut self, code: Code) -> Self {
+        self.out_offset_coding = code;
+
+        self
+    }
+
+    /// Sets the code used for the references when writing.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `code` - The universal code to use.
+    pub fn set_out_reference_coding(mut self, code: Code) -> Self {
+        self.out_reference_coding = code;
+
+        self
+    }
+
+    /// Sets the code used for the intervals when writing.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `code` - The universal code to use.
+    pub fn set_out_interval_coding(mut self, code: Code) -> Self {
+        self.out_interval_coding = code;
+
+        self
+    }
+
+    /// Sets the code used for the residuals when writing.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `code` - The universal code to use.
+    pub fn set_out_residual_coding(mut self, code: Code) -> Self {
+        self.out_residual_coding = code;
+
+        self
+    }
+
     /// Constructs the BVGraph object.
-    pub fn build(self) -> BVGraph<
-        InBlockCoding,
-        InBlockCountCoding,
-        InOutdegreeCoding,
-        InOffsetCoding,
-        InReferenceCoding,
-        InIntervalCoding,
-        InResidualCoding,
-        OutBlockCoding,
-        OutBlockCountCoding,
-        OutOutdegreeCoding,
-        OutOffsetCoding,
-        OutReferenceCoding,
-        OutIntervalCoding,
-        OutResidualCoding,
-    > {
-        BVGraph::<
-            InBlockCoding, InBlockCountCoding, InOutdegreeCoding, InOffsetCoding, InReferenceCoding, InIntervalCoding, InResidualCoding, 
-            OutBlockCoding, OutBlockCountCoding, OutOutdegreeCoding, OutOffsetCoding, OutReferenceCoding, OutIntervalCoding, OutResidualCoding
-        > { 
+    pub fn build(self) -> BVGraph<O> {
+        BVGraph { 
             n: self.num_nodes, 
             m: self.num_edges, 
             graph_memory: self.loaded_graph, 
             offsets: self.loaded_offsets,
-            graph_binary_wrapper: RefCell::new(self.graph_binary_wrapper),
-            outdegrees_binary_wrapper: RefCell::new(self.outdegrees_binary_wrapper),
-            cached_node: Cell::new(self.cached_node), 
-            cached_outdegree: Cell::new(self.cached_outdegree), 
-            cached_ptr: Cell::new(self.cached_ptr), 
             in_max_ref_count: self.in_max_ref_count, 
             in_window_size: self.in_window_size,
             in_min_interval_len: self.in_min_interval_len,
@@ -1855,21 +2357,24 @@ impl<
             in_zeta_k: self.in_zeta_k,
             out_zeta_k: self.out_zeta_k,
             elias_fano: self.elias_fano,
-            compression_vectors: CompressionVectors::default(),
-            _phantom_in_block_coding: PhantomData,
-            _phantom_in_block_count_coding: PhantomData,
-            _phantom_in_outdegree_coding: PhantomData,
-            _phantom_in_offset_coding: PhantomData,
-            _phantom_in_reference_coding: PhantomData,
-            _phantom_in_interval_coding: PhantomData,
-            _phantom_in_residual_coding: PhantomData,
-            _phantom_out_block_coding: PhantomData,
-            _phantom_out_block_count_coding: PhantomData,
-            _phantom_out_outdegree_coding: PhantomData,
-            _phantom_out_offset_coding: PhantomData,
-            _phantom_out_reference_coding: PhantomData,
-            _phantom_out_interval_coding: PhantomData,
-            _phantom_out_residual_coding: PhantomData,
+            container: self.container,
+            stats: self.stats,
+            threads: self.threads,
+            sync_interval: self.sync_interval,
+            cache: SharedCache::new(self.cache_size),
+            in_block_coding: self.in_block_coding,
+            in_block_count_coding: self.in_block_count_coding,
+            in_outdegree_coding: self.in_outdegree_coding,
+            in_reference_coding: self.in_reference_coding,
+            in_interval_coding: self.in_interval_coding,
+            in_residual_coding: self.in_residual_coding,
+            out_block_coding: self.out_block_coding,
+            out_block_count_coding: self.out_block_count_coding,
+            out_outdegree_coding: self.out_outdegree_coding,
+            out_offset_coding: self.out_offset_coding,
+            out_reference_coding: self.out_reference_coding,
+            out_interval_coding: self.out_interval_coding,
+            out_residual_coding: self.out_residual_coding,
         }
     }
-}
\ No newline at end of file
+}
@@ -1,9 +1,14 @@
-use std::{fs, vec, cmp::Ordering, marker::PhantomData, cell::{RefCell, Cell}, rc::Rc};
+use std::{fs, vec, borrow::Cow, cmp::Ordering, marker::PhantomData, cell::{RefCell, Cell}, rc::Rc, ops::Range};
 
-use sucds::{mii_sequences::{EliasFanoBuilder, EliasFano}, Serializable};
+use crate::{Error, SequentialGraph, RandomAccessGraph, AccessCost, CheckedGraph, CheckedNodes, Store, properties::{GraphFormat, Properties}, utils::{encodings::{UniversalCode, GammaCode, Huff, Huffman, UnaryCode, ZetaCode, zuck_encode, K_ZUCK, I_ZUCK, J_ZUCK}, nat2int, int2nat}, huffman_zuckerli::huffman_decoder::HuffmanDecoder};
+use crate::bitstreams::{BinaryReader, BinaryWriter, InputStream};
 
-use crate::{ImmutableGraph, properties::Properties, utils::{encodings::{UniversalCode, GammaCode, Huffman, zuck_encode, K_ZUCK, I_ZUCK, J_ZUCK}, nat2int, int2nat}, huffman_zuckerli::huffman_decoder::HuffmanDecoder};
-use crate::bitstreams::{BinaryReader, BinaryWriter};
+use super::buffers::DecodeBuffers;
+use super::cache::{CacheStats, Pinning, SharedCache};
+use super::container::{self, FileKind};
+use super::offsets::{Offsets, load_offsets};
+use super::parallel::{self, RangeStart};
+use super::sync::SyncPoints;
 
 use super::bvgraph_huffman_out::{INTERVALS_LEN_IDX_BEGIN, INTERVALS_LEN_IDX_LEN, OUTD_IDX_BEGIN, BLOCKS_IDX_BEGIN, INTERVALS_LEFT_IDX_BEGIN, RESIDUALS_IDX_BEGIN, NUM_CONTEXTS};
 
@@ -16,7 +21,7 @@ struct CompressionVectors {
     residuals: RefCell<Vec<usize>>,
 }
 
-#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Clone, Debug)]
 pub struct BVGraph<
     InBlockCoding: Huffman,
     InBlockCountCoding: UniversalCode,
@@ -32,16 +37,19 @@ pub struct BVGraph<
     OutReferenceCoding: UniversalCode,
     OutIntervalCoding: UniversalCode,
     OutResidualCoding: UniversalCode,
+    O: Offsets = Box<[usize]>,
 > {
     n: usize,
     m: usize,
-    pub graph_memory: Rc<[u8]>,
-    pub offsets: Box<[usize]>,
+    pub graph_memory: InputStream,
+    pub offsets: O,
+    huffman_decoder: HuffmanDecoder,
     graph_binary_wrapper: Rc<RefCell<BinaryReader>>,
     outdegrees_binary_wrapper: RefCell<BinaryReader>,
     cached_node: Cell<Option<usize>>,
     cached_outdegree: Cell<Option<usize>>,
     cached_ptr: Cell<Option<usize>>,
+    cache: SharedCache,
     in_max_ref_count: usize,
     in_window_size: usize,
     in_min_interval_len: usize,
@@ -82,7 +90,8 @@ impl<
     OutReferenceCoding: UniversalCode,
     OutIntervalCoding: UniversalCode,
     OutResidualCoding: UniversalCode,
-> ImmutableGraph for BVGraph<
+    O: Offsets,
+> SequentialGraph for BVGraph<
     InBlockCoding,
     InBlockCountCoding,
     InOutdegreeCoding,
@@ -97,6 +106,7 @@ impl<
     OutReferenceCoding,
     OutIntervalCoding,
     OutResidualCoding,
+    O,
 >
 {
     type NodeT = usize;
@@ -113,23 +123,209 @@ impl<
         self.m
     }
 
+    /// Returns an iterator over the nodes of the BVGraph, each paired with its successors.
+    fn iter_nodes(&self) -> impl Iterator<Item = (Self::NodeT, Box<[Self::NodeT]>)> + '_ {
+        let mut iter = self.iter();
+
+        std::iter::from_fn(move || {
+            let node = iter.next()?;
+            Some((node, iter.successor_array()[..iter.outdegree()].into()))
+        })
+    }
+}
+
+impl<
+    InBlockCoding: Huffman,
+    InBlockCountCoding: UniversalCode,
+    InOutdegreeCoding: Huffman,
+    InOffsetCoding: UniversalCode,
+    InReferenceCoding: UniversalCode,
+    InIntervalCoding: Huffman,
+    InResidualCoding: Huffman,
+    OutBlockCoding: UniversalCode,
+    OutBlockCountCoding: UniversalCode,
+    OutOutdegreeCoding: UniversalCode,
+    OutOffsetCoding: UniversalCode,
+    OutReferenceCoding: UniversalCode,
+    OutIntervalCoding: UniversalCode,
+    OutResidualCoding: UniversalCode,
+    O: Offsets,
+> RandomAccessGraph for BVGraph<
+    InBlockCoding,
+    InBlockCountCoding,
+    InOutdegreeCoding,
+    InOffsetCoding,
+    InReferenceCoding,
+    InIntervalCoding,
+    InResidualCoding,
+    OutBlockCoding,
+    OutBlockCountCoding,
+    OutOutdegreeCoding,
+    OutOffsetCoding,
+    OutReferenceCoding,
+    OutIntervalCoding,
+    OutResidualCoding,
+    O,
+>
+{
+    /// Returns the outdegree of a given node or `None` otherwise.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The node number
     fn outdegree(&self, x: Self::NodeT) -> Option<usize> {
-        todo!("Impossible Huffman with this signature")
+        if x >= self.n {
+            return None;
+        }
+
+        Some(self.outdegree_internal(x, &self.huffman_decoder))
     }
 
+    /// Returns the list of successors of a given node.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The node number
     fn successors(&self, x: Self::NodeT) -> Box<[Self::NodeT]> {
-        todo!("Impossible Huffman with this signature")
+        let mut successors = Vec::new();
+        self.successors_into(x, &mut DecodeBuffers::default(), &mut successors);
+
+        successors.into_boxed_slice()
     }
 
-    fn store(&mut self, basename: &str) -> std::io::Result<()> {      
-        let mut graph_obs = BinaryWriter::new();
-        let mut offsets_obs = BinaryWriter::new();
+    /// Returns a lazy iterator over the successors of a given node.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The node number
+    fn successors_iter(&self, x: Self::NodeT) -> impl Iterator<Item = Self::NodeT> + '_ {
+        assert!(x < self.n, "Node index out of range {}", x);
+        BVGraphSuccessors::new(self, x)
+    }
+
+    /// Returns whether the arc from `x` to `y` exists.
+    /// 
+    /// The successor list is decoded only up to `y`, skipping whole intervals at once.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The source node
+    /// * `y` - The destination node
+    fn has_arc(&self, x: Self::NodeT, y: Self::NodeT) -> bool {
+        assert!(x < self.n, "Node index out of range {}", x);
+        let mut successors = BVGraphSuccessors::new(self, x);
+        successors.skip_to(y);
+        successors.next() == Some(y)
+    }
+
+    /// Returns a lazy iterator over the successors of a given node in the range `[lo, hi)`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The node number
+    /// * `lo` - The smallest successor to return
+    /// * `hi` - The first successor not to be returned
+    fn successors_in_range(&self, x: Self::NodeT, lo: Self::NodeT, hi: Self::NodeT) -> impl Iterator<Item = Self::NodeT> + '_ {
+        assert!(x < self.n, "Node index out of range {}", x);
+        let mut successors = BVGraphSuccessors::new(self, x);
+        successors.skip_to(lo);
+        successors.take_while(move |&succ| succ < hi)
+    }
+}
+
+impl<
+    InBlockCoding: Huffman,
+    InBlockCountCoding: UniversalCode,
+    InOutdegreeCoding: Huffman,
+    InOffsetCoding: UniversalCode,
+    InReferenceCoding: UniversalCode,
+    InIntervalCoding: Huffman,
+    InResidualCoding: Huffman,
+    OutBlockCoding: UniversalCode,
+    OutBlockCountCoding: UniversalCode,
+    OutOutdegreeCoding: UniversalCode,
+    OutOffsetCoding: UniversalCode,
+    OutReferenceCoding: UniversalCode,
+    OutIntervalCoding: UniversalCode,
+    OutResidualCoding: UniversalCode,
+    O: Offsets,
+> CheckedGraph for BVGraph<
+    InBlockCoding,
+    InBlockCountCoding,
+    InOutdegreeCoding,
+    InOffsetCoding,
+    InReferenceCoding,
+    InIntervalCoding,
+    InResidualCoding,
+    OutBlockCoding,
+    OutBlockCountCoding,
+    OutOutdegreeCoding,
+    OutOffsetCoding,
+    OutReferenceCoding,
+    OutIntervalCoding,
+    OutResidualCoding,
+    O,
+>
+{
+    fn try_iter_nodes(&self) -> CheckedNodes<'_> {
+        Box::new(BVGraph::try_iter_nodes(self))
+    }
+
+    fn try_successors(&self, x: usize) -> crate::Result<Box<[usize]>> {
+        BVGraph::try_successors(self, x)
+    }
+
+    fn try_access_cost(&self, x: usize) -> crate::Result<AccessCost> {
+        BVGraph::try_access_cost(self, x)
+    }
+}
+
+impl<
+    InBlockCoding: Huffman,
+    InBlockCountCoding: UniversalCode,
+    InOutdegreeCoding: Huffman,
+    InOffsetCoding: UniversalCode,
+    InReferenceCoding: UniversalCode,
+    InIntervalCoding: Huffman,
+    InResidualCoding: Huffman,
+    OutBlockCoding: UniversalCode,
+    OutBlockCountCoding: UniversalCode,
+    OutOutdegreeCoding: UniversalCode,
+    OutOffsetCoding: UniversalCode,
+    OutReferenceCoding: UniversalCode,
+    OutIntervalCoding: UniversalCode,
+    OutResidualCoding: UniversalCode,
+    O: Offsets,
+> Store for BVGraph<
+    InBlockCoding,
+    InBlockCountCoding,
+    InOutdegreeCoding,
+    InOffsetCoding,
+    InReferenceCoding,
+    InIntervalCoding,
+    InResidualCoding,
+    OutBlockCoding,
+    OutBlockCountCoding,
+    OutOutdegreeCoding,
+    OutOffsetCoding,
+    OutReferenceCoding,
+    OutIntervalCoding,
+    OutResidualCoding,
+    O,
+>
+{
+    fn store(&mut self, basename: &str) -> crate::Result<()> {      
+        let graph_path = format!("{}.graph", basename);
+        let offsets_path = format!("{}.offsets", basename);
+        let mut graph_obs = BinaryWriter::with_sink(container::create(&graph_path)?);
+        let mut offsets_obs = BinaryWriter::with_sink(container::create(&offsets_path)?);
 
         self.compress(&mut graph_obs, &mut offsets_obs);
         
-        let graph = graph_obs.build();
-        let offsets = offsets_obs.build();
+        graph_obs.finish().map_err(Error::io(&graph_path))?;
+        offsets_obs.finish().map_err(Error::io(&offsets_path))?;
         let props = Properties {
+            format: GraphFormat::BV,
             nodes: self.n,
             arcs: self.m,
             window_size: self.out_window_size,
@@ -145,9 +341,10 @@ impl<
             offset_coding: OutOffsetCoding::to_encoding_type()
         };
 
-        fs::write(format!("{}.graph", basename), graph.os).unwrap();
-        fs::write(format!("{}.offsets", basename), offsets.os).unwrap();
-        fs::write(format!("{}.properties", basename), Into::<String>::into(props))?;
+        container::seal(&graph_path, FileKind::Graph, None)?;
+        container::seal(&offsets_path, FileKind::Offsets, None)?;
+        let properties_path = format!("{}.properties", basename);
+        fs::write(&properties_path, Into::<String>::into(props)).map_err(Error::io(&properties_path))?;
 
         Ok(())
     }
@@ -168,6 +365,7 @@ pub struct BVGraphNodeIterator<
     OutReferenceCoding: UniversalCode,
     OutIntervalCoding: UniversalCode,
     OutResidualCoding: UniversalCode,
+    O: Offsets,
     BV: AsRef<BVGraph<
         InBlockCoding,
         InBlockCountCoding,
@@ -183,9 +381,10 @@ pub struct BVGraphNodeIterator<
         OutReferenceCoding,
         OutIntervalCoding,
         OutResidualCoding,
+        O,
 >>>
 {
-    // The number of nodes
+    // The node at which the iteration ends, that is the number of nodes unless iterating on a range
     n: usize,
     // The graph on which we iterate
     graph: BV,
@@ -195,10 +394,14 @@ pub struct BVGraphNodeIterator<
     pub huff_decoder: HuffmanDecoder,
     // The size of the cyclic buffer
     cyclic_buffer_size: usize,
-    // Window to be passed to [`decode_list`]
+    // Window to be passed to [`decode_list_into`]
     window: Vec<Vec<usize>>,
-    // Outdegrees of the window's lists to be passed to [`decode_list`]
+    // Outdegrees of the window's lists to be passed to [`decode_list_into`]
     outd: Vec<usize>,
+    // The buffers for decoding the lists
+    buffers: DecodeBuffers,
+    // The vector receiving the next list, which then takes the place of the oldest list of the window
+    list: Vec<usize>,
     // The index of the node from which we started iterating
     from: usize,
     // The index of the node just before the next one
@@ -217,6 +420,7 @@ pub struct BVGraphNodeIterator<
     _phantom_out_reference_coding: PhantomData<OutReferenceCoding>,
     _phantom_out_interval_coding: PhantomData<OutIntervalCoding>,
     _phantom_out_residual_coding: PhantomData<OutResidualCoding>,
+    _phantom_offsets: PhantomData<O>,
 }
 
 impl<
@@ -234,6 +438,7 @@ impl<
     OutReferenceCoding: UniversalCode,
     OutIntervalCoding: UniversalCode,
     OutResidualCoding: UniversalCode,
+    O: Offsets,
     BV: AsRef<BVGraph<
         InBlockCoding,
         InBlockCountCoding,
@@ -249,6 +454,7 @@ impl<
         OutReferenceCoding,
         OutIntervalCoding,
         OutResidualCoding,
+        O,
 >>> Iterator for BVGraphNodeIterator<
         InBlockCoding,
         InBlockCountCoding,
@@ -264,6 +470,7 @@ impl<
         OutReferenceCoding,
         OutIntervalCoding,
         OutResidualCoding,
+        O,
         BV
 > {
     type Item = usize;
@@ -275,26 +482,15 @@ impl<
 
         self.curr += 1;
         let curr_idx = self.curr as usize % self.cyclic_buffer_size;
-        let decoded_list = 
-            self.graph.as_ref().decode_list(
-                self.curr as usize, 
-                self.ibs.clone(), 
-                Some(&mut self.window), 
-                &mut self.outd,
-                &mut self.huff_decoder,
-            );
-
-        let d = self.outd[curr_idx];
-
-        if self.window[curr_idx].len() < d {
-            self.window[curr_idx] = vec![0usize; d];
-        }
-        
-        let mut i = 0; 
-        while i < d && i < decoded_list.len() {
-            self.window[curr_idx][i] = decoded_list[i];
-            i += 1;
-        }
+        self.graph.as_ref().decode_list_into(
+            self.curr as usize, 
+            self.ibs.clone(), 
+            Some((&self.window, &mut self.outd)), 
+            &self.huff_decoder,
+            &mut self.buffers,
+            &mut self.list,
+        );
+        std::mem::swap(&mut self.window[curr_idx], &mut self.list);
 
         Some(self.curr as usize)
     }
@@ -315,6 +511,7 @@ impl<
     OutReferenceCoding: UniversalCode,
     OutIntervalCoding: UniversalCode,
     OutResidualCoding: UniversalCode,
+    O: Offsets,
     BV: AsRef<BVGraph<
         InBlockCoding,
         InBlockCountCoding,
@@ -330,6 +527,7 @@ impl<
         OutReferenceCoding,
         OutIntervalCoding,
         OutResidualCoding,
+        O,
 >>> BVGraphNodeIterator<
         InBlockCoding,
         InBlockCountCoding,
@@ -345,25 +543,544 @@ impl<
         OutReferenceCoding,
         OutIntervalCoding,
         OutResidualCoding,
-        BV
-> {
-    #[inline(always)]
-    pub fn has_next(&self) -> bool {
-        self.curr < self.n as i64 - 1
-    }
-
-    #[inline(always)]
-    pub fn outdegree(&self) -> usize {
-        assert!(self.curr != self.from as i64 - 1);
-        self.outd[self.curr as usize % self.cyclic_buffer_size]
-    }
-
-    #[inline(always)]
-    pub fn successor_array(&self) -> &[usize] {
-        assert!(self.curr != self.from as i64 - 1);
-        self.window[self.curr as usize % self.cyclic_buffer_size].as_slice()
-    }
-}
+        O,
+        BV
+> {
+    #[inline(always)]
+    pub fn has_next(&self) -> bool {
+        self.curr < self.n as i64 - 1
+    }
+
+    #[inline(always)]
+    pub fn outdegree(&self) -> usize {
+        assert!(self.curr != self.from as i64 - 1);
+        self.outd[self.curr as usize % self.cyclic_buffer_size]
+    }
+
+    #[inline(always)]
+    pub fn successor_array(&self) -> &[usize] {
+        assert!(self.curr != self.from as i64 - 1);
+        self.window[self.curr as usize % self.cyclic_buffer_size].as_slice()
+    }
+
+    /// Moves the iterator to the start of a range of nodes, filling the window with the lists preceding it.
+    pub(crate) fn seek(&mut self, start: RangeStart) {
+        self.ibs.borrow_mut().position(start.position as u64);
+
+        let first = start.nodes.start - start.window.len();
+        for (i, list) in start.window.into_iter().enumerate() {
+            let index = (first + i) % self.cyclic_buffer_size;
+            self.outd[index] = list.len();
+            self.window[index] = list.into_vec();
+        }
+
+        self.from = start.nodes.start;
+        self.curr = start.nodes.start as i64 - 1;
+        self.n = start.nodes.end;
+    }
+
+    /// Turns the iterator into one over the nodes, each paired with its successors.
+    pub(crate) fn into_lists(mut self) -> impl Iterator<Item = (usize, Box<[usize]>)> {
+        std::iter::from_fn(move || {
+            let node = self.next()?;
+            Some((node, self.successor_array()[..self.outdegree()].into()))
+        })
+    }
+}
+
+/// A lazy iterator over the successors of a node of a [`BVGraph`].
+/// 
+/// The successors copied from the reference list, the ones belonging to intervals and the residuals
+/// are merged on the fly, so that the residuals and the reference list are decoded only as far as needed.
+pub struct BVGraphSuccessors<
+    InBlockCoding: Huffman,
+    InBlockCountCoding: UniversalCode,
+    InOutdegreeCoding: Huffman,
+    InOffsetCoding: UniversalCode,
+    InReferenceCoding: UniversalCode,
+    InIntervalCoding: Huffman,
+    InResidualCoding: Huffman,
+    OutBlockCoding: UniversalCode,
+    OutBlockCountCoding: UniversalCode,
+    OutOutdegreeCoding: UniversalCode,
+    OutOffsetCoding: UniversalCode,
+    OutReferenceCoding: UniversalCode,
+    OutIntervalCoding: UniversalCode,
+    OutResidualCoding: UniversalCode,
+    O: Offsets,
+    BV: AsRef<BVGraph<
+        InBlockCoding,
+        InBlockCountCoding,
+        InOutdegreeCoding,
+        InOffsetCoding,
+        InReferenceCoding,
+        InIntervalCoding,
+        InResidualCoding,
+        OutBlockCoding,
+        OutBlockCountCoding,
+        OutOutdegreeCoding,
+        OutOffsetCoding,
+        OutReferenceCoding,
+        OutIntervalCoding,
+        OutResidualCoding,
+        O,
+>> + Clone,
+> {
+    // The graph on which we iterate
+    graph: BV,
+    // The input bit stream, positioned at the next residual
+    ibs: BinaryReader,
+    // The node whose successors are returned
+    x: usize,
+    // The number of successors not returned yet
+    remaining: usize,
+    // The successors of the reference node, if any
+    reference: Option<Box<Self>>,
+    // The copy blocks, alternately copying and skipping successors of the reference
+    blocks: Vec<usize>,
+    // The index of the current copy block
+    curr_block: usize,
+    // The successors of the reference left in the current copy block
+    block_left: usize,
+    // The intervals, as pairs of left extreme and length
+    intervals: Vec<(usize, usize)>,
+    // The index of the current interval
+    curr_interval: usize,
+    // The index of the next successor inside the current interval
+    interval_idx: usize,
+    // The number of residuals
+    residual_count: usize,
+    // The number of residuals not decoded yet
+    residuals_left: usize,
+    // The last decoded residual gap, used for selecting the context of the next one
+    prev_residual: usize,
+    // The next successor copied from the reference and the next residual
+    next_copied: Option<usize>,
+    next_residual: Option<usize>,
+    _phantom_in_block_coding: PhantomData<InBlockCoding>,
+    _phantom_in_block_count_coding: PhantomData<InBlockCountCoding>,
+    _phantom_in_outdegree_coding: PhantomData<InOutdegreeCoding>,
+    _phantom_in_offset_coding: PhantomData<InOffsetCoding>,
+    _phantom_in_reference_coding: PhantomData<InReferenceCoding>,
+    _phantom_in_interval_coding: PhantomData<InIntervalCoding>,
+    _phantom_in_residual_coding: PhantomData<InResidualCoding>,
+    _phantom_out_block_coding: PhantomData<OutBlockCoding>,
+    _phantom_out_block_count_coding: PhantomData<OutBlockCountCoding>,
+    _phantom_out_outdegree_coding: PhantomData<OutOutdegreeCoding>,
+    _phantom_out_offset_coding: PhantomData<OutOffsetCoding>,
+    _phantom_out_reference_coding: PhantomData<OutReferenceCoding>,
+    _phantom_out_interval_coding: PhantomData<OutIntervalCoding>,
+    _phantom_out_residual_coding: PhantomData<OutResidualCoding>,
+    _phantom_offsets: PhantomData<O>,
+}
+
+impl<
+    InBlockCoding: Huffman,
+    InBlockCountCoding: UniversalCode,
+    InOutdegreeCoding: Huffman,
+    InOffsetCoding: UniversalCode,
+    InReferenceCoding: UniversalCode,
+    InIntervalCoding: Huffman,
+    InResidualCoding: Huffman,
+    OutBlockCoding: UniversalCode,
+    OutBlockCountCoding: UniversalCode,
+    OutOutdegreeCoding: UniversalCode,
+    OutOffsetCoding: UniversalCode,
+    OutReferenceCoding: UniversalCode,
+    OutIntervalCoding: UniversalCode,
+    OutResidualCoding: UniversalCode,
+    O: Offsets,
+    BV: AsRef<BVGraph<
+        InBlockCoding,
+        InBlockCountCoding,
+        InOutdegreeCoding,
+        InOffsetCoding,
+        InReferenceCoding,
+        InIntervalCoding,
+        InResidualCoding,
+        OutBlockCoding,
+        OutBlockCountCoding,
+        OutOutdegreeCoding,
+        OutOffsetCoding,
+        OutReferenceCoding,
+        OutIntervalCoding,
+        OutResidualCoding,
+        O,
+>> + Clone,
+> BVGraphSuccessors<
+    InBlockCoding,
+    InBlockCountCoding,
+    InOutdegreeCoding,
+    InOffsetCoding,
+    InReferenceCoding,
+    InIntervalCoding,
+    InResidualCoding,
+    OutBlockCoding,
+    OutBlockCountCoding,
+    OutOutdegreeCoding,
+    OutOffsetCoding,
+    OutReferenceCoding,
+    OutIntervalCoding,
+    OutResidualCoding,
+    O,
+    BV,
+> {
+    fn new(graph: BV, x: usize) -> Self {
+        let bvgraph = graph.as_ref();
+        let huff = &bvgraph.huffman_decoder;
+        let mut ibs = BinaryReader::new(bvgraph.graph_memory.clone());
+        ibs.position(bvgraph.offsets.get(x) as u64);
+
+        let ctx =
+            if x.is_multiple_of(32) {
+                0
+            } else {
+                1 + zuck_encode((x % 32) + 1, K_ZUCK, I_ZUCK, J_ZUCK).0.min(30)
+            };
+        let degree = huff.read_next(&mut ibs, OUTD_IDX_BEGIN + ctx);
+
+        let mut successors = BVGraphSuccessors {
+            graph: graph.clone(),
+            ibs,
+            x,
+            remaining: degree,
+            reference: None,
+            blocks: Vec::new(),
+            curr_block: 0,
+            block_left: 0,
+            intervals: Vec::new(),
+            curr_interval: 0,
+            interval_idx: 0,
+            residual_count: 0,
+            residuals_left: 0,
+            prev_residual: 0,
+            next_copied: None,
+            next_residual: None,
+            _phantom_in_block_coding: PhantomData,
+            _phantom_in_block_count_coding: PhantomData,
+            _phantom_in_outdegree_coding: PhantomData,
+            _phantom_in_offset_coding: PhantomData,
+            _phantom_in_reference_coding: PhantomData,
+            _phantom_in_interval_coding: PhantomData,
+            _phantom_in_residual_coding: PhantomData,
+            _phantom_out_block_coding: PhantomData,
+            _phantom_out_block_count_coding: PhantomData,
+            _phantom_out_outdegree_coding: PhantomData,
+            _phantom_out_offset_coding: PhantomData,
+            _phantom_out_reference_coding: PhantomData,
+            _phantom_out_interval_coding: PhantomData,
+            _phantom_out_residual_coding: PhantomData,
+            _phantom_offsets: PhantomData,
+        };
+
+        if degree == 0 {
+            return successors;
+        }
+
+        let mut reference = 0;
+        if bvgraph.in_window_size > 0 {
+            reference = InReferenceCoding::read_next(&mut successors.ibs, bvgraph.in_zeta_k) as usize;
+        }
+
+        let mut extra_count = degree;
+
+        if reference > 0 {
+            let block_count = InBlockCountCoding::read_next(&mut successors.ibs, bvgraph.in_zeta_k) as usize;
+            successors.blocks = Vec::with_capacity(block_count);
+
+            let mut copied = 0; // # of copied successors
+            let mut total = 0; // total # of successors specified in some copy block
+
+            for i in 0..block_count {
+                let block = huff.read_next(&mut successors.ibs, BLOCKS_IDX_BEGIN + if i == 0 {0} else {i % 2 + 1}) + if i == 0 {0} else {1};
+                successors.blocks.push(block);
+                total += block;
+                if (i & 1) == 0 { // Alternate, count only even blocks
+                    copied += block;
+                }
+            }
+
+            let reference_successors = BVGraphSuccessors::new(graph.clone(), x - reference);
+
+            // If the block count is even, we must compute the number of successors copied implicitly
+            if (block_count & 1) == 0 {
+                copied += reference_successors.remaining - total;
+            }
+
+            extra_count -= copied;
+
+            successors.reference = Some(Box::new(reference_successors));
+            successors.block_left = successors.blocks.first().copied().unwrap_or(usize::MAX);
+            successors.next_copied = successors.advance_copied();
+        }
+
+        if extra_count > 0 && bvgraph.in_min_interval_len != 0 {
+            let interval_count = GammaCode::read_next(&mut successors.ibs, bvgraph.in_zeta_k) as usize;
+
+            if interval_count != 0 {
+                successors.intervals = Vec::with_capacity(interval_count);
+
+                let mut prev_left = huff.read_next(&mut successors.ibs, INTERVALS_LEFT_IDX_BEGIN);
+                let mut prev_len = huff.read_next(&mut successors.ibs, INTERVALS_LEN_IDX_BEGIN);
+
+                let left = (nat2int(prev_left as u64) + x as i64) as usize;
+                let len = prev_len + bvgraph.in_min_interval_len;
+                successors.intervals.push((left, len));
+                let mut prev = left + len; // Holds the last integer in the last interval
+                extra_count -= len;
+
+                for _ in 1..interval_count {
+                    let mut ctx = 1 + zuck_encode(prev_left, K_ZUCK, I_ZUCK, J_ZUCK).0.min(30);
+                    prev_left = huff.read_next(&mut successors.ibs, INTERVALS_LEFT_IDX_BEGIN + ctx);
+                    prev += prev_left + 1;
+
+                    ctx = 1 + zuck_encode(prev_len, K_ZUCK, I_ZUCK, J_ZUCK).0.min(30);
+                    prev_len = huff.read_next(&mut successors.ibs, INTERVALS_LEN_IDX_BEGIN + ctx);
+                    let len = prev_len + bvgraph.in_min_interval_len;
+                    successors.intervals.push((prev, len));
+
+                    prev += len;
+                    extra_count -= len;
+                }
+            }
+        }
+
+        successors.residual_count = extra_count;
+        successors.residuals_left = extra_count;
+        successors.next_residual = successors.advance_residual(None);
+
+        successors
+    }
+
+    #[inline(always)]
+    fn advance_copied(&mut self) -> Option<usize> {
+        let reference = self.reference.as_mut()?;
+
+        loop {
+            while self.block_left == 0 {
+                self.curr_block += 1;
+                self.block_left = match self.curr_block.cmp(&self.blocks.len()) {
+                    Ordering::Less => self.blocks[self.curr_block],
+                    // The implicit last block extends up to the end of the reference list
+                    Ordering::Equal if (self.curr_block & 1) == 0 => usize::MAX,
+                    _ => return None,
+                };
+            }
+
+            let succ = reference.next()?;
+            self.block_left -= 1;
+
+            if (self.curr_block & 1) == 0 {
+                return Some(succ);
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn next_interval(&self) -> Option<usize> {
+        self.intervals.get(self.curr_interval).map(|&(left, _)| left + self.interval_idx)
+    }
+
+    #[inline(always)]
+    fn advance_interval(&mut self) {
+        self.interval_idx += 1;
+        if self.interval_idx == self.intervals[self.curr_interval].1 {
+            self.curr_interval += 1;
+            self.interval_idx = 0;
+        }
+    }
+
+    #[inline(always)]
+    fn advance_residual(&mut self, prev: Option<usize>) -> Option<usize> {
+        if self.residuals_left == 0 {
+            return None;
+        }
+
+        self.residuals_left -= 1;
+
+        let ctx = match prev {
+            Some(_) => 32 + zuck_encode(self.prev_residual, K_ZUCK, I_ZUCK, J_ZUCK).0.min(79),
+            None => zuck_encode(self.residual_count, K_ZUCK, I_ZUCK, J_ZUCK).0.min(31),
+        };
+        self.prev_residual = self.graph.as_ref().huffman_decoder.read_next(&mut self.ibs, RESIDUALS_IDX_BEGIN + ctx);
+
+        Some(match prev {
+            Some(prev) => prev + self.prev_residual + 1,
+            None => (self.x as i64 + nat2int(self.prev_residual as u64)) as usize,
+        })
+    }
+
+    /// Skips the successors smaller than `lo`.
+    /// 
+    /// Whole intervals are skipped at once, while the copied successors and the residuals
+    /// are decoded only up to the first one not smaller than `lo`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `lo` - The smallest successor to keep
+    pub fn skip_to(&mut self, lo: usize) {
+        let mut skipped = 0;
+
+        while let Some(&(left, len)) = self.intervals.get(self.curr_interval) {
+            if left + len <= lo {
+                skipped += len - self.interval_idx;
+                self.curr_interval += 1;
+                self.interval_idx = 0;
+            } else {
+                if left + self.interval_idx < lo {
+                    skipped += lo - left - self.interval_idx;
+                    self.interval_idx = lo - left;
+                }
+                break;
+            }
+        }
+
+        while self.next_copied.is_some_and(|succ| succ < lo) {
+            self.next_copied = self.advance_copied();
+            skipped += 1;
+        }
+
+        while let Some(residual) = self.next_residual.filter(|&succ| succ < lo) {
+            self.next_residual = self.advance_residual(Some(residual));
+            skipped += 1;
+        }
+
+        self.remaining -= skipped;
+    }
+}
+
+impl<
+    InBlockCoding: Huffman,
+    InBlockCountCoding: UniversalCode,
+    InOutdegreeCoding: Huffman,
+    InOffsetCoding: UniversalCode,
+    InReferenceCoding: UniversalCode,
+    InIntervalCoding: Huffman,
+    InResidualCoding: Huffman,
+    OutBlockCoding: UniversalCode,
+    OutBlockCountCoding: UniversalCode,
+    OutOutdegreeCoding: UniversalCode,
+    OutOffsetCoding: UniversalCode,
+    OutReferenceCoding: UniversalCode,
+    OutIntervalCoding: UniversalCode,
+    OutResidualCoding: UniversalCode,
+    O: Offsets,
+    BV: AsRef<BVGraph<
+        InBlockCoding,
+        InBlockCountCoding,
+        InOutdegreeCoding,
+        InOffsetCoding,
+        InReferenceCoding,
+        InIntervalCoding,
+        InResidualCoding,
+        OutBlockCoding,
+        OutBlockCountCoding,
+        OutOutdegreeCoding,
+        OutOffsetCoding,
+        OutReferenceCoding,
+        OutIntervalCoding,
+        OutResidualCoding,
+        O,
+>> + Clone,
+> Iterator for BVGraphSuccessors<
+    InBlockCoding,
+    InBlockCountCoding,
+    InOutdegreeCoding,
+    InOffsetCoding,
+    InReferenceCoding,
+    InIntervalCoding,
+    InResidualCoding,
+    OutBlockCoding,
+    OutBlockCountCoding,
+    OutOutdegreeCoding,
+    OutOffsetCoding,
+    OutReferenceCoding,
+    OutIntervalCoding,
+    OutResidualCoding,
+    O,
+    BV,
+> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        self.remaining -= 1;
+
+        let copied = self.next_copied.unwrap_or(usize::MAX);
+        let interval = self.next_interval().unwrap_or(usize::MAX);
+        let residual = self.next_residual.unwrap_or(usize::MAX);
+
+        if copied < interval && copied < residual {
+            self.next_copied = self.advance_copied();
+            Some(copied)
+        } else if interval < residual {
+            self.advance_interval();
+            Some(interval)
+        } else {
+            self.next_residual = self.advance_residual(Some(residual));
+            Some(residual)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<
+    InBlockCoding: Huffman,
+    InBlockCountCoding: UniversalCode,
+    InOutdegreeCoding: Huffman,
+    InOffsetCoding: UniversalCode,
+    InReferenceCoding: UniversalCode,
+    InIntervalCoding: Huffman,
+    InResidualCoding: Huffman,
+    OutBlockCoding: UniversalCode,
+    OutBlockCountCoding: UniversalCode,
+    OutOutdegreeCoding: UniversalCode,
+    OutOffsetCoding: UniversalCode,
+    OutReferenceCoding: UniversalCode,
+    OutIntervalCoding: UniversalCode,
+    OutResidualCoding: UniversalCode,
+    O: Offsets,
+    BV: AsRef<BVGraph<
+        InBlockCoding,
+        InBlockCountCoding,
+        InOutdegreeCoding,
+        InOffsetCoding,
+        InReferenceCoding,
+        InIntervalCoding,
+        InResidualCoding,
+        OutBlockCoding,
+        OutBlockCountCoding,
+        OutOutdegreeCoding,
+        OutOffsetCoding,
+        OutReferenceCoding,
+        OutIntervalCoding,
+        OutResidualCoding,
+        O,
+>> + Clone,
+> ExactSizeIterator for BVGraphSuccessors<
+    InBlockCoding,
+    InBlockCountCoding,
+    InOutdegreeCoding,
+    InOffsetCoding,
+    InReferenceCoding,
+    InIntervalCoding,
+    InResidualCoding,
+    OutBlockCoding,
+    OutBlockCountCoding,
+    OutOutdegreeCoding,
+    OutOffsetCoding,
+    OutReferenceCoding,
+    OutIntervalCoding,
+    OutResidualCoding,
+    O,
+    BV,
+> {}
 
 impl<
     InBlockCoding: Huffman,
@@ -380,6 +1097,7 @@ impl<
     OutReferenceCoding: UniversalCode,
     OutIntervalCoding: UniversalCode,
     OutResidualCoding: UniversalCode,
+    O: Offsets,
 > AsMut<BVGraph<
     InBlockCoding,
     InBlockCountCoding,
@@ -395,6 +1113,7 @@ impl<
     OutReferenceCoding,
     OutIntervalCoding,
     OutResidualCoding,
+    O,
 >> for BVGraph<
     InBlockCoding,
     InBlockCountCoding,
@@ -410,6 +1129,7 @@ impl<
     OutReferenceCoding,
     OutIntervalCoding,
     OutResidualCoding,
+    O,
 > {
 
     fn as_mut(&mut self) -> &mut BVGraph<
@@ -427,6 +1147,7 @@ impl<
         OutReferenceCoding,
     OutIntervalCoding,
         OutResidualCoding,
+        O,
     > {
         self
     }
@@ -447,6 +1168,7 @@ impl<
     OutReferenceCoding: UniversalCode,
     OutIntervalCoding: UniversalCode,
     OutResidualCoding: UniversalCode,
+    O: Offsets,
 > AsRef<BVGraph<
     InBlockCoding,
     InBlockCountCoding,
@@ -462,6 +1184,7 @@ impl<
     OutReferenceCoding,
     OutIntervalCoding,
     OutResidualCoding,
+    O,
 >> for BVGraph<
     InBlockCoding,
     InBlockCountCoding,
@@ -477,6 +1200,7 @@ impl<
     OutReferenceCoding,
     OutIntervalCoding,
     OutResidualCoding,
+    O,
 >
 {
     fn as_ref(&self) -> &BVGraph<
@@ -493,7 +1217,8 @@ impl<
         OutOffsetCoding, 
         OutReferenceCoding,
         OutIntervalCoding, 
-        OutResidualCoding
+        OutResidualCoding,
+        O,
     > {
         self
     }
@@ -514,6 +1239,7 @@ impl<
     OutReferenceCoding: UniversalCode,
     OutIntervalCoding: UniversalCode,
     OutResidualCoding: UniversalCode,
+    O: Offsets,
 > IntoIterator for BVGraph<
     InBlockCoding,
     InBlockCountCoding,
@@ -529,6 +1255,7 @@ impl<
     OutReferenceCoding,
     OutIntervalCoding,
     OutResidualCoding,
+    O,
 > 
 {
     type Item = usize;
@@ -548,6 +1275,7 @@ impl<
         OutReferenceCoding,
         OutIntervalCoding,
         OutResidualCoding,
+        O,
         BVGraph<
             InBlockCoding, 
             InBlockCountCoding, 
@@ -562,7 +1290,8 @@ impl<
             OutOffsetCoding, 
             OutReferenceCoding,
             OutIntervalCoding, 
-            OutResidualCoding
+            OutResidualCoding,
+            O,
         >
     >;
 
@@ -570,7 +1299,7 @@ impl<
         let ibs = Rc::new(RefCell::new(BinaryReader::new(self.graph_memory.clone())));
 
         let mut huff_decoder = HuffmanDecoder::new();
-        huff_decoder.decode_headers(&mut ibs.borrow_mut(), NUM_CONTEXTS);
+        huff_decoder.decode_headers(&mut ibs.borrow_mut(), NUM_CONTEXTS).expect("The headers have been decoded when loading the graph");
 
         BVGraphNodeIterator {
             n: self.n,
@@ -579,6 +1308,8 @@ impl<
             cyclic_buffer_size: self.in_window_size + 1,
             window: vec![vec![0usize; self.in_window_size + 1]; 1024],
             outd: vec![0usize; self.in_window_size + 1],
+            buffers: DecodeBuffers::default(),
+            list: Vec::new(),
             graph: self,
             from: 0,
             curr: -1,
@@ -596,6 +1327,7 @@ impl<
             _phantom_out_reference_coding: PhantomData,
             _phantom_out_interval_coding: PhantomData,
             _phantom_out_residual_coding: PhantomData,
+            _phantom_offsets: PhantomData,
         }
     }
 }
@@ -615,6 +1347,7 @@ impl<
     OutReferenceCoding: UniversalCode,
     OutIntervalCoding: UniversalCode,
     OutResidualCoding: UniversalCode,
+    O: Offsets,
 > BVGraph<
     InBlockCoding,
     InBlockCountCoding,
@@ -630,6 +1363,7 @@ impl<
     OutReferenceCoding,
     OutIntervalCoding,
     OutResidualCoding,
+    O,
 > {
     pub fn iter(&self) -> BVGraphNodeIterator<
         InBlockCoding,
@@ -646,12 +1380,13 @@ impl<
         OutReferenceCoding,
         OutIntervalCoding,
         OutResidualCoding,
+        O,
         &Self
     > {
         let ibs = Rc::new(RefCell::new(BinaryReader::new(self.graph_memory.clone())));
 
         let mut huff_decoder = HuffmanDecoder::new();
-        huff_decoder.decode_headers(&mut ibs.borrow_mut(), INTERVALS_LEN_IDX_BEGIN + INTERVALS_LEN_IDX_LEN);
+        huff_decoder.decode_headers(&mut ibs.borrow_mut(), INTERVALS_LEN_IDX_BEGIN + INTERVALS_LEN_IDX_LEN).expect("The headers have been decoded when loading the graph");
 
         BVGraphNodeIterator {
             n: self.n,
@@ -661,6 +1396,8 @@ impl<
             cyclic_buffer_size: self.in_window_size + 1,
             window: vec![vec![0usize; self.in_window_size + 1]; 1024],
             outd: vec![0usize; self.in_window_size + 1],
+            buffers: DecodeBuffers::default(),
+            list: Vec::new(),
             from: 0,
             curr: -1,
             _phantom_in_block_coding: PhantomData,
@@ -677,16 +1414,201 @@ impl<
             _phantom_out_reference_coding: PhantomData,
             _phantom_out_interval_coding: PhantomData,
             _phantom_out_residual_coding: PhantomData,
+            _phantom_offsets: PhantomData,
+        }
+    }
+
+    /// Returns an iterator over the nodes of a range, each paired with its successors, starting at its first node.
+    /// 
+    /// The lists preceding the range within the window are decoded by random access, so that the lists
+    /// of the range can refer to them as in a scan starting from node 0.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `nodes` - The range of nodes to iterate on
+    pub fn iter_range(&self, nodes: Range<usize>) -> impl Iterator<Item = (usize, Box<[usize]>)> + '_ {
+        let mut iter = self.iter();
+        iter.seek(self.range_start(nodes));
+
+        iter.into_lists()
+    }
+
+    /// Returns an iterator over the nodes from `x` on, each paired with its successors, as by [`Self::iter_range()`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The first node of the iteration
+    pub fn iter_from(&self, x: usize) -> impl Iterator<Item = (usize, Box<[usize]>)> + '_ {
+        self.iter_range(x..self.n)
+    }
+
+    /// Returns an iterator over the nodes from `x` on, each paired with its successors, without using the offsets.
+    /// 
+    /// The iteration starts with an empty window at the last sync point not after `x`, skipping the lists before `x`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The first node of the iteration
+    /// * `sync_points` - The sync points of the graph
+    pub fn iter_from_sync(&self, x: usize, sync_points: &SyncPoints) -> impl Iterator<Item = (usize, Box<[usize]>)> + '_ {
+        assert!(x <= self.n, "Node index out of range {}", x);
+        let (sync_point, position) = sync_points.get(x);
+
+        let mut iter = self.iter();
+        iter.seek(RangeStart { nodes: sync_point..self.n, position, window: Vec::new() });
+        for _ in sync_point..x {
+            iter.next();
+        }
+
+        iter.into_lists()
+    }
+
+    /// Splits the nodes in at most `parts` ranges of consecutive nodes taking about the same number of bits,
+    /// and returns an iterator on each of them, as by [`Self::iter_range()`].
+    /// 
+    /// The iterators share the graph, so they cannot be moved to other threads: see [`Self::par_scan()`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `parts` - The number of ranges
+    pub fn split_iter(&self, parts: usize) -> Vec<impl Iterator<Item = (usize, Box<[usize]>)> + '_> {
+        parallel::split_nodes(self.n, parts, |x| self.offsets.get(x))
+            .into_iter()
+            .map(|nodes| self.iter_range(nodes))
+            .collect()
+    }
+
+    /// Scans the graph on the given number of threads, each mapping the nodes of one of the ranges
+    /// of [`Self::split_iter()`], paired with their successors.
+    /// 
+    /// The graph cannot be shared among threads, so the lists preceding each range are decoded on the calling
+    /// thread, and each thread decodes its range through a graph of its own, loaded from the same bitstream.
+    /// Returns the results in the order of the ranges.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `threads` - The number of threads
+    /// * `map` - The function mapping the nodes of a range
+    pub fn par_scan<R: Send>(
+        &self, 
+        threads: usize, 
+        map: impl Fn(&mut dyn Iterator<Item = (usize, Box<[usize]>)>) -> R + Sync,
+    ) -> Vec<R> {
+        let starts = parallel::split_nodes(self.n, threads, |x| self.offsets.get(x))
+            .into_iter()
+            .map(|nodes| self.range_start(nodes))
+            .collect();
+
+        let (graph_memory, n, m) = (self.graph_memory.clone(), self.n, self.m);
+        let (max_ref_count, window_size, min_interval_len, zeta_k) = 
+            (self.in_max_ref_count, self.in_window_size, self.in_min_interval_len, self.in_zeta_k);
+
+        parallel::map_ranges(starts, |start| {
+            let graph = BVGraphBuilder::<
+                InBlockCoding, InBlockCountCoding, InOutdegreeCoding, InOffsetCoding, InReferenceCoding, InIntervalCoding, InResidualCoding, 
+                OutBlockCoding, OutBlockCountCoding, OutOutdegreeCoding, OutOffsetCoding, OutReferenceCoding, OutIntervalCoding, OutResidualCoding
+            >::new()
+                .set_num_nodes(n)
+                .set_num_edges(m)
+                .set_in_max_ref_count(max_ref_count)
+                .set_in_window_size(window_size)
+                .set_in_min_interval_len(min_interval_len)
+                .set_in_zeta(zeta_k)
+                .load_graph_from(graph_memory.clone())
+                .expect("The headers have been decoded when loading the graph")
+                .build();
+
+            let mut iter = graph.iter();
+            iter.seek(start);
+            let mut lists = iter.into_lists();
+
+            map(&mut lists)
+        })
+    }
+
+    /// Returns where the iteration over a range of nodes starts, decoding the lists preceding it by random access.
+    fn range_start(&self, nodes: Range<usize>) -> RangeStart {
+        assert!(nodes.start <= nodes.end && nodes.end <= self.n, "Node range out of bounds {:?}", nodes);
+
+        RangeStart::new(nodes, self.in_window_size, |x| self.offsets.get(x), |x| RandomAccessGraph::successors(self, x))
+    }
+
+    /// Returns the counters of the cache of decoded lists, if the graph has one
+    /// (see [`BVGraphBuilder::set_cache_size()`] and [`Self::pin()`]).
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache.stats()
+    }
+
+    /// Pins the lists of the nodes chosen by `pinning` in the cache of decoded lists, creating it if absent,
+    /// so that they are never decoded again.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `pinning` - The lists to pin
+    pub fn pin(&mut self, pinning: Pinning) {
+        let nodes = pinning.select(
+            self.n, 
+            |x| self.outdegree_internal(x, &self.huffman_decoder), 
+            |x| self.reference_at(x),
+        );
+        let lists: Vec<_> = nodes.into_iter().map(|x| (x, RandomAccessGraph::successors(self, x))).collect();
+
+        self.cache.pin(lists);
+    }
+
+    /// Returns the reference of the list of `x`, or 0 if it does not refer to any list.
+    fn reference_at(&self, x: usize) -> usize {
+        let degree = self.outdegree_internal(x, &self.huffman_decoder);
+
+        if degree == 0 || self.in_window_size == 0 {
+            return 0;
+        }
+        let mut decoder = self.graph_binary_wrapper.borrow_mut();
+        decoder.position(self.cached_ptr.get().unwrap() as u64);
+        InReferenceCoding::read_next(&mut decoder, self.in_zeta_k) as usize
+    }
+
+    /// Writes the successors of a given node into `successors`, replacing its content, decoding them
+    /// through the given buffers.
+    /// 
+    /// Once the buffers and `successors` have grown to the size of the lists decoded, no memory is allocated,
+    /// but for the lists added to the cache of decoded lists, if any.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The node number
+    /// * `buffers` - The buffers for the intermediate values
+    /// * `successors` - The vector receiving the successors
+    pub fn successors_into(&self, x: usize, buffers: &mut DecodeBuffers, successors: &mut Vec<usize>) {
+        assert!(x < self.n, "Node index out of range {}", x);
+        self.decode_list_at(x, self.graph_binary_wrapper.clone(), &self.huffman_decoder, buffers, successors);
+    }
+
+    /// Decodes the successor list of `x` into `successors` by random access, unless the list is in the cache.
+    #[inline(always)]
+    fn decode_list_at(
+        &self, 
+        x: usize, 
+        decoder: Rc<RefCell<BinaryReader>>, 
+        huff: &HuffmanDecoder, 
+        buffers: &mut DecodeBuffers, 
+        successors: &mut Vec<usize>,
+    ) {
+        if self.cache.get_into(x, successors) {
+            return;
         }
+
+        self.decode_list_into(x, decoder, None, huff, buffers, successors);
+        self.cache.insert(x, successors);
     }
 
     #[inline(always)]
-    fn outdegree_internal(&self, x: usize, huff_outdegrees: &mut HuffmanDecoder) -> usize {
+    fn outdegree_internal(&self, x: usize, huff_outdegrees: &HuffmanDecoder) -> usize {
         if self.cached_node.get().is_some() && x == self.cached_node.get().unwrap() {
             return self.cached_outdegree.get().unwrap();
         }
         
-        self.outdegrees_binary_wrapper.borrow_mut().position(self.offsets[x] as u64);
+        self.outdegrees_binary_wrapper.borrow_mut().position(self.offsets.get(x) as u64);
         let d;
         if x == 0 || x % 32 == 0 {
             d = huff_outdegrees.read_next(&mut self.outdegrees_binary_wrapper.borrow_mut(), OUTD_IDX_BEGIN + 0);
@@ -709,24 +1631,31 @@ impl<
         huff: &mut HuffmanDecoder,
     ) -> Box<[usize]> {
         assert!(x < self.n, "Node index out of range {}", x);
-        self.decode_list(x, self.graph_binary_wrapper.clone(), None, &mut [], huff)
+        let mut successors = Vec::new();
+        self.decode_list_at(x, self.graph_binary_wrapper.clone(), huff, &mut DecodeBuffers::default(), &mut successors);
+
+        successors.into_boxed_slice()
     }
 
+    /// Decodes the successor list of `x` into `successors`, using `buffers` for the intermediate values.
+    /// 
+    /// During a sequential scan, `window` holds the lists preceding `x` and their outdegrees, where the outdegree
+    /// of `x` is recorded; otherwise, the list is decoded by random access.
     #[inline(always)]
-    fn decode_list(
+    fn decode_list_into(
         &self, 
         x: usize, 
         decoder: Rc<RefCell<BinaryReader>>, 
-        window: Option<&mut Vec<Vec<usize>>>, 
-        outd: &mut [usize],
-        huff: &mut HuffmanDecoder,
-    ) -> Box<[usize]> {
+        mut window: Option<(&[Vec<usize>], &mut [usize])>, 
+        huff: &HuffmanDecoder,
+        buffers: &mut DecodeBuffers,
+        successors: &mut Vec<usize>,
+    ) {
+        successors.clear();
+
         let cyclic_buffer_size = self.in_window_size + 1;
         let degree;
-        if window.is_none() {
-            degree = self.outdegree_internal(x, huff);
-            decoder.borrow_mut().position(self.cached_ptr.get().unwrap() as u64);
-        } else {
+        if let Some((_, outd)) = &mut window {
             let ctx =
                 if x == 0 || x % 32 == 0 {
                     0
@@ -735,12 +1664,29 @@ impl<
                 };
             degree = huff.read_next(&mut decoder.borrow_mut(), OUTD_IDX_BEGIN + ctx);
             outd[x % cyclic_buffer_size] = degree; 
+        } else {
+            degree = self.outdegree_internal(x, huff);
+            decoder.borrow_mut().position(self.cached_ptr.get().unwrap() as u64);
         }
 
         if degree == 0 {
-            return Box::new([]);
+            return;
         }
 
+        let DecodeBuffers { 
+            blocks: block, 
+            left, 
+            len, 
+            intervals, 
+            residuals: residual_list, 
+            extra, 
+            copied: block_list, 
+            reference: reference_buffer, 
+            next,
+        } = buffers;
+
+        successors.reserve_exact(degree);
+
         let mut reference = -1;
         if self.in_window_size > 0 {
             reference = InReferenceCoding::read_next(&mut decoder.borrow_mut(), self.in_zeta_k) as i64;
@@ -749,15 +1695,13 @@ impl<
         // Position in the circular buffer of the reference of the current node
         let reference_index = ((x as i64 - reference + cyclic_buffer_size as i64) as usize) % cyclic_buffer_size;
 
-        let mut block = Vec::default();
+        block.clear();
 
         let mut extra_count;
 
         if reference > 0 {
             let block_count = InBlockCountCoding::read_next(&mut decoder.borrow_mut(), self.in_zeta_k) as usize;
-            if block_count != 0 {
-                block = Vec::with_capacity(block_count);
-            }
+            block.reserve(block_count);
 
             let mut copied = 0; // # of copied successors
             let mut total = 0; // total # of successors specified in some copy block
@@ -776,7 +1720,7 @@ impl<
             // If the block count is even, we must compute the number of successors copied implicitly
             if (block_count & 1) == 0 {
                 copied += (
-                    if window.is_some() {outd[reference_index]} 
+                    if let Some((_, outd)) = &window {outd[reference_index]} 
                     else {self.outdegree_internal((x as i64 - reference) as usize, huff)} //////////// The same here. It should use its predecessor node's outd
                 ) - total;
             }
@@ -788,16 +1732,16 @@ impl<
 
         let mut interval_count = 0; // Number of intervals
 
-        let mut left = Vec::default();
-        let mut len = Vec::default();
+        left.clear();
+        len.clear();
 
         if extra_count > 0 && self.in_min_interval_len != 0 {
             interval_count = GammaCode::read_next(&mut decoder.borrow_mut(), self.in_zeta_k) as usize;
             
             if interval_count != 0 {
-                left = Vec::with_capacity(interval_count);
-                len = Vec::with_capacity(interval_count);
-                
+                left.reserve(interval_count);
+                len.reserve(interval_count);
+
                 let mut prev_left = huff.read_next(&mut decoder.borrow_mut(), INTERVALS_LEFT_IDX_BEGIN);
                 let mut prev_len = huff.read_next(&mut decoder.borrow_mut(), INTERVALS_LEN_IDX_BEGIN);
 
@@ -831,7 +1775,8 @@ impl<
             }
         }
 
-        let mut residual_list = Vec::with_capacity(extra_count);
+        residual_list.clear();
+        residual_list.reserve(extra_count);
         if extra_count > 0 {
             let mut ctx = 
                 zuck_encode(extra_count, K_ZUCK, I_ZUCK, J_ZUCK)
@@ -856,18 +1801,17 @@ impl<
         }
 
         // The extra part is made by the contribution of intervals, if any, and by the residuals list.
-        let mut extra_list;
+        let extra_list: &[i64];
         if interval_count > 0 {
-            let total_lenght = len.iter().sum();
-
-            extra_list = Vec::with_capacity(total_lenght);
+            intervals.clear();
+            intervals.reserve(len.iter().sum());
             let mut curr_left = if !left.is_empty() {left[0]} else {0};
             let mut curr_index = 0;
             let mut curr_interval = 0;
             let mut remaining = left.len();
 
             while remaining > 0 {
-                extra_list.push(curr_left + curr_index as i64);
+                intervals.push(curr_left + curr_index as i64);
                 curr_index += 1;
 
                 if curr_index == len[curr_interval] {
@@ -882,133 +1826,350 @@ impl<
         
             if extra_count > 0 {
                 let len_residual = residual_list.len();
-                let len_extra = extra_list.len();
+                let len_extra = intervals.len();
 
-                let mut temp_list = Vec::with_capacity(len_residual + len_extra);
+                extra.clear();
+                extra.reserve(len_residual + len_extra);
                 let mut idx0 = 0;
                 let mut idx1 = 0;
                 while idx0 < len_residual && idx1 < len_extra {
-                    if residual_list[idx0] <= extra_list[idx1] {
-                        temp_list.push(residual_list[idx0]);
+                    if residual_list[idx0] <= intervals[idx1] {
+                        extra.push(residual_list[idx0]);
                         idx0 += 1;
                     } else {
-                        temp_list.push(extra_list[idx1]);
+                        extra.push(intervals[idx1]);
                         idx1 += 1;
                         
                     }
                 }
 
-                while idx0 < len_residual {
-                    temp_list.push(residual_list[idx0]);
-                    idx0 += 1;
-                }
-
-                while idx1 < len_extra {
-                    temp_list.push(extra_list[idx1]);
-                    idx1 += 1;
-                }
+                extra.extend_from_slice(&residual_list[idx0..]);
+                extra.extend_from_slice(&intervals[idx1..]);
 
-                extra_list = temp_list;
+                extra_list = extra;
+            } else {
+                extra_list = intervals;
             }
         } else {
             extra_list = residual_list;
         }
 
-        let mut block_list = Vec::default();
-        if reference > 0 {
-            let decoded_reference;
-
-            let mut reference_it = 
-                if let Some(window) = window {
-                    window[reference_index][0..outd[reference_index]].iter()
-                } else {
-                    decoded_reference = self.decode_list(
-                        (x as i64 - reference) as usize, 
-                        decoder,
-                        None, 
-                        &mut [],
-                        huff
-                    );
-                    decoded_reference.iter()
-                };
-            
-            let mask_len = block.len();
-            let mut curr_mask = 0;
-            let mut left;
+        if reference <= 0 {
+            successors.extend(extra_list.iter().map(|&x| x as usize));
+            return;
+        }
 
-            if mask_len != 0 {
-                left = block[curr_mask] as i64;
+        let mut reference_it = 
+            if let Some((window, outd)) = window {
+                window[reference_index][0..outd[reference_index]].iter()
+            } else {
+                self.decode_list_at(
+                    (x as i64 - reference) as usize, 
+                    decoder,
+                    huff,
+                    next.get_or_insert_with(Box::default),
+                    reference_buffer,
+                );
+                reference_buffer.iter()
+            };
+        
+        let mask_len = block.len();
+        let mut curr_mask = 0;
+        let mut left;
+
+        if mask_len != 0 {
+            left = block[curr_mask] as i64;
+            curr_mask += 1;
+            if left == 0 && curr_mask < mask_len {
+                reference_it.nth(block[curr_mask] - 1);
                 curr_mask += 1;
-                if left == 0 && curr_mask < mask_len {
-                    reference_it.nth(block[curr_mask] - 1);
-                    curr_mask += 1;
 
-                    left = if curr_mask < mask_len {curr_mask += 1; block[curr_mask - 1] as i64} else {-1};
-                }
-            } else {
-                left = -1;
+                left = if curr_mask < mask_len {curr_mask += 1; block[curr_mask - 1] as i64} else {-1};
             }
+        } else {
+            left = -1;
+        }
 
-            block_list = Vec::with_capacity(reference_it.len());
+        // Without extra successors the copied ones are the whole list, so they are copied into `successors` itself
+        let copied_only = extra_list.is_empty();
+        if copied_only {
+            std::mem::swap(block_list, successors);
+        }
 
-            while left != 0 {
-                let next = reference_it.next();
+        block_list.clear();
+        block_list.reserve(reference_it.len());
 
-                if next.is_none() {
-                    break;
-                }
+        while left != 0 {
+            let next = reference_it.next();
 
-                if left == -1 {
-                    block_list.push(*next.unwrap());
-                }
-                
-                if left > 0 {
-                    left -= 1;
-                    if left == 0 && curr_mask < mask_len {
-                        reference_it.nth(block[curr_mask] - 1);
-                        curr_mask += 1;
+            if next.is_none() {
+                break;
+            }
 
-                        left = if curr_mask < mask_len {curr_mask += 1; block[curr_mask - 1] as i64} else {-1};
-                    }
-                    block_list.push(*next.unwrap());
-                }                
+            if left == -1 {
+                block_list.push(*next.unwrap());
             }
+            
+            if left > 0 {
+                left -= 1;
+                if left == 0 && curr_mask < mask_len {
+                    reference_it.nth(block[curr_mask] - 1);
+                    curr_mask += 1;
+
+                    left = if curr_mask < mask_len {curr_mask += 1; block[curr_mask - 1] as i64} else {-1};
+                }
+                block_list.push(*next.unwrap());
+            }                
         }
 
-        if reference <= 0 {
-            let extra_list: Vec<usize> = extra_list.into_iter().map(|x| x as usize).collect();
-            return extra_list.into_boxed_slice();
-        } else if extra_list.is_empty() {
-            return block_list.into_boxed_slice();
-        };
+        if copied_only {
+            std::mem::swap(block_list, successors);
+            return;
+        }
 
         let len_block = block_list.len();
         let len_extra = extra_list.len();
 
-        let mut temp_list = Vec::with_capacity(len_block + len_extra);
         let mut idx0 = 0;
         let mut idx1 = 0;
         while idx0 < len_block && idx1 < len_extra {
             if block_list[idx0] < extra_list[idx1] as usize {
-                temp_list.push(block_list[idx0]);
+                successors.push(block_list[idx0]);
                 idx0 += 1;
             } else {
-                temp_list.push(extra_list[idx1] as usize);
+                successors.push(extra_list[idx1] as usize);
                 idx1 += 1;
             }
         }
 
-        while idx0 < len_block {
-            temp_list.push(block_list[idx0]);
-            idx0 += 1;
+        successors.extend_from_slice(&block_list[idx0..]);
+        successors.extend(extra_list[idx1..].iter().map(|&x| x as usize));
+    }
+
+    /// Returns an iterator over the nodes of the graph, each paired with its successors, decoded in checked mode.
+    /// 
+    /// The Huffman headers are decoded again and every list is validated as by [`Self::try_successors()`];
+    /// the iteration stops after the first error.
+    pub fn try_iter_nodes(&self) -> impl Iterator<Item = crate::Result<(usize, Box<[usize]>)>> + '_ {
+        let mut ibs = BinaryReader::new(self.graph_memory.clone());
+        let mut huff = HuffmanDecoder::new();
+        let headers = huff.decode_headers(&mut ibs, NUM_CONTEXTS);
+
+        let mut window: Vec<Box<[usize]>> = vec![Box::default(); self.in_window_size.min(self.n) + 1];
+        let mut x = 0;
+        let mut failed = headers.is_err();
+        let mut headers = headers.err();
+
+        std::iter::from_fn(move || {
+            if let Some(e) = headers.take() {
+                return Some(Err(e));
+            }
+            if failed || x >= self.n {
+                return None;
+            }
+
+            let cyclic_buffer_size = window.len();
+            let successors = self.try_decode_list_with(x, &mut ibs, &huff, |_, reference| {
+                Ok(Cow::Borrowed(&window[reference % cyclic_buffer_size]))
+            });
+
+            match successors {
+                Ok(successors) => {
+                    let successors = successors.into_boxed_slice();
+                    window[x % cyclic_buffer_size] = successors.clone();
+                    x += 1;
+
+                    Some(Ok((x - 1, successors)))
+                },
+                Err(e) => {
+                    failed = true;
+                    Some(Err(e))
+                },
+            }
+        })
+    }
+
+    /// Returns the list of successors of a given node, decoded in checked mode.
+    /// 
+    /// The reference has to fall inside the window, the copy blocks inside the reference list, and the copied
+    /// successors, the intervals and the residuals cannot exceed the outdegree; the resulting list has to be
+    /// strictly increasing and made of nodes of the graph. Any violation, as well as an invalid Huffman code or
+    /// the end of the bitstream, is reported as an error.
+    /// The chain of references followed to decode the list cannot be longer than the maximum reference count
+    /// of the graph.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The node number
+    pub fn try_successors(&self, x: usize) -> crate::Result<Box<[usize]>> {
+        assert!(x < self.n, "Node index out of range {}", x);
+        let mut decoder = BinaryReader::new(self.graph_memory.clone());
+
+        self.try_decode_list_at(x, &mut decoder, self.in_max_ref_count, &mut 0).map(Vec::into_boxed_slice)
+    }
+
+    /// Returns the cost of decoding the successors of a given node by random access, that is the bits read
+    /// and the number of references followed, decoding the list in checked mode as [`Self::try_successors()`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The node number
+    pub fn try_access_cost(&self, x: usize) -> crate::Result<AccessCost> {
+        assert!(x < self.n, "Node index out of range {}", x);
+        let mut decoder = BinaryReader::new(self.graph_memory.clone());
+        let mut chain_depth = 0;
+
+        self.try_decode_list_at(x, &mut decoder, self.in_max_ref_count, &mut chain_depth)?;
+
+        Ok(AccessCost { bits: decoder.read_bits as u64, chain_depth })
+    }
+
+    /// Decodes the successor list of `x` in checked mode, following at most `max_ref_count` references,
+    /// whose number is added to `chain_depth`.
+    fn try_decode_list_at(&self, x: usize, decoder: &mut BinaryReader, max_ref_count: usize, chain_depth: &mut usize) -> crate::Result<Vec<usize>> {
+        let offset = self.offsets.get(x);
+        if offset > self.graph_memory.len() * 8 {
+            return Err(Error::Malformed(format!("node {}: offset {} past the end of the graph", x, offset)));
+        }
+        decoder.position(offset as u64);
+
+        self.try_decode_list_with(x, decoder, &self.huffman_decoder, |decoder, reference| {
+            if max_ref_count == 0 {
+                return Err(Error::Malformed(format!("node {}: reference chain longer than {}", x, self.in_max_ref_count)));
+            }
+
+            *chain_depth += 1;
+            let position = decoder.get_position();
+            let reference_list = self.try_decode_list_at(reference, decoder, max_ref_count - 1, chain_depth)?;
+            decoder.position(position as u64);
+
+            Ok(Cow::Owned(reference_list))
+        })
+    }
+
+    /// Decodes the successor list of `x` like [`Self::decode_list_into()`], validating every decoded value.
+    /// 
+    /// The reference has to fall inside the window, the copy blocks inside the reference list, and the copied
+    /// successors, the intervals and the residuals cannot exceed the outdegree; the resulting list has to be
+    /// strictly increasing and made of nodes of the graph. Any violation, as well as an invalid Huffman code or
+    /// the end of the bitstream, is reported as an error.
+    /// 
+    /// The successor list of the reference node, if any, is retrieved through `reference_list`.
+    fn try_decode_list_with<'r>(
+        &self, 
+        x: usize, 
+        decoder: &mut BinaryReader, 
+        huff: &HuffmanDecoder,
+        mut reference_list: impl FnMut(&mut BinaryReader, usize) -> crate::Result<Cow<'r, [usize]>>
+    ) -> crate::Result<Vec<usize>> {
+        // Values are checked against their bounds as `u64`, so that saturated sums always fail the check
+        let check = |value: u64, limit: usize, what: &str| match usize::try_from(value) {
+            Ok(value) if value <= limit => Ok(value),
+            _ => Err(Error::Malformed(format!("node {}: {} {} exceeds {}", x, what, value, limit))),
+        };
+        // The first residual and the first interval are coded as a signed gap from `x`
+        let from_x = |value: usize| (x as i64).checked_add(nat2int(value as u64))
+            .and_then(|node| u64::try_from(node).ok())
+            .unwrap_or(u64::MAX);
+
+        let ctx = if x.is_multiple_of(32) { 0 } else { 1 + zuck_encode((x % 32) + 1, K_ZUCK, I_ZUCK, J_ZUCK).0.min(30) };
+        let degree = huff.try_read_next(decoder, OUTD_IDX_BEGIN + ctx)?;
+        let degree = check(degree as u64, self.n, "outdegree")?;
+
+        if degree == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut reference = 0;
+        if self.in_window_size > 0 {
+            let value = InReferenceCoding::try_read_next(decoder, self.in_zeta_k)?;
+            reference = check(value, self.in_window_size.min(x), "reference")?;
+        }
+
+        let mut successors = Vec::new();
+
+        if reference > 0 {
+            let reference_successors = reference_list(decoder, x - reference)?;
+
+            let block_count = InBlockCountCoding::try_read_next(decoder, self.in_zeta_k)?;
+            let block_count = check(block_count, reference_successors.len() + 1, "block count")?;
+
+            let mut pos = 0;
+            for i in 0..block_count {
+                let ctx = if i == 0 { 0 } else { i % 2 + 1 };
+                let block = (huff.try_read_next(decoder, BLOCKS_IDX_BEGIN + ctx)? as u64).saturating_add((i != 0) as u64);
+                let block = check(block, reference_successors.len() - pos, "copy block")?;
+
+                if (i & 1) == 0 { // Alternate, copy only even blocks
+                    successors.extend_from_slice(&reference_successors[pos..pos + block]);
+                }
+                pos += block;
+            }
+
+            // If the block count is even, the last block implicitly extends up to the end of the reference list
+            if (block_count & 1) == 0 {
+                successors.extend_from_slice(&reference_successors[pos..]);
+            }
+
+            check(successors.len() as u64, degree, "number of copied successors")?;
+        }
+
+        let mut extra_count = degree - successors.len();
+
+        if extra_count > 0 && self.in_min_interval_len != 0 {
+            let interval_count = GammaCode::try_read_next(decoder, self.in_zeta_k)?;
+            let interval_count = check(interval_count, extra_count, "interval count")?;
+
+            let mut prev = 0; // Holds the integer following the last interval
+            let mut prev_left = 0;
+            let mut prev_len = 0;
+            for i in 0..interval_count {
+                let left = if i == 0 {
+                    prev_left = huff.try_read_next(decoder, INTERVALS_LEFT_IDX_BEGIN)?;
+                    from_x(prev_left)
+                } else {
+                    let ctx = 1 + zuck_encode(prev_left, K_ZUCK, I_ZUCK, J_ZUCK).0.min(30);
+                    prev_left = huff.try_read_next(decoder, INTERVALS_LEFT_IDX_BEGIN + ctx)?;
+                    (prev as u64).saturating_add(prev_left as u64).saturating_add(1)
+                };
+
+                let ctx = if i == 0 { 0 } else { 1 + zuck_encode(prev_len, K_ZUCK, I_ZUCK, J_ZUCK).0.min(30) };
+                prev_len = huff.try_read_next(decoder, INTERVALS_LEN_IDX_BEGIN + ctx)?;
+                let len = check((prev_len as u64).saturating_add(self.in_min_interval_len as u64), extra_count, "interval length")?;
+
+                prev = check(left.saturating_add(len as u64), self.n, "interval end")?;
+                successors.extend(prev - len..prev);
+                extra_count -= len;
+            }
+        }
+
+        let mut prev: Option<usize> = None;
+        let mut prev_residual = 0;
+        for _ in 0..extra_count {
+            let residual = match prev {
+                None => {
+                    let ctx = zuck_encode(extra_count, K_ZUCK, I_ZUCK, J_ZUCK).0.min(31);
+                    prev_residual = huff.try_read_next(decoder, RESIDUALS_IDX_BEGIN + ctx)?;
+                    from_x(prev_residual)
+                },
+                Some(prev) => {
+                    let ctx = 32 + zuck_encode(prev_residual, K_ZUCK, I_ZUCK, J_ZUCK).0.min(79);
+                    prev_residual = huff.try_read_next(decoder, RESIDUALS_IDX_BEGIN + ctx)?;
+                    (prev as u64).saturating_add(prev_residual as u64).saturating_add(1)
+                },
+            };
+            let residual = check(residual, self.n - 1, "residual")?;
+
+            successors.push(residual);
+            prev = Some(residual);
         }
 
-        while idx1 < len_extra {
-            temp_list.push(extra_list[idx1] as usize);
-            idx1 += 1;
+        successors.sort_unstable();
+        if successors.windows(2).any(|pair| pair[0] == pair[1]) || successors.last().is_some_and(|&last| last >= self.n) {
+            return Err(Error::Malformed(format!("node {}: successors are not distinct nodes of the graph", x)));
         }
 
-        temp_list.into_boxed_slice()
+        Ok(successors)
     }
 
     #[inline(always)]
@@ -1342,6 +2503,21 @@ impl<
     }
 }
 
+/// A graph in the Huffman format with the codes it is stored with by the `webgraph` binary: Huffman codes
+/// for the blocks, outdegrees, intervals and residuals, and universal codes for the rest.
+pub type DefaultBVGraph<O = Box<[usize]>> = BVGraph<
+    Huff, GammaCode, Huff, GammaCode, UnaryCode, Huff, Huff,
+    GammaCode, GammaCode, GammaCode, GammaCode, UnaryCode, GammaCode, ZetaCode,
+    O,
+>;
+
+/// The builder of a [`DefaultBVGraph`].
+pub type DefaultBVGraphBuilder<O = Box<[usize]>> = BVGraphBuilder<
+    Huff, GammaCode, Huff, GammaCode, UnaryCode, Huff, Huff,
+    GammaCode, GammaCode, GammaCode, GammaCode, UnaryCode, GammaCode, ZetaCode,
+    O,
+>;
+
 pub struct BVGraphBuilder<
     InBlockCoding: Huffman,
     InBlockCountCoding: UniversalCode,
@@ -1357,11 +2533,13 @@ pub struct BVGraphBuilder<
     OutReferenceCoding: UniversalCode,
     OutIntervalCoding: UniversalCode,
     OutResidualCoding: UniversalCode,
+    O: Offsets = Box<[usize]>,
 > {
     num_nodes: usize,
     num_edges: usize,
-    loaded_graph: Rc<[u8]>,
-    loaded_offsets: Box<[usize]>,
+    loaded_graph: InputStream,
+    loaded_offsets: O,
+    huffman_decoder: HuffmanDecoder,
     graph_binary_wrapper: BinaryReader,
     outdegrees_binary_wrapper: BinaryReader,
     cached_node: Option<usize>,
@@ -1375,6 +2553,8 @@ pub struct BVGraphBuilder<
     out_min_interval_len: usize,
     in_zeta_k: Option<u64>,
     out_zeta_k: Option<u64>,
+    cache_size: usize,
+    mmap: bool,
     _phantom_in_block_coding: PhantomData<InBlockCoding>,
     _phantom_in_block_count_coding: PhantomData<InBlockCountCoding>,
     _phantom_in_outdegree_coding: PhantomData<InOutdegreeCoding>,
@@ -1406,6 +2586,7 @@ impl<
     OutReferenceCoding: UniversalCode,
     OutIntervalCoding: UniversalCode,
     OutResidualCoding: UniversalCode,
+    O: Offsets,
 > Default for BVGraphBuilder<
     InBlockCoding,
     InBlockCountCoding,
@@ -1421,13 +2602,15 @@ impl<
     OutReferenceCoding,
     OutIntervalCoding,
     OutResidualCoding,
+    O,
 > {
     fn default() -> Self {
         Self { 
             num_nodes: 0, 
             num_edges: 0, 
-            loaded_graph: Rc::new([]), 
-            loaded_offsets: Box::default(), 
+            loaded_graph: InputStream::default(), 
+            loaded_offsets: O::default(), 
+            huffman_decoder: HuffmanDecoder::new(),
             graph_binary_wrapper: BinaryReader::default(),
             outdegrees_binary_wrapper: BinaryReader::default(),
             cached_node: None, 
@@ -1441,6 +2624,8 @@ impl<
             out_min_interval_len: 4,
             in_zeta_k: None,
             out_zeta_k: Some(3),
+            cache_size: 0,
+            mmap: false,
             _phantom_in_block_coding: PhantomData,
             _phantom_in_block_count_coding: PhantomData,
             _phantom_in_outdegree_coding: PhantomData,
@@ -1474,6 +2659,7 @@ impl<
     OutReferenceCoding: UniversalCode,
     OutIntervalCoding: UniversalCode,
     OutResidualCoding: UniversalCode,
+    O: Offsets,
 > BVGraphBuilder<
     InBlockCoding,
     InBlockCountCoding,
@@ -1489,10 +2675,11 @@ impl<
     OutReferenceCoding,
     OutIntervalCoding,
     OutResidualCoding,
+    O,
 > {
     pub fn new() -> BVGraphBuilder<
         InBlockCoding, InBlockCountCoding, InOutdegreeCoding, InOffsetCoding, InReferenceCoding, InIntervalCoding, InResidualCoding, 
-        OutBlockCoding, OutBlockCountCoding, OutOutdegreeCoding, OutOffsetCoding, OutReferenceCoding, OutIntervalCoding, OutResidualCoding
+        OutBlockCoding, OutBlockCountCoding, OutOutdegreeCoding, OutOffsetCoding, OutReferenceCoding, OutIntervalCoding, OutResidualCoding, O
     > {
         Self::default()
     }
@@ -1513,17 +2700,27 @@ impl<
     ///     GammaCode, GammaCode, UnaryCode, DeltaCode, GammaCode, GammaCode, ZetaCode
     /// >::new()
     ///     .load_properties(file_base_name);
-    ///     .load_graph(file_base_name);
+    ///     .load_graph(file_base_name)?;
     /// ```
-    pub fn load_graph(mut self, basename: &str) -> Self {
-        let graph = fs::read(format!("{}.graph", basename)).unwrap();
-
-        let graph = graph.into_boxed_slice().into();
+    pub fn load_graph(self, basename: &str) -> crate::Result<Self> {
+        let graph = container::load(format!("{}.graph", basename), self.mmap, FileKind::Graph)?;
+        self.load_graph_from(graph)
+    }
 
-        self.loaded_graph = graph;
+    /// Uses the given in-memory content as the compressed graph, instead of loading it from a file,
+    /// and decodes its Huffman headers.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `graph` - The content of the graph file
+    pub fn load_graph_from(mut self, graph: impl Into<InputStream>) -> crate::Result<Self> {
+        self.loaded_graph = graph.into();
         self.graph_binary_wrapper = BinaryReader::new(self.loaded_graph.clone());
 
-        self
+        self.huffman_decoder = HuffmanDecoder::new();
+        self.huffman_decoder.decode_headers(&mut BinaryReader::new(self.loaded_graph.clone()), NUM_CONTEXTS)?;
+
+        Ok(self)
     }
 
     /// Loads a previously-compressed BVGraph's offsets file.
@@ -1542,32 +2739,18 @@ impl<
     ///     GammaCode, GammaCode, UnaryCode, DeltaCode, GammaCode, GammaCode, ZetaCode
     /// >::new()
     ///     .load_properties(file_base_name);
-    ///     .load_graph(file_base_name);
-    ///     .load_offsets(file_base_name);
+    ///     .load_graph(file_base_name)?;
+    ///     .load_offsets(file_base_name)?;
     /// let graph = builder.build();
     /// ```
-    pub fn load_offsets(mut self, basename: &str) -> Self {
+    pub fn load_offsets(mut self, basename: &str) -> crate::Result<Self> {
         assert!(self.num_nodes > 0, "The number of nodes has to be >0.");
-        let offsets = fs::read(format!("{}.offsets", basename)).unwrap();
 
-        let mut curr = 0;
+        self.loaded_offsets = load_offsets(basename, self.num_nodes, &self.loaded_graph, self.mmap, |offsets_ibs| {
+            InOffsetCoding::try_read_next(offsets_ibs, self.in_zeta_k)
+        })?;
 
-        let mut offsets_ibs = BinaryReader::new(offsets.into());
-        
-        let mut n = self.num_nodes;
-
-        let mut increasing_offsets = Vec::with_capacity(n);
-
-        while n > 0 {
-            curr += InOffsetCoding::read_next(&mut offsets_ibs, self.in_zeta_k);
-            increasing_offsets.push(curr as usize);
-
-            n -= 1;
-        }
-
-        self.loaded_offsets = increasing_offsets.into_boxed_slice();
-
-        self
+        Ok(self)
     }
 
     /// Creates a new binary wrapper around the previously-loaded graph.
@@ -1691,6 +2874,33 @@ impl<
         self
     }
 
+    /// Sets the number of decoded successor lists cached for random access, or 0 not to cache them.
+    /// 
+    /// The cache is consulted for every list decoded by random access, including the lists referred to by other
+    /// lists, and it evicts them with the CLOCK policy. Its counters are given by [`BVGraph::cache_stats()`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `capacity` - The maximum number of cached lists
+    pub fn set_cache_size(mut self, capacity: usize) -> Self {
+        self.cache_size = capacity;
+
+        self
+    }
+
+    /// Sets whether the graph and offsets files have to be memory-mapped instead of read in memory.
+    /// 
+    /// This has to be called before [`Self::load_graph()`] and [`Self::load_offsets()`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `flag` - Flag specifying whether to memory-map the files.
+    pub fn set_mmap(mut self, flag: bool) -> Self {
+        self.mmap = flag;
+
+        self
+    }
+
     /// Constructs the BVGraph object.
     pub fn build(self) -> BVGraph<
         InBlockCoding,
@@ -1707,20 +2917,23 @@ impl<
         OutReferenceCoding,
         OutIntervalCoding,
         OutResidualCoding,
+        O,
     > {
         BVGraph::<
             InBlockCoding, InBlockCountCoding, InOutdegreeCoding, InOffsetCoding, InReferenceCoding, InIntervalCoding, InResidualCoding, 
-            OutBlockCoding, OutBlockCountCoding, OutOutdegreeCoding, OutOffsetCoding, OutReferenceCoding, OutIntervalCoding, OutResidualCoding
+            OutBlockCoding, OutBlockCountCoding, OutOutdegreeCoding, OutOffsetCoding, OutReferenceCoding, OutIntervalCoding, OutResidualCoding, O
         > { 
             n: self.num_nodes, 
             m: self.num_edges, 
             graph_memory: self.loaded_graph, 
             offsets: self.loaded_offsets,
+            huffman_decoder: self.huffman_decoder,
             graph_binary_wrapper: Rc::new(RefCell::new(self.graph_binary_wrapper)),
             outdegrees_binary_wrapper: RefCell::new(self.outdegrees_binary_wrapper),
             cached_node: Cell::new(self.cached_node), 
             cached_outdegree: Cell::new(self.cached_outdegree), 
             cached_ptr: Cell::new(self.cached_ptr), 
+            cache: SharedCache::new(self.cache_size),
             in_max_ref_count: self.in_max_ref_count, 
             in_window_size: self.in_window_size,
             in_min_interval_len: self.in_min_interval_len,
@@ -1,33 +1,32 @@
 use std::{
-    borrow::BorrowMut,
     cell::{Cell, RefCell},
     cmp::Ordering,
-    collections::HashMap,
     fs,
     marker::PhantomData,
-    rc::Rc,
-    time::Instant,
     vec,
 };
 
-use sucds::{
-    mii_sequences::{EliasFano, EliasFanoBuilder},
-    Serializable,
-};
-
-use crate::bitstreams::{BinaryReader, BinaryWriter};
+use crate::bitstreams::{BinaryReader, BinaryWriter, InputStream};
 use crate::{
     huffman_zuckerli::huffman_encoder::HuffmanEncoder,
-    properties::Properties,
+    properties::{GraphFormat, Properties},
     utils::{
         encodings::{
-            zuck_encode, GammaCode, Huffman, UniversalCode, ZetaCode, I_ZUCK, J_ZUCK, K_ZUCK,
+            zuck_encode, GammaCode, Huff, Huffman, UnaryCode, UniversalCode, ZetaCode, I_ZUCK, J_ZUCK,
+            K_ZUCK,
         },
         int2nat, nat2int,
     },
-    ImmutableGraph,
+    Error, RandomAccessGraph, SequentialGraph, Store,
 };
 
+use super::container::{self, FileKind};
+use super::stats::CompressionStats;
+use super::offsets::{load_offsets, store_elias_fano};
+use super::nodes::NodeCheck;
+use super::parallel::{self, CompressedChunk, Concatenation};
+use super::sync::SyncPoints;
+
 pub const OUTD_IDX_BEGIN: usize = 0;
 pub const OUTD_IDX_LEN: usize = 32;
 pub const BLOCKS_IDX_BEGIN: usize = OUTD_IDX_BEGIN + OUTD_IDX_LEN;
@@ -48,6 +47,8 @@ struct CompressionVectors {
     left: RefCell<Vec<usize>>,
     len: RefCell<Vec<usize>>,
     residuals: RefCell<Vec<usize>>,
+    /// The positions in the bitstream where the reference, the blocks and the intervals of the last list end
+    section_ends: Cell<[usize; 3]>,
 }
 
 #[derive(Clone, Eq, PartialEq, Debug)]
@@ -69,7 +70,7 @@ pub struct BVGraph<
 > {
     n: usize,
     m: usize,
-    pub graph_memory: Rc<[u8]>,
+    pub graph_memory: InputStream,
     pub offsets: Box<[usize]>,
     graph_binary_wrapper: RefCell<BinaryReader>,
     outdegrees_binary_wrapper: RefCell<BinaryReader>,
@@ -84,6 +85,11 @@ pub struct BVGraph<
     out_min_interval_len: usize,
     in_zeta_k: Option<u64>,
     out_zeta_k: Option<u64>,
+    elias_fano: bool,
+    container: bool,
+    stats: bool,
+    threads: usize,
+    sync_interval: usize,
     compression_vectors: CompressionVectors,
     _phantom_in_block_coding: PhantomData<InBlockCoding>,
     _phantom_in_block_count_coding: PhantomData<InBlockCountCoding>,
@@ -116,7 +122,7 @@ impl<
         OutReferenceCoding: UniversalCode,
         OutIntervalCoding: Huffman,
         OutResidualCoding: Huffman,
-    > ImmutableGraph
+    > SequentialGraph
     for BVGraph<
         InBlockCoding,
         InBlockCountCoding,
@@ -148,6 +154,50 @@ impl<
         self.m
     }
 
+    /// Returns an iterator over the nodes of the BVGraph, each paired with its successors.
+    fn iter_nodes(&self) -> impl Iterator<Item = (Self::NodeT, Box<[Self::NodeT]>)> + '_ {
+        let mut iter = self.iter();
+
+        std::iter::from_fn(move || {
+            let node = iter.next()?;
+            Some((node, iter.successor_array()[..iter.outdegree()].into()))
+        })
+    }
+}
+
+impl<
+        InBlockCoding: UniversalCode,
+        InBlockCountCoding: UniversalCode,
+        InOutdegreeCoding: UniversalCode,
+        InOffsetCoding: UniversalCode,
+        InReferenceCoding: UniversalCode,
+        InIntervalCoding: UniversalCode,
+        InResidualCoding: UniversalCode,
+        OutBlockCoding: Huffman,
+        OutBlockCountCoding: UniversalCode,
+        OutOutdegreeCoding: Huffman,
+        OutOffsetCoding: UniversalCode,
+        OutReferenceCoding: UniversalCode,
+        OutIntervalCoding: Huffman,
+        OutResidualCoding: Huffman,
+    > RandomAccessGraph
+    for BVGraph<
+        InBlockCoding,
+        InBlockCountCoding,
+        InOutdegreeCoding,
+        InOffsetCoding,
+        InReferenceCoding,
+        InIntervalCoding,
+        InResidualCoding,
+        OutBlockCoding,
+        OutBlockCountCoding,
+        OutOutdegreeCoding,
+        OutOffsetCoding,
+        OutReferenceCoding,
+        OutIntervalCoding,
+        OutResidualCoding,
+    >
+{
     /// Returns the outdegree of a given node or `None` otherwise.
     ///
     /// # Arguments
@@ -194,38 +244,54 @@ impl<
         .into_boxed_slice()
     }
 
-    fn store(&mut self, basename: &str) -> std::io::Result<()> {
-        let mut graph_obs = BinaryWriter::new();
-        let mut offsets_obs = BinaryWriter::new();
-
-        self.compress(&mut graph_obs, &mut offsets_obs);
-
-        let graph = graph_obs.build();
-        let offsets = offsets_obs.build();
-        let props = Properties {
-            nodes: self.n,
-            arcs: self.m,
-            window_size: self.out_window_size,
-            max_ref_count: self.out_max_ref_count,
-            min_interval_len: self.out_min_interval_len,
-            zeta_k: self.out_zeta_k,
-            outdegree_coding: OutOutdegreeCoding::to_encoding_type(),
-            block_coding: OutBlockCoding::to_encoding_type(),
-            residual_coding: OutResidualCoding::to_encoding_type(),
-            interval_coding: OutIntervalCoding::to_encoding_type(),
-            reference_coding: OutReferenceCoding::to_encoding_type(),
-            block_count_coding: OutBlockCountCoding::to_encoding_type(),
-            offset_coding: OutOffsetCoding::to_encoding_type(),
-        };
-
-        fs::write(format!("{}.graph", basename), graph.os).unwrap();
-        fs::write(format!("{}.offsets", basename), offsets.os).unwrap();
-        fs::write(
-            format!("{}.properties", basename),
-            Into::<String>::into(props),
-        )?;
+    /// Returns an iterator over the successors of a given node.
+    ///
+    /// The source graph is a plain BVGraph read only to be recompressed, hence the list is decoded
+    /// eagerly through [`Self::successors()`].
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The node number
+    fn successors_iter(&self, x: usize) -> impl Iterator<Item = Self::NodeT> + '_ {
+        self.successors(x).into_vec().into_iter()
+    }
+}
 
-        Ok(())
+impl<
+        InBlockCoding: UniversalCode,
+        InBlockCountCoding: UniversalCode,
+        InOutdegreeCoding: UniversalCode,
+        InOffsetCoding: UniversalCode,
+        InReferenceCoding: UniversalCode,
+        InIntervalCoding: UniversalCode,
+        InResidualCoding: UniversalCode,
+        OutBlockCoding: Huffman,
+        OutBlockCountCoding: UniversalCode,
+        OutOutdegreeCoding: Huffman,
+        OutOffsetCoding: UniversalCode,
+        OutReferenceCoding: UniversalCode,
+        OutIntervalCoding: Huffman,
+        OutResidualCoding: Huffman,
+    > Store
+    for BVGraph<
+        InBlockCoding,
+        InBlockCountCoding,
+        InOutdegreeCoding,
+        InOffsetCoding,
+        InReferenceCoding,
+        InIntervalCoding,
+        InResidualCoding,
+        OutBlockCoding,
+        OutBlockCountCoding,
+        OutOutdegreeCoding,
+        OutOffsetCoding,
+        OutReferenceCoding,
+        OutIntervalCoding,
+        OutResidualCoding,
+    >
+{
+    fn store(&mut self, basename: &str) -> crate::Result<()> {
+        self.store_graph(&*self, basename)
     }
 }
 
@@ -1080,14 +1146,260 @@ impl<
         temp_list
     }
 
+    /// Compresses any sequential graph and stores it under the given basename, using the
+    /// output parameters of this graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The graph to compress
+    /// * `basename` - The basename of the output files
+    pub fn store_graph<G: SequentialGraph<NodeT = usize>>(
+        &self,
+        graph: &G,
+        basename: &str,
+    ) -> crate::Result<()> {
+        self.store_from(|| graph.iter_nodes(), basename)
+    }
+
+    /// Compresses a sequence of nodes, each paired with its successors, and stores it under
+    /// the given basename, using the output parameters of this graph.
+    ///
+    /// The nodes must be consecutive, starting from 0, and each successor list must be sorted.
+    /// At the first node out of place, [`Error::MisplacedNode`] is returned, and the files of any graph
+    /// with the same basename are left as they were.
+    /// The sequence is cloned since it is scanned twice, once to build the Huffman codes and
+    /// once to write the lists.
+    ///
+    /// # Arguments
+    ///
+    /// * `nodes` - The `(node, successors)` pairs to compress
+    /// * `basename` - The basename of the output files
+    pub fn store_nodes<I, L>(&self, nodes: I, basename: &str) -> crate::Result<()>
+    where
+        I: IntoIterator<Item = (usize, L)> + Clone,
+        L: AsRef<[usize]>,
+    {
+        self.store_from(|| nodes.clone(), basename)
+    }
+
+    fn store_from<I, L>(&self, nodes: impl Fn() -> I, basename: &str) -> crate::Result<()>
+    where
+        I: IntoIterator<Item = (usize, L)>,
+        L: AsRef<[usize]>,
+    {
+        let graph_path = format!("{}.graph", basename);
+        let mut graph_obs = BinaryWriter::with_sink(container::create(&graph_path)?);
+        let mut offsets_values = Vec::new();
+
+        let mut stats = self.stats.then(CompressionStats::default);
+
+        let check = NodeCheck::default();
+        let nodes = || check.check(nodes());
+        let (nodes, arcs) = if self.threads > 1 || self.sync_interval > 0 {
+            self.compress_parallel(nodes, &mut graph_obs, &mut offsets_values, stats.as_mut())
+        } else {
+            self.compress(nodes, &mut graph_obs, &mut offsets_values, stats.as_mut())
+        };
+
+        if let Err(e) = check.result() {
+            drop(graph_obs);
+            container::discard(&graph_path)?;
+            return Err(e);
+        }
+        graph_obs.finish().map_err(Error::io(&graph_path))?;
+        let props = Properties {
+            format: GraphFormat::Huffman,
+            nodes,
+            arcs,
+            window_size: self.out_window_size,
+            max_ref_count: self.out_max_ref_count,
+            min_interval_len: self.out_min_interval_len,
+            zeta_k: self.out_zeta_k,
+            outdegree_coding: OutOutdegreeCoding::to_encoding_type(),
+            block_coding: OutBlockCoding::to_encoding_type(),
+            residual_coding: OutResidualCoding::to_encoding_type(),
+            interval_coding: OutIntervalCoding::to_encoding_type(),
+            reference_coding: OutReferenceCoding::to_encoding_type(),
+            block_count_coding: OutBlockCountCoding::to_encoding_type(),
+            offset_coding: OutOffsetCoding::to_encoding_type(),
+        };
+
+        let container = self.container.then_some(&props);
+
+        container::seal(&graph_path, FileKind::Graph, container)?;
+
+        if self.sync_interval > 0 {
+            SyncPoints::from_offsets(self.sync_interval, &offsets_values).store(basename, container)?;
+        }
+
+        if self.elias_fano {
+            store_elias_fano(&offsets_values, format!("{}.offsets.ef", basename), container)?;
+        } else {
+            let offsets_path = format!("{}.offsets", basename);
+            let mut offsets_obs = BinaryWriter::with_sink(container::create(&offsets_path)?);
+            let mut prev = 0;
+
+            for &offset in offsets_values.iter() {
+                self.write_offset(&mut offsets_obs, offset - prev).unwrap();
+                prev = offset;
+            }
+
+            offsets_obs.finish().map_err(Error::io(&offsets_path))?;
+            container::seal(&offsets_path, FileKind::Offsets, container)?;
+        }
+
+        let properties_path = format!("{}.properties", basename);
+        fs::write(&properties_path, Into::<String>::into(props)).map_err(Error::io(&properties_path))?;
+
+        if let Some(stats) = stats {
+            stats.store(format!("{}.stats", basename))?;
+        }
+
+        Ok(())
+    }
+
+    /// Compresses a sequence of nodes, each paired with its successors, writing the lists to
+    /// `graph_obs` and the bit offset of each of them to `offsets_values`, and gathering the
+    /// statistics of the compression in `stats`, if given.
+    ///
+    /// The sequence is obtained twice through `nodes`: the first scan collects the values of
+    /// each context to build the Huffman codes, the second one writes the lists.
+    ///
+    /// Returns the number of nodes and arcs that were compressed.
     #[inline(always)]
-    pub(crate) fn compress(
-        &mut self,
+    pub(crate) fn compress<I, L>(
+        &self,
+        nodes: impl Fn() -> I,
         graph_obs: &mut BinaryWriter,
-        offsets_obs: &mut BinaryWriter,
-    ) {
-        let mut bit_offset: usize = 0;
+        offsets_values: &mut Vec<usize>,
+        mut stats: Option<&mut CompressionStats>,
+    ) -> (usize, usize)
+    where
+        I: IntoIterator<Item = (usize, L)>,
+        L: AsRef<[usize]>,
+    {
+        const V: Vec<usize> = Vec::new();
+
+        let mut values = [V; NUM_CONTEXTS];
+        // List of (best_candidate, best_reference) tuples which prevents recomputing the best candidate for each node
+        let mut best_candidates = Vec::new();
+
+        let (num_nodes, num_arcs) = self.first_pass(nodes(), &mut values, &mut best_candidates);
+
+        let mut huff = Self::init_huffman(&values, graph_obs, stats.as_deref_mut());
+
+        self.second_pass(nodes(), &best_candidates, &mut huff, graph_obs, offsets_values, stats.as_deref_mut());
+
+        offsets_values.push(graph_obs.written_bits);
+
+        if let Some(stats) = stats {
+            stats.nodes = num_nodes;
+            stats.arcs = num_arcs;
+            stats.graph_bits = graph_obs.written_bits as u64;
+            stats.bits_per_context = huff.bits_per_context()[..NUM_CONTEXTS].to_vec();
+        }
+
+        (num_nodes, num_arcs)
+    }
+
+    /// Compresses a sequence of nodes as [`Self::compress()`] does, but splitting it in chunks of
+    /// [`CHUNK_SIZE`](super::parallel::CHUNK_SIZE) nodes (or of the sync interval, if any) that are compressed
+    /// in parallel and then concatenated. Both scans run in parallel, the values of each context being merged
+    /// after the first one.
+    ///
+    /// Returns the number of nodes and arcs that were compressed.
+    pub(crate) fn compress_parallel<I, L>(
+        &self,
+        nodes: impl Fn() -> I,
+        graph_obs: &mut BinaryWriter,
+        offsets_values: &mut Vec<usize>,
+        mut stats: Option<&mut CompressionStats>,
+    ) -> (usize, usize)
+    where
+        I: IntoIterator<Item = (usize, L)>,
+        L: AsRef<[usize]>,
+    {
+        const V: Vec<usize> = Vec::new();
+
+        let first_passes = parallel::map_chunks(nodes(), parallel::chunk_size(self.sync_interval), self.threads, self.workers(), |worker, _, chunk| {
+            let mut values = [V; NUM_CONTEXTS];
+            let mut best_candidates = Vec::with_capacity(chunk.len());
+
+            worker.first_pass(chunk.iter(), &mut values, &mut best_candidates);
+
+            (values, best_candidates)
+        });
+
+        let mut values = [V; NUM_CONTEXTS];
+        let mut best_candidates = Vec::with_capacity(first_passes.len());
+        for (chunk_values, chunk_best_candidates) in first_passes {
+            for (ctx, chunk_ctx) in values.iter_mut().zip(chunk_values) {
+                ctx.extend(chunk_ctx);
+            }
+            best_candidates.push(chunk_best_candidates);
+        }
+
+        let huff = Self::init_huffman(&values, graph_obs, stats.as_deref_mut());
+        drop(values);
+
+        let gather_stats = stats.is_some();
+        let mut concatenation = Concatenation::new(graph_obs, offsets_values, stats);
 
+        parallel::for_each_chunk(nodes(), parallel::chunk_size(self.sync_interval), self.threads, self.workers(), |worker, index, chunk| {
+            let mut huff = huff.clone();
+            let mut compressed = CompressedChunk {
+                graph_obs: BinaryWriter::new(),
+                offsets: Vec::with_capacity(chunk.len() + 1),
+                stats: gather_stats.then(CompressionStats::default),
+                arcs: chunk.iter().map(|(_, successors)| successors.len()).sum(),
+            };
+
+            worker.second_pass(
+                chunk.iter(),
+                &best_candidates[index],
+                &mut huff,
+                &mut compressed.graph_obs,
+                &mut compressed.offsets,
+                compressed.stats.as_mut(),
+            );
+            compressed.offsets.push(compressed.graph_obs.written_bits);
+
+            if let Some(stats) = compressed.stats.as_mut() {
+                stats.bits_per_context = huff.bits_per_context()[..NUM_CONTEXTS].to_vec();
+            }
+
+            compressed
+        }, |chunk| concatenation.push(chunk));
+
+        concatenation.finish()
+    }
+
+    /// Returns a function building graphs with the output parameters of this one, each with its own
+    /// compression buffers, so that chunks of nodes can be compressed on other threads.
+    fn workers(&self) -> impl Fn() -> Self + Sync {
+        let (max_ref_count, window_size) = (self.out_max_ref_count, self.out_window_size);
+        let (min_interval_len, zeta_k) = (self.out_min_interval_len, self.out_zeta_k);
+
+        move || {
+            BVGraphBuilder::new()
+                .set_out_max_ref_count(max_ref_count)
+                .set_out_window_size(window_size)
+                .set_out_min_interval_len(min_interval_len)
+                .set_out_zeta(zeta_k)
+                .build()
+        }
+    }
+
+    /// Chooses the reference of each list, pushing it to `best_candidates`, and collects the values
+    /// to be written in each context.
+    ///
+    /// Returns the number of nodes and arcs that were scanned.
+    fn first_pass<L: AsRef<[usize]>>(
+        &self,
+        nodes: impl IntoIterator<Item = (usize, L)>,
+        values: &mut [Vec<usize>; NUM_CONTEXTS],
+        best_candidates: &mut Vec<(usize, usize)>,
+    ) -> (usize, usize) {
         let mut bit_count = BinaryWriter::new();
 
         let cyclic_buffer_size = self.out_window_size + 1;
@@ -1098,19 +1410,13 @@ impl<
         // The depth of the references of each list
         let mut ref_count: Vec<i32> = vec![0; cyclic_buffer_size];
 
-        // List of (best_candidate, best_reference) tuples which prevents recomputing the best candidate for each node
-        let mut best_candidates = vec![(0, 0); self.n];
-
-        let mut node_iter = self.iter();
-
-        const V: Vec<usize> = Vec::new();
-
-        let mut values = [V; NUM_CONTEXTS];
+        let mut num_nodes = 0;
+        let mut num_arcs = 0;
 
         // Populate the above vectors with their respective values
-        while node_iter.has_next() {
-            let curr_node = node_iter.next().unwrap();
-            let outd = node_iter.outdegree();
+        for (curr_node, successors) in nodes {
+            let successors = successors.as_ref();
+            let outd = successors.len();
             let curr_idx = curr_node % cyclic_buffer_size;
 
             let ctx = if curr_node == 0 || curr_node % 32 == 0 {
@@ -1124,13 +1430,14 @@ impl<
             };
             values[OUTD_IDX_BEGIN + ctx].push(outd);
 
-            if outd > list[curr_idx].len() {
-                list[curr_idx].resize(outd, 0);
-            }
-
-            list[curr_idx] = Vec::from(&node_iter.successor_array()[..outd]);
+            list[curr_idx].clear();
+            list[curr_idx].extend_from_slice(successors);
             list_len[curr_idx] = outd;
 
+            num_nodes += 1;
+            num_arcs += outd;
+            let mut best_candidate = (0, 0);
+
             if outd > 0 {
                 let mut best_comp = i64::MAX;
                 let mut best_cand = -1;
@@ -1171,43 +1478,67 @@ impl<
                     best_ref as usize,
                     list[best_cand as usize].as_slice(),
                     list[curr_idx].as_slice(),
-                    &mut values,
+                    values,
                 );
 
-                best_candidates[curr_node] = (best_cand as usize, best_ref as usize);
+                best_candidate = (best_cand as usize, best_ref as usize);
             }
+
+            best_candidates.push(best_candidate);
         }
 
-        debug_assert_eq!(graph_obs.written_bits, 0);
+        (num_nodes, num_arcs)
+    }
 
-        for (i, ctx) in values.iter().enumerate() {
-            if ctx.is_empty() {
-                println!("context {i} is empty");
-            }
-        }
+    /// Builds the Huffman codes of the values of each context and writes their headers to `graph_obs`,
+    /// which must be empty.
+    fn init_huffman(
+        values: &[Vec<usize>; NUM_CONTEXTS],
+        graph_obs: &mut BinaryWriter,
+        stats: Option<&mut CompressionStats>,
+    ) -> HuffmanEncoder {
+        debug_assert_eq!(graph_obs.written_bits, 0);
 
         // Create Huffman encoder
         let mut huff = HuffmanEncoder::new();
 
         // Write Huffman headers
-        huff.init(&values, graph_obs);
+        huff.init(values, graph_obs);
+
+        if let Some(stats) = stats {
+            stats.header_bits = graph_obs.written_bits as u64;
+        }
 
-        println!("Headers took {} bits", graph_obs.written_bits);
+        huff
+    }
+
+    /// Writes the lists with the references chosen by [`Self::first_pass()`], pushing the offset of each
+    /// of them to `offsets_values` (but not the final one).
+    fn second_pass<L: AsRef<[usize]>>(
+        &self,
+        nodes: impl IntoIterator<Item = (usize, L)>,
+        best_candidates: &[(usize, usize)],
+        huff: &mut HuffmanEncoder,
+        graph_obs: &mut BinaryWriter,
+        offsets_values: &mut Vec<usize>,
+        mut stats: Option<&mut CompressionStats>,
+    ) {
+        let cyclic_buffer_size = self.out_window_size + 1;
+        // Cyclic array of previous lists
+        let mut list = vec![vec![0; 1024]; cyclic_buffer_size];
+        // The depth of the references of each list
+        let mut ref_count: Vec<i32> = vec![0; cyclic_buffer_size];
 
         // Now, compress each node
-        node_iter = self.iter();
-        while node_iter.has_next() {
-            let curr_node = node_iter.next().unwrap();
-            let outd = node_iter.outdegree();
+        for ((curr_node, successors), &(best_cand, best_ref)) in nodes.into_iter().zip(best_candidates) {
+            let successors = successors.as_ref();
+            let outd = successors.len();
             let curr_idx = curr_node % cyclic_buffer_size;
 
             // println!("Curr node: {}, outdegree: {}", curr_node, outd);
 
-            // We write the final offset to the offsets stream
-            self.write_offset(offsets_obs, graph_obs.written_bits - bit_offset)
-                .unwrap();
-
-            bit_offset = graph_obs.written_bits;
+            // We add the final offset to the offsets
+            offsets_values.push(graph_obs.written_bits);
 
             let ctx = if curr_node == 0 || curr_node % 32 == 0 {
                 0
@@ -1219,31 +1550,50 @@ impl<
             // Encode through Huffman
             huff.write_next(outd, graph_obs, OUTD_IDX_BEGIN + ctx);
 
-            if outd > list[curr_idx].len() {
-                list[curr_idx].resize(outd, 0);
+            if let Some(stats) = stats.as_deref_mut() {
+                stats.outdegree_bits += (graph_obs.written_bits - offsets_values[offsets_values.len() - 1]) as u64;
             }
 
-            list[curr_idx] = Vec::from(&node_iter.successor_array()[..outd]);
-            list_len[curr_idx] = outd;
+            list[curr_idx].clear();
+            list[curr_idx].extend_from_slice(successors);
 
             if outd > 0 {
-                let (best_cand, best_ref) = best_candidates[curr_node];
+                // The list is its own candidate when it has no reference
+                ref_count[curr_idx] = if best_ref == 0 { 0 } else { ref_count[best_cand] + 1 };
 
-                ref_count[curr_idx] = ref_count[best_cand] + 1;
+                let list_start = graph_obs.written_bits;
                 self.diff_comp(
                     graph_obs,
                     curr_node,
                     best_ref,
                     list[best_cand].as_slice(),
                     list[curr_idx].as_slice(),
-                    Some(&mut huff),
+                    Some(huff),
                 )
                 .unwrap();
+
+                if let Some(stats) = stats.as_deref_mut() {
+                    stats.add_reference(best_ref, ref_count[curr_idx] as usize);
+                    stats.add_sections(
+                        list_start,
+                        self.compression_vectors.section_ends.get(),
+                        graph_obs.written_bits,
+                    );
+
+                    let residuals = if self.out_min_interval_len != 0 {
+                        self.compression_vectors.residuals.borrow()
+                    } else {
+                        self.compression_vectors.extras.borrow()
+                    };
+                    stats.add_list(
+                        curr_node,
+                        &list[curr_idx],
+                        self.compression_vectors.len.borrow().iter().sum(),
+                        &residuals,
+                    );
+                }
             }
         }
-
-        self.write_offset(offsets_obs, graph_obs.written_bits - bit_offset)
-            .unwrap();
     }
 
     #[inline(always)]
@@ -1408,6 +1758,7 @@ impl<
         if self.out_window_size > 0 {
             _t = self.write_reference(graph_obs, reference)?;
         }
+        let reference_end = graph_obs.written_bits;
 
         // Then, if the reference is not void we write the length of the copy list
         if reference != 0 {
@@ -1445,6 +1796,8 @@ impl<
                 }
             }
         }
+        let blocks_end = graph_obs.written_bits;
+        let mut intervals_end = blocks_end;
 
         // Finally, we write the extra list
         if extra_count > 0 {
@@ -1550,6 +1903,7 @@ impl<
                 residual_count = self.compression_vectors.extras.borrow().len();
                 residual = self.compression_vectors.extras.borrow();
             }
+            intervals_end = graph_obs.written_bits;
 
             // Now we write out the residuals, if any
             if residual_count != 0 {
@@ -1608,6 +1962,10 @@ impl<
             }
         }
 
+        self.compression_vectors
+            .section_ends
+            .set([reference_end, blocks_end, intervals_end]);
+
         Ok(graph_obs.written_bits /* graph_obs.len() */ - written_data_at_start)
     }
 
@@ -1832,6 +2190,13 @@ impl<
     }
 }
 
+/// The builder compressing graphs in the Huffman format with the codes the `webgraph` binary uses: Huffman codes
+/// for the blocks, outdegrees, intervals and residuals, and universal codes for the rest.
+pub type DefaultBVGraphBuilder = BVGraphBuilder<
+    GammaCode, GammaCode, GammaCode, GammaCode, UnaryCode, GammaCode, ZetaCode,
+    Huff, GammaCode, Huff, GammaCode, UnaryCode, Huff, Huff,
+>;
+
 pub struct BVGraphBuilder<
     InBlockCoding: UniversalCode,
     InBlockCountCoding: UniversalCode,
@@ -1850,7 +2215,7 @@ pub struct BVGraphBuilder<
 > {
     num_nodes: usize,
     num_edges: usize,
-    loaded_graph: Rc<[u8]>,
+    loaded_graph: InputStream,
     loaded_offsets: Box<[usize]>,
     graph_binary_wrapper: BinaryReader,
     outdegrees_binary_wrapper: BinaryReader,
@@ -1865,6 +2230,12 @@ pub struct BVGraphBuilder<
     out_min_interval_len: usize,
     in_zeta_k: Option<u64>,
     out_zeta_k: Option<u64>,
+    elias_fano: bool,
+    container: bool,
+    stats: bool,
+    threads: usize,
+    sync_interval: usize,
+    mmap: bool,
     _phantom_in_block_coding: PhantomData<InBlockCoding>,
     _phantom_in_block_count_coding: PhantomData<InBlockCountCoding>,
     _phantom_in_outdegree_coding: PhantomData<InOutdegreeCoding>,
@@ -1918,7 +2289,7 @@ impl<
         Self {
             num_nodes: 0,
             num_edges: 0,
-            loaded_graph: Rc::new([]),
+            loaded_graph: InputStream::default(),
             loaded_offsets: Box::default(),
             graph_binary_wrapper: BinaryReader::default(),
             outdegrees_binary_wrapper: BinaryReader::default(),
@@ -1933,6 +2304,12 @@ impl<
             out_min_interval_len: 4,
             in_zeta_k: None,
             out_zeta_k: Some(3),
+            elias_fano: false,
+            container: false,
+            stats: false,
+            threads: 1,
+            sync_interval: 0,
+            mmap: false,
             _phantom_in_block_coding: PhantomData,
             _phantom_in_block_count_coding: PhantomData,
             _phantom_in_outdegree_coding: PhantomData,
@@ -2019,17 +2396,13 @@ impl<
     ///     GammaCode, GammaCode, UnaryCode, DeltaCode, GammaCode, GammaCode, ZetaCode
     /// >::new()
     ///     .load_properties(file_base_name);
-    ///     .load_graph(file_base_name);
+    ///     .load_graph(file_base_name)?;
     /// ```
-    pub fn load_graph(mut self, basename: &str) -> Self {
-        let graph = fs::read(format!("{}.graph", basename)).unwrap();
-
-        let graph = graph.into_boxed_slice().into();
-
-        self.loaded_graph = graph;
+    pub fn load_graph(mut self, basename: &str) -> crate::Result<Self> {
+        self.loaded_graph = container::load(format!("{}.graph", basename), self.mmap, FileKind::Graph)?;
         self.graph_binary_wrapper = BinaryReader::new(self.loaded_graph.clone());
 
-        self
+        Ok(self)
     }
 
     /// Loads a previously-compressed BVGraph's offsets file.
@@ -2048,32 +2421,18 @@ impl<
     ///     GammaCode, GammaCode, UnaryCode, DeltaCode, GammaCode, GammaCode, ZetaCode
     /// >::new()
     ///     .load_properties(file_base_name);
-    ///     .load_graph(file_base_name);
-    ///     .load_offsets(file_base_name);
+    ///     .load_graph(file_base_name)?;
+    ///     .load_offsets(file_base_name)?;
     /// let graph = builder.build();
     /// ```
-    pub fn load_offsets(mut self, basename: &str) -> Self {
+    pub fn load_offsets(mut self, basename: &str) -> crate::Result<Self> {
         assert!(self.num_nodes > 0, "The number of nodes has to be >0.");
-        let offsets = fs::read(format!("{}.offsets", basename)).unwrap();
-
-        let mut curr = 0;
-
-        let mut offsets_ibs = BinaryReader::new(offsets.into());
-
-        let mut n = self.num_nodes;
-
-        let mut increasing_offsets = Vec::with_capacity(n);
 
-        while n > 0 {
-            curr += InOffsetCoding::read_next(&mut offsets_ibs, self.in_zeta_k);
-            increasing_offsets.push(curr as usize);
+        self.loaded_offsets = load_offsets(basename, self.num_nodes, &self.loaded_graph, self.mmap, |offsets_ibs| {
+            InOffsetCoding::try_read_next(offsets_ibs, self.in_zeta_k)
+        })?;
 
-            n -= 1;
-        }
-
-        self.loaded_offsets = increasing_offsets.into_boxed_slice();
-
-        self
+        Ok(self)
     }
 
     /// Creates a new binary wrapper around the previously-loaded graph.
@@ -2197,6 +2556,85 @@ impl<
         self
     }
 
+    /// Sets whether the offsets have to be Elias-Fano encoded.
+    ///
+    /// # Arguments
+    ///
+    /// * `flag` - Flag specifying whether to encode offsets through Elias-Fano.
+    pub fn set_elias_fano(mut self, flag: bool) -> Self {
+        self.elias_fano = flag;
+
+        self
+    }
+
+    /// Sets whether the written files have to be preceded by a container header, which identifies them
+    /// and records their checksums (see [`container`]).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `flag` - Flag specifying whether to write the files in containers.
+    pub fn set_container(mut self, flag: bool) -> Self {
+        self.container = flag;
+
+        self
+    }
+
+    /// Sets whether a `.stats` file has to be written together with the graph, reporting where
+    /// the bits of the compressed graph go (see [`CompressionStats`]).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `flag` - Flag specifying whether to write the statistics.
+    pub fn set_stats(mut self, flag: bool) -> Self {
+        self.stats = flag;
+
+        self
+    }
+
+    /// Sets the number of threads compressing the graph.
+    ///
+    /// With more than one thread, the graph is split in chunks of [`CHUNK_SIZE`](super::parallel::CHUNK_SIZE)
+    /// nodes that are compressed in parallel and then concatenated. Each chunk starts with an empty window,
+    /// so its lists only refer to lists of the same chunk: the output does not depend on the number of threads,
+    /// but its size differs slightly from the one written by a single thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `threads` - The number of threads
+    pub fn set_threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+
+        self
+    }
+
+    /// Sets the interval of the sync points written together with the graph, or 0 not to write them.
+    /// 
+    /// With a positive interval `k`, the graph is compressed in chunks of `k` nodes, as by [`Self::set_threads()`],
+    /// so that a scan can start at any multiple of `k` with an empty window, and the offsets of these nodes
+    /// are written to a `.sync` file (see [`SyncPoints`]).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `interval` - The number of nodes between two sync points
+    pub fn set_sync_interval(mut self, interval: usize) -> Self {
+        self.sync_interval = interval;
+
+        self
+    }
+
+    /// Sets whether the graph and offsets files have to be memory-mapped instead of read in memory.
+    ///
+    /// This has to be called before [`Self::load_graph()`] and [`Self::load_offsets()`].
+    ///
+    /// # Arguments
+    ///
+    /// * `flag` - Flag specifying whether to memory-map the files.
+    pub fn set_mmap(mut self, flag: bool) -> Self {
+        self.mmap = flag;
+
+        self
+    }
+
     /// Constructs the BVGraph object.
     pub fn build(
         self,
@@ -2249,6 +2687,11 @@ impl<
             out_min_interval_len: self.out_min_interval_len,
             in_zeta_k: self.in_zeta_k,
             out_zeta_k: self.out_zeta_k,
+            elias_fano: self.elias_fano,
+            container: self.container,
+            stats: self.stats,
+            threads: self.threads,
+            sync_interval: self.sync_interval,
             compression_vectors: CompressionVectors::default(),
             _phantom_in_block_coding: PhantomData,
             _phantom_in_block_count_coding: PhantomData,
@@ -0,0 +1,318 @@
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+/// The counters of a [`ListCache`].
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub struct CacheStats {
+    /// The lookups finding the list, pinned or cached
+    pub hits: u64,
+    /// The lookups not finding the list, which had to be decoded
+    pub misses: u64,
+    /// The lists evicted to make room for others
+    pub evictions: u64,
+    /// The number of pinned lists
+    pub pinned: usize,
+}
+
+impl CacheStats {
+    /// Returns the fraction of lookups finding the list, or 0 if there were none.
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+
+        if lookups == 0 { 0. } else { self.hits as f64 / lookups as f64 }
+    }
+}
+
+/// Which lists are pinned in the cache of a graph, that is kept decoded for its whole life.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Pinning {
+    /// The lists of the given number of nodes with the highest outdegree
+    Degree(usize),
+    /// The lists of the given number of nodes referred to by the most lists
+    References(usize),
+}
+
+impl Pinning {
+    /// Returns the nodes to pin, the highest-ranking first.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_nodes` - The number of nodes of the graph
+    /// * `outdegree` - Returns the outdegree of a node
+    /// * `reference` - Returns the reference of the list of a node, that is the distance of the list it refers to,
+    ///   or 0 if it does not refer to any
+    pub(crate) fn select(
+        &self,
+        num_nodes: usize,
+        outdegree: impl FnMut(usize) -> usize,
+        mut reference: impl FnMut(usize) -> usize,
+    ) -> Vec<usize> {
+        let (scores, count) = match *self {
+            Pinning::Degree(count) => ((0..num_nodes).map(outdegree).collect(), count),
+            Pinning::References(count) => {
+                let mut references = vec![0; num_nodes];
+                for x in 0..num_nodes {
+                    let r = reference(x);
+                    if r > 0 {
+                        references[x - r] += 1;
+                    }
+                }
+
+                (references, count)
+            },
+        };
+
+        let mut nodes: Vec<usize> = (0..num_nodes).filter(|&x| scores[x] > 0).collect();
+        nodes.sort_unstable_by_key(|&x| (Reverse(scores[x]), x));
+        nodes.truncate(count);
+
+        nodes
+    }
+}
+
+/// A cached list, with the reference bit of the CLOCK policy.
+#[derive(Clone, Eq, PartialEq, Debug)]
+struct Slot {
+    node: usize,
+    list: Vec<usize>,
+    referenced: bool,
+}
+
+/// A bounded cache of decoded successor lists, evicting them with the CLOCK policy.
+///
+/// Besides the cached lists, whose number is bounded by the capacity, the cache holds the pinned lists,
+/// which are never evicted.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct ListCache {
+    capacity: usize,
+    slots: Vec<Slot>,
+    /// The slot of each cached node
+    index: HashMap<usize, usize>,
+    /// The next slot considered for eviction
+    hand: usize,
+    pinned: HashMap<usize, Box<[usize]>>,
+    stats: CacheStats,
+}
+
+impl ListCache {
+    /// Creates an empty cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The maximum number of cached lists, pinned lists excluded
+    pub fn new(capacity: usize) -> Self {
+        ListCache { capacity, ..Default::default() }
+    }
+
+    /// Returns the list of `x`, if pinned or cached, counting the lookup.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The node number
+    pub fn get(&mut self, x: usize) -> Option<&[usize]> {
+        if let Some(list) = self.pinned.get(&x) {
+            self.stats.hits += 1;
+            return Some(list);
+        }
+
+        match self.index.get(&x) {
+            Some(&i) => {
+                self.stats.hits += 1;
+                let slot = &mut self.slots[i];
+                slot.referenced = true;
+
+                Some(&slot.list)
+            },
+            None => {
+                self.stats.misses += 1;
+
+                None
+            },
+        }
+    }
+
+    /// Caches the list of `x`, evicting the first list not referenced since the hand of the clock last passed it.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The node number
+    /// * `list` - The successors of `x`
+    pub fn insert(&mut self, x: usize, list: &[usize]) {
+        if self.capacity == 0 || self.pinned.contains_key(&x) || self.index.contains_key(&x) {
+            return;
+        }
+
+        if self.slots.len() < self.capacity {
+            self.index.insert(x, self.slots.len());
+            self.slots.push(Slot { node: x, list: list.to_vec(), referenced: false });
+            return;
+        }
+
+        while self.slots[self.hand].referenced {
+            self.slots[self.hand].referenced = false;
+            self.hand = (self.hand + 1) % self.capacity;
+        }
+
+        // The list of the evicted node is overwritten, reusing its memory
+        let slot = &mut self.slots[self.hand];
+        self.index.remove(&slot.node);
+        self.index.insert(x, self.hand);
+        slot.node = x;
+        slot.list.clear();
+        slot.list.extend_from_slice(list);
+        self.hand = (self.hand + 1) % self.capacity;
+        self.stats.evictions += 1;
+    }
+
+    /// Pins the list of `x`, which is then never evicted.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The node number
+    /// * `list` - The successors of `x`
+    pub fn pin(&mut self, x: usize, list: Box<[usize]>) {
+        self.pinned.insert(x, list);
+        self.stats.pinned = self.pinned.len();
+    }
+
+    /// Returns the counters of the cache.
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+}
+
+/// The maximum number of shards of a [`SharedCache`].
+const SHARDS: usize = 64;
+
+/// The optional cache of a graph, which can be shared by the threads querying it.
+///
+/// The pinned lists are only written before the graph is shared, and are read without locking. The other lists are
+/// cached in shards, each caching the lists of the nodes congruent to its index modulo their number with a
+/// [`ListCache`] of its own, so that threads querying different nodes seldom wait for each other.
+///
+/// A clone of a graph gets a copy of its cache.
+#[derive(Default)]
+pub(crate) struct SharedCache {
+    pinned: HashMap<usize, Box<[usize]>>,
+    /// The lookups finding a pinned list
+    pinned_hits: AtomicU64,
+    /// The lookups not finding a pinned list, when there are no shards to count them
+    pinned_misses: AtomicU64,
+    shards: Box<[Mutex<ListCache>]>,
+}
+
+impl SharedCache {
+    /// Creates a cache of the given capacity, or no cache if it is 0.
+    ///
+    /// The capacity is split evenly among the shards, so each of them holds at least a list.
+    pub(crate) fn new(capacity: usize) -> Self {
+        let shards = capacity.min(SHARDS);
+        let shards = (0..shards)
+            .map(|i| Mutex::new(ListCache::new(capacity / shards + usize::from(i < capacity % shards))))
+            .collect();
+
+        SharedCache { shards, ..Default::default() }
+    }
+
+    /// Returns whether the cache is present, that is, whether it has a capacity or pinned lists.
+    fn is_present(&self) -> bool {
+        !self.shards.is_empty() || !self.pinned.is_empty()
+    }
+
+    /// Returns the shard caching the list of `x`, if the cache has a capacity.
+    fn shard(&self, x: usize) -> Option<&Mutex<ListCache>> {
+        self.shards.get(x % self.shards.len().max(1))
+    }
+
+    /// Writes the list of `x` into `list`, replacing its content, if the cache is present and holds it.
+    /// Returns whether it does.
+    pub(crate) fn get_into(&self, x: usize, list: &mut Vec<usize>) -> bool {
+        if let Some(pinned) = self.pinned.get(&x) {
+            self.pinned_hits.fetch_add(1, Ordering::Relaxed);
+            list.clear();
+            list.extend_from_slice(pinned);
+
+            return true;
+        }
+
+        let Some(shard) = self.shard(x) else {
+            if !self.pinned.is_empty() {
+                self.pinned_misses.fetch_add(1, Ordering::Relaxed);
+            }
+
+            return false;
+        };
+
+        match shard.lock().unwrap().get(x) {
+            Some(cached) => {
+                list.clear();
+                list.extend_from_slice(cached);
+
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Caches the list of `x`, if the cache has a capacity and the list is not pinned.
+    pub(crate) fn insert(&self, x: usize, list: &[usize]) {
+        if self.pinned.contains_key(&x) {
+            return;
+        }
+
+        if let Some(shard) = self.shard(x) {
+            shard.lock().unwrap().insert(x, list);
+        }
+    }
+
+    /// Pins the given lists, which are never evicted.
+    pub(crate) fn pin(&mut self, lists: impl IntoIterator<Item = (usize, Box<[usize]>)>) {
+        self.pinned.extend(lists);
+    }
+
+    /// Returns the counters of the cache, added up over its shards, if present.
+    pub(crate) fn stats(&self) -> Option<CacheStats> {
+        if !self.is_present() {
+            return None;
+        }
+
+        let mut stats = CacheStats {
+            hits: self.pinned_hits.load(Ordering::Relaxed),
+            misses: self.pinned_misses.load(Ordering::Relaxed),
+            pinned: self.pinned.len(),
+            ..Default::default()
+        };
+        for shard in self.shards.iter() {
+            let shard_stats = shard.lock().unwrap().stats();
+            stats.hits += shard_stats.hits;
+            stats.misses += shard_stats.misses;
+            stats.evictions += shard_stats.evictions;
+        }
+
+        Some(stats)
+    }
+}
+
+impl Clone for SharedCache {
+    fn clone(&self) -> Self {
+        SharedCache {
+            pinned: self.pinned.clone(),
+            pinned_hits: AtomicU64::new(self.pinned_hits.load(Ordering::Relaxed)),
+            pinned_misses: AtomicU64::new(self.pinned_misses.load(Ordering::Relaxed)),
+            shards: self.shards.iter().map(|shard| Mutex::new(shard.lock().unwrap().clone())).collect(),
+        }
+    }
+}
+
+impl Debug for SharedCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SharedCache").field(&self.stats()).finish()
+    }
+}
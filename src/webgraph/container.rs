@@ -0,0 +1,477 @@
+use std::{fmt::Display, fs::{self, File}, io::{self, BufReader, BufWriter, Read, Write}, path::{Path, PathBuf}};
+
+use crc32fast::Hasher;
+
+use crate::{bitstreams::InputStream, properties::Properties, Error, Result};
+
+/// The bytes a container starts with.
+pub const MAGIC: [u8; 8] = *b"WEBGRAPH";
+/// The version of the container layout written by this crate.
+pub const VERSION: u16 = 1;
+/// The size of the chunks of the payload whose checksums are recorded in the header.
+pub const CHUNK_SIZE: usize = 1 << 20;
+
+/// The file of a graph held by a container.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum FileKind {
+    /// The successor lists (`.graph`)
+    Graph,
+    /// The gap-encoded offsets (`.offsets`)
+    Offsets,
+    /// The Elias-Fano encoded offsets (`.offsets.ef`)
+    EliasFanoOffsets,
+    /// The sync points (`.sync`)
+    SyncPoints,
+}
+
+impl FileKind {
+    fn id(self) -> u8 {
+        match self {
+            FileKind::Graph => 0,
+            FileKind::Offsets => 1,
+            FileKind::EliasFanoOffsets => 2,
+            FileKind::SyncPoints => 3,
+        }
+    }
+
+    fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(FileKind::Graph),
+            1 => Some(FileKind::Offsets),
+            2 => Some(FileKind::EliasFanoOffsets),
+            3 => Some(FileKind::SyncPoints),
+            _ => None,
+        }
+    }
+}
+
+impl Display for FileKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileKind::Graph => f.write_str("a graph"),
+            FileKind::Offsets => f.write_str("offsets"),
+            FileKind::EliasFanoOffsets => f.write_str("Elias-Fano offsets"),
+            FileKind::SyncPoints => f.write_str("sync points"),
+        }
+    }
+}
+
+/// The header of a container, identifying the file it precedes and the graph the file belongs to.
+///
+/// It is made of, in order and with integers in little-endian order:
+/// - the [`MAGIC`] bytes;
+/// - the container version (16 bits), the kind of file (8 bits) and a reserved byte;
+/// - the length (32 bits) and the text of the properties of the graph, in the format of `.properties` files,
+///   which record the graph format, its parameters and the codes of its sections;
+/// - the length of the payload (64 bits), the size of its chunks (32 bits), and the CRC-32 of the whole payload
+///   followed by the one of each chunk;
+/// - the CRC-32 of the header itself.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Header {
+    pub version: u16,
+    pub kind: FileKind,
+    pub properties: Properties,
+    pub payload_len: u64,
+    pub chunk_size: u32,
+    /// The CRC-32 of the whole payload
+    pub crc: u32,
+    /// The CRC-32 of each chunk of the payload
+    pub chunk_crcs: Vec<u32>,
+}
+
+impl Header {
+    /// Builds the header of the given payload.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The kind of file the payload is the content of
+    /// * `properties` - The properties of the graph the file belongs to
+    /// * `payload` - The content of the file
+    pub fn new(kind: FileKind, properties: &Properties, payload: &[u8]) -> Self {
+        // Reading from a slice cannot fail
+        Self::read(kind, properties, payload).unwrap()
+    }
+
+    /// Builds the header of the payload read from `payload`, a chunk at a time.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The kind of file the payload is the content of
+    /// * `properties` - The properties of the graph the file belongs to
+    /// * `payload` - The reader of the content of the file
+    pub fn read<R: Read>(kind: FileKind, properties: &Properties, mut payload: R) -> io::Result<Self> {
+        let mut payload_len = 0;
+        let mut crc = Hasher::new();
+        let mut chunk_crcs = Vec::new();
+        let mut chunk = Vec::with_capacity(CHUNK_SIZE);
+
+        loop {
+            chunk.clear();
+            (&mut payload).take(CHUNK_SIZE as u64).read_to_end(&mut chunk)?;
+            if chunk.is_empty() {
+                break;
+            }
+
+            payload_len += chunk.len() as u64;
+            crc.update(&chunk);
+            chunk_crcs.push(crc32fast::hash(&chunk));
+        }
+
+        Ok(Self {
+            version: VERSION,
+            kind,
+            properties: properties.clone(),
+            payload_len,
+            chunk_size: CHUNK_SIZE as u32,
+            crc: crc.finalize(),
+            chunk_crcs,
+        })
+    }
+
+    /// Serializes the header.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let properties = String::from(self.properties.clone());
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&MAGIC);
+        bytes.extend_from_slice(&self.version.to_le_bytes());
+        bytes.push(self.kind.id());
+        bytes.push(0);
+        bytes.extend_from_slice(&(properties.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(properties.as_bytes());
+        bytes.extend_from_slice(&self.payload_len.to_le_bytes());
+        bytes.extend_from_slice(&self.chunk_size.to_le_bytes());
+        bytes.extend_from_slice(&self.crc.to_le_bytes());
+        for chunk_crc in self.chunk_crcs.iter() {
+            bytes.extend_from_slice(&chunk_crc.to_le_bytes());
+        }
+
+        let header_crc = crc32fast::hash(&bytes);
+        bytes.extend_from_slice(&header_crc.to_le_bytes());
+
+        bytes
+    }
+
+    /// Parses the header at the beginning of `bytes`, returning it together with its length in bytes,
+    /// or `None` if `bytes` does not start with the [`MAGIC`] bytes.
+    pub fn parse(bytes: &[u8]) -> Result<Option<(Self, usize)>> {
+        if !bytes.starts_with(&MAGIC) {
+            return Ok(None);
+        }
+
+        let mut rest = &bytes[MAGIC.len()..];
+
+        let version = u16::from_le_bytes(take(&mut rest, 2)?.try_into().unwrap());
+        if version == 0 || version > VERSION {
+            return Err(Error::Malformed(format!("unsupported container version {}", version)));
+        }
+
+        let kind = take(&mut rest, 2)?[0];
+        let kind = FileKind::from_id(kind)
+            .ok_or_else(|| Error::Malformed(format!("unknown kind of file {} in the container header", kind)))?;
+
+        let properties_len = u32::from_le_bytes(take(&mut rest, 4)?.try_into().unwrap());
+        let properties = std::str::from_utf8(take(&mut rest, properties_len as usize)?)
+            .map_err(|_| Error::Malformed("the properties of the container header are not valid UTF-8".to_owned()))?;
+
+        let payload_len = u64::from_le_bytes(take(&mut rest, 8)?.try_into().unwrap());
+        let chunk_size = u32::from_le_bytes(take(&mut rest, 4)?.try_into().unwrap());
+        let crc = u32::from_le_bytes(take(&mut rest, 4)?.try_into().unwrap());
+
+        if chunk_size == 0 {
+            return Err(Error::Malformed("invalid chunk size 0 in the container header".to_owned()));
+        }
+        let num_chunks = usize::try_from(payload_len.div_ceil(chunk_size as u64))
+            .ok()
+            .and_then(|num_chunks| num_chunks.checked_mul(4))
+            .ok_or_else(|| Error::Malformed("truncated container header".to_owned()))?;
+        let chunk_crcs = take(&mut rest, num_chunks)?
+            .chunks(4)
+            .map(|chunk_crc| u32::from_le_bytes(chunk_crc.try_into().unwrap()))
+            .collect();
+
+        let header_len = bytes.len() - rest.len();
+        let header_crc = u32::from_le_bytes(take(&mut rest, 4)?.try_into().unwrap());
+        if crc32fast::hash(&bytes[..header_len]) != header_crc {
+            return Err(Error::Malformed("checksum mismatch in the container header".to_owned()));
+        }
+
+        let header = Self {
+            version,
+            kind,
+            properties: properties.parse()?,
+            payload_len,
+            chunk_size,
+            crc,
+            chunk_crcs,
+        };
+
+        Ok(Some((header, header_len + 4)))
+    }
+
+    /// Checks that `payload` has the length and the checksums recorded by the header.
+    pub fn verify(&self, payload: &[u8]) -> Result<()> {
+        self.verify_len(payload.len())?;
+
+        let chunk_size = self.chunk_size as usize;
+        let mut crc = Hasher::new();
+
+        for (i, (chunk, &chunk_crc)) in payload.chunks(chunk_size).zip(self.chunk_crcs.iter()).enumerate() {
+            let mut hasher = Hasher::new();
+            hasher.update(chunk);
+
+            if hasher.clone().finalize() != chunk_crc {
+                let start = i * chunk_size;
+                return Err(Error::Malformed(format!("checksum mismatch in bytes {}..{} of the payload", start, start + chunk.len())));
+            }
+            crc.combine(&hasher);
+        }
+
+        if crc.finalize() != self.crc {
+            return Err(Error::Malformed("checksum mismatch in the payload".to_owned()));
+        }
+
+        Ok(())
+    }
+
+    /// Checks that a payload of `len` bytes has the length recorded by the header.
+    fn verify_len(&self, len: usize) -> Result<()> {
+        if len as u64 != self.payload_len {
+            return Err(Error::Malformed(format!("{} bytes of payload instead of {}", len, self.payload_len)));
+        }
+
+        Ok(())
+    }
+}
+
+/// Splits the first `len` bytes from `rest`.
+fn take<'a>(rest: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+    if rest.len() < len {
+        return Err(Error::Malformed("truncated container header".to_owned()));
+    }
+    let (head, tail) = rest.split_at(len);
+    *rest = tail;
+
+    Ok(head)
+}
+
+/// Writes `payload` to the file at the given path, preceded by a container header if the properties of the
+/// graph it belongs to are given.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file
+/// * `kind` - The kind of file the payload is the content of
+/// * `properties` - The properties of the graph, or `None` to write the bare payload
+/// * `payload` - The content of the file
+pub fn store<P: AsRef<Path>>(path: P, kind: FileKind, properties: Option<&Properties>, payload: &[u8]) -> Result<()> {
+    let path = path.as_ref();
+    let mut file = File::create(path).map_err(Error::io(path))?;
+
+    if let Some(properties) = properties {
+        file.write_all(&Header::new(kind, properties, payload).to_bytes()).map_err(Error::io(path))?;
+    }
+
+    file.write_all(payload).map_err(Error::io(path))
+}
+
+/// Returns the path of a temporary file next to the one at the given path.
+fn temporary_path(path: &Path, extension: &str) -> PathBuf {
+    let mut temporary_path = path.as_os_str().to_owned();
+    temporary_path.push(extension);
+
+    temporary_path.into()
+}
+
+/// Creates a file to stream the payload of the file at the given path to, returning a buffered writer
+/// of it, and to be completed by [`seal()`].
+///
+/// The payload is streamed to a temporary file next to the one at the given path, which is replaced only once
+/// the payload is complete: a graph can thus be compressed from a file memory-mapped at the same path, and
+/// the header of a container, which records the length and the checksums of the payload, can precede it.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file
+pub fn create<P: AsRef<Path>>(path: P) -> Result<BufWriter<File>> {
+    let payload_path = temporary_path(path.as_ref(), ".payload");
+
+    File::create(&payload_path).map(BufWriter::new).map_err(Error::io(payload_path))
+}
+
+/// Completes the file at the given path, whose payload has been streamed to the writer returned by [`create()`]
+/// and flushed, preceding it with a container header if the properties of the graph it belongs to are given.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file
+/// * `kind` - The kind of file the payload is the content of
+/// * `properties` - The properties of the graph, or `None` to leave the bare payload
+pub fn seal<P: AsRef<Path>>(path: P, kind: FileKind, properties: Option<&Properties>) -> Result<()> {
+    let path = path.as_ref();
+    let payload_path = temporary_path(path, ".payload");
+
+    let Some(properties) = properties else {
+        return fs::rename(payload_path, path).map_err(Error::io(path));
+    };
+
+    let payload = File::open(&payload_path).map_err(Error::io(&payload_path))?;
+    let header = Header::read(kind, properties, BufReader::new(payload)).map_err(Error::io(&payload_path))?;
+
+    let container_path = temporary_path(path, ".container");
+    let mut file = File::create(&container_path).map_err(Error::io(&container_path))?;
+    file.write_all(&header.to_bytes()).map_err(Error::io(&container_path))?;
+    let mut payload = File::open(&payload_path).map_err(Error::io(&payload_path))?;
+    io::copy(&mut payload, &mut file).map_err(Error::io(&container_path))?;
+
+    fs::remove_file(&payload_path).map_err(Error::io(&payload_path))?;
+    fs::rename(container_path, path).map_err(Error::io(path))
+}
+
+/// Discards the payload streamed to the writer returned by [`create()`] for the file at the given path, which is
+/// left as it was.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file
+pub fn discard<P: AsRef<Path>>(path: P) -> Result<()> {
+    let payload_path = temporary_path(path.as_ref(), ".payload");
+
+    fs::remove_file(&payload_path).map_err(Error::io(payload_path))
+}
+
+/// Loads a file of the given kind, which can either hold a container or be the bare payload.
+///
+/// If the file holds a container, its header has to describe a file of the given kind and the payload
+/// has to match its checksums. The returned stream is made of the payload only.
+///
+/// A memory-mapped payload is not read, so that its pages are loaded only once accessed: only its length is
+/// checked, and its checksums are left to [`verify_files()`].
+///
+/// # Arguments
+///
+/// * `path` - The path of the file
+/// * `mmap` - Whether to memory-map the file instead of reading it in memory
+/// * `kind` - The kind of file expected
+pub fn load<P: AsRef<Path>>(path: P, mmap: bool, kind: FileKind) -> Result<InputStream> {
+    let path = path.as_ref();
+
+    if mmap {
+        let stream = InputStream::mmap(path).map_err(Error::io(path))?;
+        match check(path, &stream, kind, false)? {
+            0 => Ok(stream),
+            header_len if header_len == stream.len() => Ok(InputStream::default()),
+            header_len => InputStream::mmap_at(path, header_len as u64).map_err(Error::io(path)),
+        }
+    } else {
+        let mut bytes = fs::read(path).map_err(Error::io(path))?;
+        let header_len = check(path, &bytes, kind, true)?;
+        bytes.drain(..header_len);
+
+        Ok(bytes.into())
+    }
+}
+
+/// Checks the container held by `bytes`, if any, returning the length of its header (0 if there is no container).
+/// The checksums of the payload are verified only if `verify` is `true`, its length in any case.
+fn check(path: &Path, bytes: &[u8], kind: FileKind, verify: bool) -> Result<usize> {
+    let in_file = in_file(path);
+
+    let Some((header, header_len)) = Header::parse(bytes).map_err(&in_file)? else {
+        return Ok(0);
+    };
+
+    if header.kind != kind {
+        return Err(Error::Malformed(format!("{} holds {} instead of {}", path.display(), header.kind, kind)));
+    }
+    let payload = &bytes[header_len..];
+    if verify {
+        header.verify(payload).map_err(&in_file)?;
+    } else {
+        header.verify_len(payload.len()).map_err(&in_file)?;
+    }
+
+    Ok(header_len)
+}
+
+/// Reads the container header of the file at the given path, returning `None` if the file is missing
+/// or does not hold a container.
+pub fn read_header<P: AsRef<Path>>(path: P) -> Result<Option<Header>> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    // Only the pages holding the header are actually read
+    let stream = InputStream::mmap(path).map_err(Error::io(path))?;
+
+    Header::parse(&stream)
+        .map(|header| header.map(|(header, _)| header))
+        .map_err(in_file(path))
+}
+
+/// Returns a function adding the given path to the reason of the errors of malformed files.
+fn in_file(path: &Path) -> impl Fn(Error) -> Error + '_ {
+    move |e| match e {
+        Error::Malformed(reason) => Error::Malformed(format!("{}: {}", path.display(), reason)),
+        e => e,
+    }
+}
+
+/// The extensions of the files of a graph that can hold a container, with the kinds of files they hold.
+const GRAPH_FILES: [(&str, FileKind); 4] = [
+    ("graph", FileKind::Graph),
+    ("offsets", FileKind::Offsets),
+    ("offsets.ef", FileKind::EliasFanoOffsets),
+    ("sync", FileKind::SyncPoints),
+];
+
+/// Checks that the files of a graph holding a container belong to the graph described by `properties`,
+/// so that a file swapped with the one of another graph, or with another file of the same graph, is detected.
+///
+/// Files without a container are not checked.
+///
+/// # Arguments
+///
+/// * `basename` - The basename of the graph
+/// * `properties` - The properties of the graph, as read from its `.properties` file
+pub fn check_files(basename: &str, properties: &Properties) -> Result<()> {
+    for (extension, kind) in GRAPH_FILES {
+        let path = format!("{}.{}", basename, extension);
+
+        if let Some(header) = read_header(&path)? {
+            if header.kind != kind {
+                return Err(Error::Malformed(format!("{} holds {} instead of {}", path, header.kind, kind)));
+            }
+            if header.properties != *properties {
+                return Err(Error::Malformed(format!("{} belongs to another graph than the one of {}.properties", path, basename)));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Verifies the checksums of the payloads of the files of a graph holding a container, reading them whole.
+///
+/// Files without a container are not verified, and neither are the payloads of memory-mapped files when they are
+/// loaded (see [`load()`]).
+///
+/// # Arguments
+///
+/// * `basename` - The basename of the graph
+pub fn verify_files(basename: &str) -> Result<()> {
+    for (extension, _) in GRAPH_FILES {
+        let path = PathBuf::from(format!("{}.{}", basename, extension));
+        if !path.exists() {
+            continue;
+        }
+
+        let stream = InputStream::mmap(&path).map_err(Error::io(&path))?;
+        if let Some((header, header_len)) = Header::parse(&stream).map_err(in_file(&path))? {
+            header.verify(&stream[header_len..]).map_err(in_file(&path))?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use std::path::Path;
+
+use sucds::mii_sequences::EliasFano;
+
+use crate::{
+    properties::{GraphFormat, Properties},
+    CheckedGraph, DynRandomAccessGraph, DynSequentialGraph, Result,
+};
+
+use super::{bvgraph, bvgraph_huffman_in, container, offsets::Offsets, zuckerli_in};
+
+/// Loads a compressed graph without knowing its representation in advance.
+///
+/// The format of the graph and its parameters are read from the `.properties` file, and the offsets are
+/// kept Elias-Fano encoded if they were stored so (i.e., if there is a `.offsets.ef` file).
+#[derive(Default)]
+pub struct GraphLoader {
+    mmap: bool,
+    cache_size: usize,
+}
+
+impl GraphLoader {
+    pub fn new() -> GraphLoader {
+        Self::default()
+    }
+
+    /// Sets whether the graph and offsets files have to be memory-mapped instead of read in memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `flag` - If `true`, the files are memory-mapped
+    pub fn set_mmap(mut self, flag: bool) -> Self {
+        self.mmap = flag;
+
+        self
+    }
+
+    /// Sets the number of decoded successor lists cached for random access by the loaded graphs,
+    /// or 0 not to cache them.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The maximum number of cached lists
+    pub fn set_cache_size(mut self, capacity: usize) -> Self {
+        self.cache_size = capacity;
+
+        self
+    }
+
+    /// Loads a graph, together with its offsets, for random access.
+    ///
+    /// # Arguments
+    ///
+    /// * `basename` - The basename of the compressed graph
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use webgraph_rust::webgraph::load::GraphLoader;
+    ///
+    /// let graph = GraphLoader::new().set_mmap(true).load("graph1")?;
+    /// let successors = graph.successors(0);
+    /// # Ok::<(), webgraph_rust::Error>(())
+    /// ```
+    pub fn load(&self, basename: &str) -> Result<Box<dyn DynRandomAccessGraph>> {
+        Ok(self.load_with(basename, false)?)
+    }
+
+    /// Loads a graph, together with its offsets, for random access, also allowing its successor lists to be
+    /// decoded in checked mode.
+    ///
+    /// The returned graph can be used as the one returned by [`Self::load()`]; its `try_*` methods validate
+    /// the compressed data, and should be preferred when the files may be corrupted or come from untrusted sources.
+    /// The checksums of the files holding a container are verified even if they are memory-mapped, reading them whole.
+    ///
+    /// # Arguments
+    ///
+    /// * `basename` - The basename of the compressed graph
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use webgraph_rust::webgraph::load::GraphLoader;
+    ///
+    /// let graph = GraphLoader::new().load_checked("graph1")?;
+    /// let successors = graph.try_successors(0)?;
+    /// # Ok::<(), webgraph_rust::Error>(())
+    /// ```
+    pub fn load_checked(&self, basename: &str) -> Result<Box<dyn CheckedGraph>> {
+        self.load_with(basename, true)
+    }
+
+    /// Loads a graph, together with its offsets, verifying the checksums of the payloads of its memory-mapped
+    /// files if `verify` is `true` (those of the files read in memory are always verified).
+    fn load_with(&self, basename: &str, verify: bool) -> Result<Box<dyn CheckedGraph>> {
+        let props = Properties::load(basename)?;
+        container::check_files(basename, &props)?;
+        if verify && self.mmap {
+            container::verify_files(basename)?;
+        }
+        let elias_fano = Path::new(&format!("{}.offsets.ef", basename)).exists();
+
+        Ok(match (props.format, elias_fano) {
+            (GraphFormat::BV, false) => Box::new(self.bv_builder(&props, basename)?.load_offsets(basename)?.build()),
+            (GraphFormat::BV, true) => Box::new(self.bv_builder(&props, basename)?.load_offsets_as::<EliasFano>(basename)?.build()),
+            (GraphFormat::Huffman, false) => Box::new(self.huffman_builder::<Box<[usize]>>(&props, basename)?.load_offsets(basename)?.build()),
+            (GraphFormat::Huffman, true) => Box::new(self.huffman_builder::<EliasFano>(&props, basename)?.load_offsets(basename)?.build()),
+            (GraphFormat::Zuckerli, false) => Box::new(self.zuckerli_builder::<Box<[usize]>>(&props, basename)?.load_offsets(basename)?.build()),
+            (GraphFormat::Zuckerli, true) => Box::new(self.zuckerli_builder::<EliasFano>(&props, basename)?.load_offsets(basename)?.build()),
+        })
+    }
+
+    /// Loads a graph for sequential access only, skipping its offsets (which may be missing).
+    ///
+    /// # Arguments
+    ///
+    /// * `basename` - The basename of the compressed graph
+    pub fn load_sequential(&self, basename: &str) -> Result<Box<dyn DynSequentialGraph>> {
+        let props = Properties::load(basename)?;
+        container::check_files(basename, &props)?;
+
+        Ok(match props.format {
+            GraphFormat::BV => Box::new(self.bv_builder(&props, basename)?.build()),
+            GraphFormat::Huffman => Box::new(self.huffman_builder::<Box<[usize]>>(&props, basename)?.build()),
+            GraphFormat::Zuckerli => Box::new(self.zuckerli_builder::<Box<[usize]>>(&props, basename)?.build()),
+        })
+    }
+
+    pub(crate) fn bv_builder(&self, props: &Properties, basename: &str) -> Result<bvgraph::BVGraphBuilder> {
+        bvgraph::BVGraphBuilder::new()
+            .set_in_min_interval_len(props.min_interval_len)
+            .set_in_max_ref_count(props.max_ref_count)
+            .set_in_window_size(props.window_size)
+            .set_in_zeta(props.zeta_k)
+            .set_in_block_coding(props.block_coding.try_into()?)
+            .set_in_block_count_coding(props.block_count_coding.try_into()?)
+            .set_in_outdegree_coding(props.outdegree_coding.try_into()?)
+            .set_in_offset_coding(props.offset_coding.try_into()?)
+            .set_in_reference_coding(props.reference_coding.try_into()?)
+            .set_in_interval_coding(props.interval_coding.try_into()?)
+            .set_in_residual_coding(props.residual_coding.try_into()?)
+            .set_num_nodes(props.nodes)
+            .set_num_edges(props.arcs)
+            .set_mmap(self.mmap)
+            .set_cache_size(self.cache_size)
+            .load_graph(basename)
+    }
+
+    pub(crate) fn huffman_builder<O: Offsets>(&self, props: &Properties, basename: &str) -> Result<bvgraph_huffman_in::DefaultBVGraphBuilder<O>> {
+        bvgraph_huffman_in::DefaultBVGraphBuilder::new()
+            .set_in_min_interval_len(props.min_interval_len)
+            .set_in_max_ref_count(props.max_ref_count)
+            .set_in_window_size(props.window_size)
+            .set_in_zeta(props.zeta_k)
+            .set_num_nodes(props.nodes)
+            .set_num_edges(props.arcs)
+            .set_mmap(self.mmap)
+            .set_cache_size(self.cache_size)
+            .load_graph(basename)
+            .map(|builder| builder.load_outdegrees())
+    }
+
+    pub(crate) fn zuckerli_builder<O: Offsets>(&self, props: &Properties, basename: &str) -> Result<zuckerli_in::DefaultBVGraphBuilder<O>> {
+        zuckerli_in::DefaultBVGraphBuilder::new()
+            .set_in_min_interval_len(props.min_interval_len)
+            .set_in_max_ref_count(props.max_ref_count)
+            .set_in_window_size(props.window_size)
+            .set_in_zeta(props.zeta_k)
+            .set_num_nodes(props.nodes)
+            .set_num_edges(props.arcs)
+            .set_mmap(self.mmap)
+            .set_cache_size(self.cache_size)
+            .load_graph(basename)
+            .map(|builder| builder.load_outdegrees())
+    }
+}
+
+/// Loads a graph, together with its offsets, for random access, in whatever format it has been compressed.
+///
+/// # Arguments
+///
+/// * `basename` - The basename of the compressed graph
+pub fn load(basename: &str) -> Result<Box<dyn DynRandomAccessGraph>> {
+    GraphLoader::new().load(basename)
+}
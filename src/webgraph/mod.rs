@@ -2,4 +2,16 @@ pub mod bvgraph;
 pub mod bvgraph_huffman_out;
 pub mod bvgraph_huffman_in;
 pub mod zuckerli_out;
-pub mod zuckerli_in;
\ No newline at end of file
+pub mod zuckerli_in;
+pub mod offsets;
+pub mod load;
+pub mod container;
+pub mod stats;
+pub mod bench;
+pub mod parallel;
+pub mod sync;
+pub mod cache;
+pub mod buffers;
+pub mod nodes;
+#[cfg(test)]
+mod tests;
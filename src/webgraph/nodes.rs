@@ -0,0 +1,40 @@
+use std::cell::Cell;
+
+use crate::{Error, Result};
+
+/// The check that the nodes given to a writer of compressed graphs are consecutive, starting from 0, as the lists
+/// refer to the previous ones by their distance and the offsets are indexed by node.
+#[derive(Default)]
+pub(crate) struct NodeCheck {
+    /// The first node out of place, together with the node expected instead
+    misplaced: Cell<Option<(usize, usize)>>,
+}
+
+impl NodeCheck {
+    /// Returns the pairs of the given sequence up to the first one whose node is out of place, which is recorded.
+    ///
+    /// # Arguments
+    ///
+    /// * `nodes` - The `(node, successors)` pairs to check
+    pub(crate) fn check<'a, L>(&'a self, nodes: impl IntoIterator<Item = (usize, L)> + 'a) -> impl Iterator<Item = (usize, L)> + 'a {
+        let mut expected = 0;
+
+        nodes.into_iter().map_while(move |(x, successors)| {
+            if x != expected {
+                self.misplaced.set(Some((x, expected)));
+                return None;
+            }
+            expected += 1;
+
+            Some((x, successors))
+        })
+    }
+
+    /// Returns an error if a node was out of place.
+    pub(crate) fn result(&self) -> Result<()> {
+        match self.misplaced.get() {
+            Some((node, expected)) => Err(Error::MisplacedNode { node, expected }),
+            None => Ok(()),
+        }
+    }
+}
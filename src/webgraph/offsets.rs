@@ -0,0 +1,153 @@
+use std::path::Path;
+
+use sucds::{mii_sequences::{EliasFano, EliasFanoBuilder}, Serializable};
+
+use crate::{bitstreams::{BinaryReader, InputStream}, properties::Properties, Error, Result};
+
+use super::container::{self, FileKind};
+
+/// The offsets of a compressed graph, i.e., the position (in bits) of each successor list in the graph stream.
+///
+/// The offsets form a monotone sequence, hence they can be kept either explicitly or in a succinct structure
+/// answering each access without decompressing the whole sequence.
+pub trait Offsets: Sized + Default {
+    /// Returns the offset of the given node.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The node number
+    fn get(&self, x: usize) -> usize;
+
+    /// Builds the offsets from their Elias-Fano representation.
+    ///
+    /// # Arguments
+    ///
+    /// * `ef` - The Elias-Fano encoded offsets
+    fn from_elias_fano(ef: EliasFano) -> Self;
+
+    /// Builds the offsets from a non-decreasing sequence of values.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The offsets, in node order
+    /// * `num_values` - The number of offsets
+    /// * `max_value` - The last (and largest) offset
+    fn from_values(values: impl Iterator<Item = usize>, num_values: usize, max_value: usize) -> Self;
+}
+
+/// Offsets kept explicitly, taking a word per node.
+impl Offsets for Box<[usize]> {
+    #[inline(always)]
+    fn get(&self, x: usize) -> usize {
+        self[x]
+    }
+
+    fn from_elias_fano(ef: EliasFano) -> Self {
+        ef.iter(0).collect()
+    }
+
+    fn from_values(values: impl Iterator<Item = usize>, num_values: usize, _max_value: usize) -> Self {
+        let mut offsets = Vec::with_capacity(num_values);
+        offsets.extend(values);
+
+        offsets.into_boxed_slice()
+    }
+}
+
+/// Offsets kept in their Elias-Fano representation, taking about `2 + log(m / n)` bits per node
+/// (where `m` is the size in bits of the graph), each access being a select query.
+impl Offsets for EliasFano {
+    #[inline(always)]
+    fn get(&self, x: usize) -> usize {
+        self.select(x).unwrap()
+    }
+
+    fn from_elias_fano(ef: EliasFano) -> Self {
+        ef
+    }
+
+    fn from_values(values: impl Iterator<Item = usize>, num_values: usize, max_value: usize) -> Self {
+        let mut efb = EliasFanoBuilder::new(max_value + 1, num_values).unwrap();
+        efb.extend(values).unwrap();
+
+        efb.build()
+    }
+}
+
+/// Stores a non-decreasing sequence of offsets Elias-Fano encoded, as read back from `.offsets.ef` files.
+///
+/// # Arguments
+///
+/// * `offsets` - The offsets to store, including the final one
+/// * `path` - The path of the output file
+/// * `properties` - The properties of the graph, if the offsets have to be written in a container
+pub fn store_elias_fano<P: AsRef<Path>>(offsets: &[usize], path: P, properties: Option<&Properties>) -> Result<()> {
+    let ef = EliasFano::from_values(offsets.iter().copied(), offsets.len(), *offsets.last().unwrap());
+
+    let mut serialized_ef = Vec::new();
+    ef.serialize_into(&mut serialized_ef).unwrap();
+
+    container::store(path, FileKind::EliasFanoOffsets, properties, &serialized_ef)
+}
+
+/// Loads the offsets of a graph, from the `.offsets.ef` file if present, or by decoding the gaps of the `.offsets` file otherwise.
+///
+/// If the graph has already been loaded, the offsets are also checked not to point past its end.
+///
+/// # Arguments
+///
+/// * `basename` - The basename of the graph
+/// * `num_nodes` - The number of nodes of the graph
+/// * `graph` - The graph, or an empty stream if it has not been loaded yet
+/// * `mmap` - Whether to memory-map the offsets file instead of reading it in memory
+/// * `read_gap` - Decodes the next gap of the `.offsets` file
+pub(crate) fn load_offsets<O: Offsets>(
+    basename: &str,
+    num_nodes: usize,
+    graph: &InputStream,
+    mmap: bool,
+    read_gap: impl Fn(&mut BinaryReader) -> Result<u64>,
+) -> Result<O> {
+    let path = format!("{}.offsets", basename);
+    let ef_path = format!("{}.ef", path);
+
+    let (offsets, max_value) = if Path::new(&ef_path).exists() {
+        let content = container::load(&ef_path, mmap, FileKind::EliasFanoOffsets)?;
+
+        let ef = EliasFano::deserialize_from(&*content)
+            .map_err(|e| Error::Malformed(format!("could not read the Elias-Fano encoded offsets: {}", e)))?;
+
+        if ef.len() < num_nodes {
+            return Err(Error::Malformed(format!("{} offsets for {} nodes", ef.len(), num_nodes)));
+        }
+
+        let max_value = if num_nodes > 0 { ef.select(num_nodes - 1).unwrap() } else { 0 };
+
+        (O::from_elias_fano(ef), max_value)
+    } else {
+        let content = container::load(&path, mmap, FileKind::Offsets)?;
+
+        // The offsets are gap-encoded, so they are decoded once to be checked and to find the largest one,
+        // and once more to be stored
+        let mut offsets_ibs = BinaryReader::new(content.clone());
+        let mut max_value = 0;
+        for _ in 0..num_nodes {
+            max_value += read_gap(&mut offsets_ibs)? as usize;
+        }
+
+        let mut offsets_ibs = BinaryReader::new(content);
+        let mut curr = 0;
+        let increasing_offsets = (0..num_nodes).map(|_| {
+            curr += read_gap(&mut offsets_ibs).unwrap() as usize;
+            curr
+        });
+
+        (O::from_values(increasing_offsets, num_nodes, max_value), max_value)
+    };
+
+    if !graph.is_empty() && max_value > graph.len() * 8 {
+        return Err(Error::Malformed(format!("offset {} past the end of a {}-bit graph", max_value, graph.len() * 8)));
+    }
+
+    Ok(offsets)
+}
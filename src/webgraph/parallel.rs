@@ -0,0 +1,333 @@
+use std::{collections::HashMap, ops::Range, sync::{mpsc, Mutex}, thread};
+
+use crate::bitstreams::BinaryWriter;
+
+use super::stats::CompressionStats;
+
+/// The number of nodes of the chunks compressed in parallel.
+///
+/// Each chunk is compressed as a graph of its own, its first lists having no previous lists to refer to,
+/// so larger chunks lose less compression while smaller ones balance the work better.
+pub const CHUNK_SIZE: usize = 1 << 16;
+
+/// The size of the stack of the threads compressing or scanning a graph.
+///
+/// Huffman coders are large, and unoptimized builds keep several copies of them on the stack, which would overflow
+/// the default stack of spawned threads. The stack is only reserved, so a large one costs nothing otherwise.
+const STACK_SIZE: usize = 64 << 20;
+
+/// Spawns a thread with a stack of [`STACK_SIZE`] bytes in the given scope.
+pub(crate) fn spawn<'scope, T: Send + 'scope>(
+    scope: &'scope thread::Scope<'scope, '_>,
+    f: impl FnOnce() -> T + Send + 'scope,
+) -> thread::ScopedJoinHandle<'scope, T> {
+    thread::Builder::new().stack_size(STACK_SIZE).spawn_scoped(scope, f).unwrap()
+}
+
+/// A chunk of consecutive nodes, each paired with its successors.
+#[derive(Default)]
+pub(crate) struct Chunk {
+    nodes: Vec<usize>,
+    successors: Vec<usize>,
+    /// The end of the successors of each node in `successors`
+    ends: Vec<usize>,
+}
+
+impl Chunk {
+    fn push(&mut self, x: usize, successors: &[usize]) {
+        self.nodes.push(x);
+        self.successors.extend_from_slice(successors);
+        self.ends.push(self.successors.len());
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the nodes of the chunk, each paired with its successors.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (usize, &[usize])> + '_ {
+        (0..self.len()).map(|i| {
+            let start = if i == 0 { 0 } else { self.ends[i - 1] };
+
+            (self.nodes[i], &self.successors[start..self.ends[i]])
+        })
+    }
+}
+
+/// A chunk compressed on its own, with the offsets of its lists relative to its start.
+pub(crate) struct CompressedChunk {
+    pub(crate) graph_obs: BinaryWriter<'static>,
+    /// The offset of each list, followed by the final one
+    pub(crate) offsets: Vec<usize>,
+    pub(crate) stats: Option<CompressionStats>,
+    pub(crate) arcs: usize,
+}
+
+/// Returns the number of nodes of the chunks compressed by a writer: the interval of its sync points,
+/// if it writes them, since each sync point starts a chunk, or [`CHUNK_SIZE`] otherwise.
+pub(crate) fn chunk_size(sync_interval: usize) -> usize {
+    if sync_interval > 0 { sync_interval } else { CHUNK_SIZE }
+}
+
+/// Splits the nodes in chunks of `chunk_size` nodes and maps them on the given number of threads,
+/// each thread mapping the chunks it takes with a state of its own.
+///
+/// The nodes are read on the calling thread, and at most a chunk per thread is waiting to be mapped at any time.
+/// Returns the result of each chunk, in the order of the chunks.
+///
+/// # Arguments
+///
+/// * `nodes` - The `(node, successors)` pairs to split
+/// * `chunk_size` - The number of nodes of each chunk (but the last one)
+/// * `threads` - The number of threads
+/// * `state` - The function building the state of each thread, on the thread itself
+/// * `map` - The function mapping a chunk, given the state of its thread and the index of the chunk
+pub(crate) fn map_chunks<L, S, R>(
+    nodes: impl IntoIterator<Item = (usize, L)>,
+    chunk_size: usize,
+    threads: usize,
+    state: impl Fn() -> S + Sync,
+    map: impl Fn(&mut S, usize, &Chunk) -> R + Sync,
+) -> Vec<R>
+where
+    L: AsRef<[usize]>,
+    R: Send,
+{
+    let mut results = Vec::new();
+    for_each_chunk(nodes, chunk_size, threads, state, map, |result| results.push(result));
+
+    results
+}
+
+/// Splits the nodes in chunks and maps them on the given number of threads like [`map_chunks()`], but passing
+/// the result of each chunk to `consume` on the calling thread, in the order of the chunks, as soon as the results
+/// of the previous ones have been consumed.
+///
+/// At most two chunks per thread are pending at any time, that is, waiting to be mapped, being mapped, or mapped
+/// and waiting for the results of the previous chunks, so that only their results are held in memory.
+///
+/// # Arguments
+///
+/// * `nodes` - The `(node, successors)` pairs to split
+/// * `chunk_size` - The number of nodes of each chunk (but the last one)
+/// * `threads` - The number of threads
+/// * `state` - The function building the state of each thread, on the thread itself
+/// * `map` - The function mapping a chunk, given the state of its thread and the index of the chunk
+/// * `consume` - The function consuming the result of each chunk
+pub(crate) fn for_each_chunk<L, S, R>(
+    nodes: impl IntoIterator<Item = (usize, L)>,
+    chunk_size: usize,
+    threads: usize,
+    state: impl Fn() -> S + Sync,
+    map: impl Fn(&mut S, usize, &Chunk) -> R + Sync,
+    mut consume: impl FnMut(R),
+)
+where
+    L: AsRef<[usize]>,
+    R: Send,
+{
+    let threads = threads.max(1);
+    let max_pending = 2 * threads;
+    let (chunk_tx, chunk_rx) = mpsc::sync_channel::<(usize, Chunk)>(threads);
+    let chunk_rx = Mutex::new(chunk_rx);
+    let (result_tx, result_rx) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            let (chunk_rx, result_tx, state, map) = (&chunk_rx, result_tx.clone(), &state, &map);
+
+            spawn(scope, move || {
+                let mut state = state();
+
+                loop {
+                    // The lock is released before mapping the chunk
+                    let message = chunk_rx.lock().unwrap().recv();
+                    match message {
+                        Ok((index, chunk)) => result_tx.send((index, map(&mut state, index, &chunk))).unwrap(),
+                        Err(_) => break,
+                    }
+                }
+            });
+        }
+        drop(result_tx);
+
+        // The results of the chunks mapped before the previous ones, by index
+        let mut reorder = HashMap::new();
+        // The index of the next chunk to be consumed
+        let mut next = 0;
+
+        // Consumes the results in order, waiting for them until fewer than `max_pending` chunks of the `sent` ones are pending
+        let mut drain = |sent: usize, max_pending: usize| loop {
+            while let Some(result) = reorder.remove(&next) {
+                consume(result);
+                next += 1;
+            }
+
+            let (index, result) = if sent - next < max_pending {
+                match result_rx.try_recv() {
+                    Ok(message) => message,
+                    Err(_) => break,
+                }
+            } else {
+                result_rx.recv().unwrap()
+            };
+            reorder.insert(index, result);
+        };
+
+        let mut chunk = Chunk::default();
+        let mut sent = 0;
+        for (x, successors) in nodes {
+            chunk.push(x, successors.as_ref());
+
+            if chunk.len() == chunk_size {
+                drain(sent, max_pending);
+                chunk_tx.send((sent, std::mem::take(&mut chunk))).unwrap();
+                sent += 1;
+            }
+        }
+        if !chunk.nodes.is_empty() {
+            chunk_tx.send((sent, chunk)).unwrap();
+            sent += 1;
+        }
+        drop(chunk_tx);
+
+        drain(sent, 1);
+    });
+}
+
+/// Concatenates the chunks compressed in parallel, in order, fixing up their offsets and adding up their
+/// statistics.
+pub(crate) struct Concatenation<'w, 'a, 's> {
+    /// The writer the chunks are appended to
+    graph_obs: &'w mut BinaryWriter<'a>,
+    /// The offsets of the lists in `graph_obs`
+    offsets_values: &'w mut Vec<usize>,
+    /// The statistics of the whole graph, if they are gathered
+    stats: Option<&'s mut CompressionStats>,
+    num_nodes: usize,
+    num_arcs: usize,
+}
+
+impl<'w, 'a, 's> Concatenation<'w, 'a, 's> {
+    pub(crate) fn new(
+        graph_obs: &'w mut BinaryWriter<'a>,
+        offsets_values: &'w mut Vec<usize>,
+        stats: Option<&'s mut CompressionStats>,
+    ) -> Self {
+        Concatenation { graph_obs, offsets_values, stats, num_nodes: 0, num_arcs: 0 }
+    }
+
+    /// Appends the next chunk.
+    pub(crate) fn push(&mut self, chunk: CompressedChunk) {
+        let start = self.graph_obs.written_bits;
+        let nodes = chunk.offsets.len() - 1;
+
+        self.offsets_values.extend(chunk.offsets[..nodes].iter().map(|offset| start + offset));
+        self.graph_obs.append(chunk.graph_obs);
+
+        if let (Some(stats), Some(chunk_stats)) = (self.stats.as_deref_mut(), &chunk.stats) {
+            stats.merge(chunk_stats);
+        }
+
+        self.num_nodes += nodes;
+        self.num_arcs += chunk.arcs;
+    }
+
+    /// Pushes the final offset, and returns the number of nodes and arcs of the chunks.
+    pub(crate) fn finish(self) -> (usize, usize) {
+        self.offsets_values.push(self.graph_obs.written_bits);
+
+        if let Some(stats) = self.stats {
+            stats.nodes = self.num_nodes;
+            stats.arcs = self.num_arcs;
+            stats.graph_bits = self.graph_obs.written_bits as u64;
+        }
+
+        (self.num_nodes, self.num_arcs)
+    }
+}
+
+/// Where the iteration over a range of nodes starts: the position of the list of its first node in the bitstream,
+/// and the lists preceding it within the window of the graph, which the lists of the range may refer to.
+pub(crate) struct RangeStart {
+    pub(crate) nodes: Range<usize>,
+    pub(crate) position: usize,
+    /// The lists of the nodes just before the range, the last one being the list of `nodes.start - 1`
+    pub(crate) window: Vec<Box<[usize]>>,
+}
+
+impl RangeStart {
+    /// Decodes the lists preceding the range by random access.
+    ///
+    /// # Arguments
+    ///
+    /// * `nodes` - The range of nodes
+    /// * `window_size` - The window size of the graph
+    /// * `offset` - Returns the offset of the list of a node
+    /// * `successors` - Returns the successors of a node, by random access
+    pub(crate) fn new(
+        nodes: Range<usize>,
+        window_size: usize,
+        offset: impl Fn(usize) -> usize,
+        successors: impl Fn(usize) -> Box<[usize]>,
+    ) -> Self {
+        if nodes.is_empty() {
+            return RangeStart { nodes, position: 0, window: Vec::new() };
+        }
+
+        let window = (nodes.start.saturating_sub(window_size)..nodes.start).map(successors).collect();
+
+        RangeStart { position: offset(nodes.start), nodes, window }
+    }
+}
+
+/// Splits the nodes of a graph in at most `parts` ranges of consecutive nodes, taking about the same number
+/// of bits of the graph each.
+///
+/// # Arguments
+///
+/// * `num_nodes` - The number of nodes of the graph
+/// * `parts` - The number of ranges
+/// * `offset` - Returns the offset of the list of a node
+pub(crate) fn split_nodes(num_nodes: usize, parts: usize, offset: impl Fn(usize) -> usize) -> Vec<Range<usize>> {
+    if num_nodes == 0 {
+        return Vec::new();
+    }
+
+    let parts = parts.clamp(1, num_nodes);
+    let first = offset(0);
+    let bits = offset(num_nodes - 1) - first;
+
+    // The i-th range starts at the first node whose list starts past i / parts of the bits
+    let mut starts = vec![0];
+    for i in 1..parts {
+        let target = first + (bits as u128 * i as u128 / parts as u128) as usize;
+        let (mut lo, mut hi) = (*starts.last().unwrap(), num_nodes);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if offset(mid) < target { lo = mid + 1 } else { hi = mid }
+        }
+        if lo > *starts.last().unwrap() && lo < num_nodes {
+            starts.push(lo);
+        }
+    }
+    starts.push(num_nodes);
+
+    starts.windows(2).map(|pair| pair[0]..pair[1]).collect()
+}
+
+/// Maps each of the given values on a thread of its own, returning the results in the order of the values.
+///
+/// # Arguments
+///
+/// * `values` - The values to map, such as the starts of the ranges of nodes to iterate on
+/// * `map` - The function mapping a value
+pub(crate) fn map_ranges<S: Send, R: Send>(values: Vec<S>, map: impl Fn(S) -> R + Sync) -> Vec<R> {
+    let map = &map;
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = values.into_iter().map(|value| spawn(scope, move || map(value))).collect();
+
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    })
+}
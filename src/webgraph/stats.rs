@@ -0,0 +1,252 @@
+use std::{collections::HashMap, fmt::Display, fs, path::Path, str::FromStr};
+
+use serde::Serialize;
+
+use crate::{properties::{parse_property, parse_required_property}, utils::int2nat, Error, Result};
+
+/// Statistics gathered while compressing a graph, telling where the bits of its successor lists go,
+/// in the spirit of the `.stats` files of the Java WebGraph.
+///
+/// Bits are measured on the bitstream actually written, so the sections of Huffman and Zuckerli graphs
+/// include the bits of their Huffman codes, whose headers are accounted separately. Besides the `.stats`
+/// format given by [`Display`], the statistics can be serialized, e.g., to JSON through `serde_json`.
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize)]
+pub struct CompressionStats {
+    pub nodes: usize,
+    pub arcs: usize,
+    /// The bits of the whole `.graph` file, headers included
+    pub graph_bits: u64,
+    /// The bits of the headers of the Huffman codes (0 for BV graphs)
+    pub header_bits: u64,
+    pub outdegree_bits: u64,
+    pub reference_bits: u64,
+    /// The bits of the block counts and of the copy blocks
+    pub block_bits: u64,
+    /// The bits of the interval counts, left extremes and lengths
+    pub interval_bits: u64,
+    pub residual_bits: u64,
+    /// The sum of the distances of the referenced lists (0 for the lists without reference)
+    pub total_reference: u64,
+    /// The sum of the lengths of the reference chains
+    pub total_chain_length: u64,
+    /// The arcs copied from the referenced lists
+    pub copied_arcs: u64,
+    /// The arcs encoded by intervals
+    pub intervalised_arcs: u64,
+    /// The arcs encoded as residuals
+    pub residual_arcs: u64,
+    /// The histogram of the gaps between consecutive successors, where the `i`-th bucket counts the gaps
+    /// `g` such that `g + 1` has `i + 1` bits; the first gap of a list is the (natural) one from the node itself
+    pub successor_gaps: Vec<u64>,
+    /// The histogram of the gaps between consecutive residuals, bucketed as `successor_gaps`
+    pub residual_gaps: Vec<u64>,
+    /// The bits written in each Huffman context, headers excluded (empty for BV graphs)
+    pub bits_per_context: Vec<u64>,
+}
+
+impl CompressionStats {
+    /// Records how the successors of `x` have been split among copied arcs, intervals and residuals.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The node whose list was compressed
+    /// * `successors` - The successors of `x`
+    /// * `intervalised` - The number of successors encoded by intervals
+    /// * `residuals` - The successors encoded as residuals
+    pub(crate) fn add_list(&mut self, x: usize, successors: &[usize], intervalised: usize, residuals: &[usize]) {
+        self.copied_arcs += (successors.len() - intervalised - residuals.len()) as u64;
+        self.intervalised_arcs += intervalised as u64;
+        self.residual_arcs += residuals.len() as u64;
+
+        add_gaps(&mut self.successor_gaps, x, successors);
+        add_gaps(&mut self.residual_gaps, x, residuals);
+    }
+
+    /// Records the bits of the sections of a list, given the positions in the bitstream where the list starts,
+    /// where its reference, its blocks and its intervals end, and where the list ends.
+    pub(crate) fn add_sections(&mut self, start: usize, section_ends: [usize; 3], end: usize) {
+        let [reference_end, blocks_end, intervals_end] = section_ends;
+
+        self.reference_bits += (reference_end - start) as u64;
+        self.block_bits += (blocks_end - reference_end) as u64;
+        self.interval_bits += (intervals_end - blocks_end) as u64;
+        self.residual_bits += (end - intervals_end) as u64;
+    }
+
+    /// Records the reference of a list, that is the distance of the referenced list and the length
+    /// of the resulting reference chain.
+    pub(crate) fn add_reference(&mut self, reference: usize, chain_length: usize) {
+        self.total_reference += reference as u64;
+        self.total_chain_length += chain_length as u64;
+    }
+
+    /// Adds the statistics of another part of the same graph, such as a chunk compressed in parallel.
+    /// The number of nodes and arcs and the bits of the graph and of the headers are left untouched.
+    pub(crate) fn merge(&mut self, other: &CompressionStats) {
+        self.outdegree_bits += other.outdegree_bits;
+        self.reference_bits += other.reference_bits;
+        self.block_bits += other.block_bits;
+        self.interval_bits += other.interval_bits;
+        self.residual_bits += other.residual_bits;
+        self.total_reference += other.total_reference;
+        self.total_chain_length += other.total_chain_length;
+        self.copied_arcs += other.copied_arcs;
+        self.intervalised_arcs += other.intervalised_arcs;
+        self.residual_arcs += other.residual_arcs;
+
+        add_histograms(&mut self.successor_gaps, &other.successor_gaps);
+        add_histograms(&mut self.residual_gaps, &other.residual_gaps);
+        add_histograms(&mut self.bits_per_context, &other.bits_per_context);
+    }
+
+    /// Returns the bits per node, or 0 if the graph has no nodes.
+    pub fn bits_per_node(&self) -> f64 {
+        ratio(self.graph_bits, self.nodes)
+    }
+
+    /// Returns the bits per arc, or 0 if the graph has no arcs.
+    pub fn bits_per_link(&self) -> f64 {
+        ratio(self.graph_bits, self.arcs)
+    }
+
+    /// Returns the average distance of the referenced lists, over all nodes (0 if there are none).
+    pub fn avg_reference(&self) -> f64 {
+        ratio(self.total_reference, self.nodes)
+    }
+
+    /// Returns the average length of the reference chains, over all nodes (0 if there are none).
+    pub fn avg_chain_length(&self) -> f64 {
+        ratio(self.total_chain_length, self.nodes)
+    }
+
+    /// Returns the fraction of arcs copied from the referenced lists (0 if there are no arcs).
+    pub fn copy_ratio(&self) -> f64 {
+        ratio(self.copied_arcs, self.arcs)
+    }
+
+    /// Returns the fraction of arcs encoded by intervals (0 if there are no arcs).
+    pub fn interval_coverage(&self) -> f64 {
+        ratio(self.intervalised_arcs, self.arcs)
+    }
+
+    /// Writes the statistics to the given path, in the format of [`Display`].
+    pub fn store<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        fs::write(path.as_ref(), self.to_string()).map_err(Error::io(path.as_ref()))
+    }
+
+    /// Reads the statistics from the given path, in the format of [`Display`].
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        fs::read_to_string(path.as_ref()).map_err(Error::io(path.as_ref()))?.parse()
+    }
+}
+
+/// Returns `value` divided by `count`, or 0 if `count` is 0.
+fn ratio(value: u64, count: usize) -> f64 {
+    if count == 0 { 0. } else { value as f64 / count as f64 }
+}
+
+/// Adds the gaps of `list` to the histogram `gaps`, the first one being taken from `x`.
+fn add_gaps(gaps: &mut Vec<u64>, x: usize, list: &[usize]) {
+    let mut add = |gap: u64| {
+        let bucket = (u64::BITS - (gap + 1).leading_zeros() - 1) as usize;
+        if gaps.len() <= bucket {
+            gaps.resize(bucket + 1, 0);
+        }
+        gaps[bucket] += 1;
+    };
+
+    if let Some(&first) = list.first() {
+        add(int2nat(first as i64 - x as i64));
+    }
+    for pair in list.windows(2) {
+        add((pair[1] - pair[0] - 1) as u64);
+    }
+}
+
+/// Adds the buckets of `other` to those of `histogram`, extending it if needed.
+fn add_histograms(histogram: &mut Vec<u64>, other: &[u64]) {
+    if histogram.len() < other.len() {
+        histogram.resize(other.len(), 0);
+    }
+    for (bucket, &count) in histogram.iter_mut().zip(other) {
+        *bucket += count;
+    }
+}
+
+/// Writes the statistics as `key=value` lines, like a `.properties` file, the histograms being comma-separated.
+impl Display for CompressionStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |values: &[u64]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(",");
+
+        writeln!(f, "nodes={}", self.nodes)?;
+        writeln!(f, "arcs={}", self.arcs)?;
+        writeln!(f, "graphbits={}", self.graph_bits)?;
+        writeln!(f, "bitspernode={}", self.bits_per_node())?;
+        writeln!(f, "bitsperlink={}", self.bits_per_link())?;
+        writeln!(f, "bitsforheaders={}", self.header_bits)?;
+        writeln!(f, "bitsforoutdegrees={}", self.outdegree_bits)?;
+        writeln!(f, "bitsforreferences={}", self.reference_bits)?;
+        writeln!(f, "bitsforblocks={}", self.block_bits)?;
+        writeln!(f, "bitsforintervals={}", self.interval_bits)?;
+        writeln!(f, "bitsforresiduals={}", self.residual_bits)?;
+        writeln!(f, "avgref={}", self.avg_reference())?;
+        writeln!(f, "avgchainlength={}", self.avg_chain_length())?;
+        writeln!(f, "copiedarcs={}", self.copied_arcs)?;
+        writeln!(f, "intervalisedarcs={}", self.intervalised_arcs)?;
+        writeln!(f, "residualarcs={}", self.residual_arcs)?;
+        writeln!(f, "copyratio={}", self.copy_ratio())?;
+        writeln!(f, "intervalcoverage={}", self.interval_coverage())?;
+        writeln!(f, "successorloggaps={}", join(&self.successor_gaps))?;
+        writeln!(f, "residualloggaps={}", join(&self.residual_gaps))?;
+        if !self.bits_per_context.is_empty() {
+            writeln!(f, "bitspercontext={}", join(&self.bits_per_context))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses the statistics in the format of [`Display`], recovering the total reference and chain length
+/// from their averages and ignoring the other derived measures.
+impl FromStr for CompressionStats {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let p = java_properties::read(value.as_bytes())
+            .map_err(|e| Error::Malformed(format!("could not parse the statistics: {}", e)))?;
+
+        let nodes = parse_required_property(&p, "nodes")?;
+        let total = |key: &str| parse_required_property(&p, key).map(|avg: f64| (avg * nodes as f64).round() as u64);
+
+        Ok(CompressionStats {
+            nodes,
+            arcs: parse_required_property(&p, "arcs")?,
+            graph_bits: parse_required_property(&p, "graphbits")?,
+            header_bits: parse_required_property(&p, "bitsforheaders")?,
+            outdegree_bits: parse_required_property(&p, "bitsforoutdegrees")?,
+            reference_bits: parse_required_property(&p, "bitsforreferences")?,
+            block_bits: parse_required_property(&p, "bitsforblocks")?,
+            interval_bits: parse_required_property(&p, "bitsforintervals")?,
+            residual_bits: parse_required_property(&p, "bitsforresiduals")?,
+            total_reference: total("avgref")?,
+            total_chain_length: total("avgchainlength")?,
+            copied_arcs: parse_required_property(&p, "copiedarcs")?,
+            intervalised_arcs: parse_required_property(&p, "intervalisedarcs")?,
+            residual_arcs: parse_required_property(&p, "residualarcs")?,
+            successor_gaps: parse_values(&p, "successorloggaps")?,
+            residual_gaps: parse_values(&p, "residualloggaps")?,
+            bits_per_context: parse_values(&p, "bitspercontext")?,
+        })
+    }
+}
+
+/// Parses the comma-separated values of the given key, if present.
+fn parse_values(value: &HashMap<String, String>, key: &str) -> Result<Vec<u64>> {
+    let values: String = parse_property(value, key)?.unwrap_or_default();
+
+    values.split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|v| v.parse().map_err(|_| Error::InvalidProperty { key: key.to_owned(), value: values.clone() }))
+        .collect()
+}
@@ -0,0 +1,87 @@
+use crate::{properties::Properties, Error, Result};
+
+use super::container::{self, FileKind};
+
+/// The sync points of a graph, written when it is compressed with a sync interval `k` (see, e.g.,
+/// [`BVGraphBuilder::set_sync_interval()`](super::bvgraph::BVGraphBuilder::set_sync_interval)).
+///
+/// The lists of the nodes from each multiple of `k` on do not refer to the lists before it, so a scan can start
+/// at such a node with an empty window, once positioned at its offset: a graph can thus be iterated from any node
+/// without loading its offsets, decoding at most `k - 1` lists in vain.
+///
+/// The `.sync` file holds the interval followed by the offset of each sync point, as 64-bit little-endian integers.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct SyncPoints {
+    pub interval: usize,
+    /// The offset of the list of each multiple of the interval
+    pub offsets: Box<[usize]>,
+}
+
+impl SyncPoints {
+    /// Takes the sync points from the offsets of all the lists of a graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - The sync interval
+    /// * `offsets` - The offsets of the lists, followed by the final one
+    pub(crate) fn from_offsets(interval: usize, offsets: &[usize]) -> Self {
+        let lists = offsets.len().saturating_sub(1);
+
+        SyncPoints {
+            interval,
+            offsets: offsets[..lists].iter().step_by(interval).copied().collect(),
+        }
+    }
+
+    /// Returns the last sync point not after `x`, together with its offset.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The node number
+    pub fn get(&self, x: usize) -> (usize, usize) {
+        if self.offsets.is_empty() {
+            return (0, 0);
+        }
+
+        let i = (x / self.interval).min(self.offsets.len() - 1);
+        (i * self.interval, self.offsets[i])
+    }
+
+    /// Writes the sync points to the `.sync` file of the given basename.
+    ///
+    /// # Arguments
+    ///
+    /// * `basename` - The basename of the graph
+    /// * `properties` - The properties of the graph, if the sync points have to be written in a container
+    pub fn store(&self, basename: &str, properties: Option<&Properties>) -> Result<()> {
+        let mut payload = Vec::with_capacity((self.offsets.len() + 1) * 8);
+        payload.extend_from_slice(&(self.interval as u64).to_le_bytes());
+        for &offset in self.offsets.iter() {
+            payload.extend_from_slice(&(offset as u64).to_le_bytes());
+        }
+
+        container::store(format!("{}.sync", basename), FileKind::SyncPoints, properties, &payload)
+    }
+
+    /// Reads the sync points from the `.sync` file of the given basename.
+    ///
+    /// # Arguments
+    ///
+    /// * `basename` - The basename of the graph
+    pub fn load(basename: &str) -> Result<Self> {
+        let path = format!("{}.sync", basename);
+        let payload = container::load(&path, false, FileKind::SyncPoints)?;
+
+        if payload.len() < 8 || payload.len() % 8 != 0 {
+            return Err(Error::Malformed(format!("{}: truncated sync points", path)));
+        }
+
+        let mut values = payload.chunks(8).map(|value| u64::from_le_bytes(value.try_into().unwrap()) as usize);
+        let interval = values.next().unwrap();
+        if interval == 0 {
+            return Err(Error::Malformed(format!("{}: invalid sync interval 0", path)));
+        }
+
+        Ok(SyncPoints { interval, offsets: values.collect() })
+    }
+}
@@ -0,0 +1,1248 @@
+use std::{
+    env, fs,
+    io::Write,
+    ops::Range,
+    panic,
+    path::Path,
+    process, thread,
+};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use sucds::mii_sequences::EliasFano;
+
+use crate::{
+    ascii_graph::{AsciiGraph, AsciiGraphBuilder},
+    properties::{GraphFormat, Properties},
+    utils::encodings::Code,
+    first_difference, Error, RandomAccessGraph, SequentialGraph,
+};
+
+use super::{
+    buffers::DecodeBuffers,
+    bvgraph, bvgraph_huffman_in, bvgraph_huffman_out,
+    cache::{CacheStats, ListCache, Pinning, SharedCache},
+    container::{self, FileKind, Header, CHUNK_SIZE, MAGIC, VERSION},
+    load::GraphLoader,
+    offsets::Offsets,
+    parallel::{self, split_nodes, RangeStart},
+    stats::CompressionStats,
+    sync::SyncPoints,
+    zuckerli_in, zuckerli_out,
+};
+
+/// The formats the compressed graphs of the tests are written in.
+const FORMATS: [GraphFormat; 3] = [GraphFormat::BV, GraphFormat::Huffman, GraphFormat::Zuckerli];
+
+/// Returns the path of a temporary file for the given test, unique to this process.
+fn temporary_path(name: &str) -> String {
+    env::temp_dir()
+        .join(format!("webgraph_test_{}_{}", process::id(), name))
+        .to_str()
+        .unwrap()
+        .to_owned()
+}
+
+/// Generates a graph with `num_nodes` nodes whose lists have the locality of web graphs, so that once compressed
+/// they have references, intervals and residuals, and loads it with an [`AsciiGraphBuilder`].
+fn test_graph(name: &str, num_nodes: usize) -> AsciiGraph<usize> {
+    let mut rng = StdRng::seed_from_u64(num_nodes as u64);
+    let mut lists: Vec<Vec<usize>> = Vec::with_capacity(num_nodes);
+
+    for x in 0..num_nodes {
+        let mut list = Vec::new();
+
+        // Most of the successors of one of the previous nodes
+        if x > 0 && rng.gen_bool(0.6) {
+            let reference = rng.gen_range(1..=x.min(8));
+            list.extend(lists[x - reference].iter().filter(|_| rng.gen_bool(0.8)));
+        }
+        // A run of consecutive successors
+        if rng.gen_bool(0.3) {
+            let start = rng.gen_range(0..num_nodes);
+            let len = rng.gen_range(1..12);
+            list.extend(start..(start + len).min(num_nodes));
+        }
+        for _ in 0..rng.gen_range(0..5) {
+            list.push(rng.gen_range(0..num_nodes));
+        }
+
+        list.sort_unstable();
+        list.dedup();
+        lists.push(list);
+    }
+
+    let path = temporary_path(&format!("{}.txt", name));
+    let ascii: String = lists
+        .iter()
+        .enumerate()
+        .map(|(x, list)| format!("{}\t{}\n", x, list.iter().map(|y| y.to_string()).collect::<Vec<_>>().join("\t")))
+        .collect();
+    fs::write(&path, ascii).unwrap();
+
+    let graph = AsciiGraphBuilder::new().load_ascii(&path).unwrap().build();
+    fs::remove_file(path).unwrap();
+
+    graph
+}
+
+/// Compresses `graph` in the given format to the given basename, with the given sync interval and threads.
+fn compress(graph: &AsciiGraph<usize>, format: GraphFormat, basename: &str, sync_interval: usize, threads: usize) {
+    let compress = || match format {
+        GraphFormat::BV => bvgraph::BVGraphBuilder::new()
+            .set_sync_interval(sync_interval)
+            .set_threads(threads)
+            .build()
+            .store_graph(graph, basename),
+        GraphFormat::Huffman => bvgraph_huffman_out::DefaultBVGraphBuilder::new()
+            .set_sync_interval(sync_interval)
+            .set_threads(threads)
+            .build()
+            .store_graph(graph, basename),
+        GraphFormat::Zuckerli => zuckerli_out::DefaultBVGraphBuilder::new()
+            .set_sync_interval(sync_interval)
+            .set_threads(threads)
+            .build()
+            .store_graph(graph, basename),
+    };
+
+    with_large_stack(compress).unwrap();
+}
+
+/// Removes the files of the compressed graph with the given basename.
+fn remove_graph(basename: &str) {
+    for extension in ["graph", "offsets", "offsets.ef", "properties", "sync", "stats"] {
+        let _ = fs::remove_file(format!("{}.{}", basename, extension));
+    }
+}
+
+/// Runs `f` on a thread with the stack of the threads compressing graphs, returning its result.
+///
+/// Huffman coders are large, and debug builds keep several copies of them on the stack while building and using
+/// the Huffman and Zuckerli graphs, overflowing the stack of the tests.
+fn with_large_stack<R: Send>(f: impl FnOnce() -> R + Send) -> R {
+    thread::scope(|scope| parallel::spawn(scope, f).join().unwrap_or_else(|e| panic::resume_unwind(e)))
+}
+
+/// Loads the graph in the BV format with the given basename, together with its offsets.
+fn load_bv(basename: &str) -> bvgraph::BVGraph {
+    let props = Properties::load(basename).unwrap();
+    GraphLoader::new().bv_builder(&props, basename).unwrap().load_offsets(basename).unwrap().build()
+}
+
+/// Loads the graph in the Huffman format with the given basename, together with its offsets.
+fn load_huffman(basename: &str) -> bvgraph_huffman_in::DefaultBVGraph {
+    let props = Properties::load(basename).unwrap();
+    GraphLoader::new().huffman_builder(&props, basename).unwrap().load_offsets(basename).unwrap().build()
+}
+
+/// Loads the graph in the Zuckerli format with the given basename, together with its offsets.
+fn load_zuckerli(basename: &str) -> zuckerli_in::DefaultBVGraph {
+    let props = Properties::load(basename).unwrap();
+    GraphLoader::new().zuckerli_builder(&props, basename).unwrap().load_offsets(basename).unwrap().build()
+}
+
+/// Compresses `$ascii` in each format with the given sync interval and runs `$check` on each compressed graph,
+/// loaded with its offsets, binding the graph to `$graph` and its basename to `$basename`.
+macro_rules! for_each_format {
+    ($name:expr, $ascii:expr, $sync_interval:expr, |$graph:ident, $basename:ident| $check:block) => {
+        for format in FORMATS {
+            let $basename: &str = &temporary_path(&format!("{}_{:?}", $name, format));
+            compress($ascii, format, $basename, $sync_interval, 1);
+
+            with_large_stack(|| match format {
+                GraphFormat::BV => {
+                    let $graph = load_bv($basename);
+                    $check
+                },
+                GraphFormat::Huffman => {
+                    let $graph = load_huffman($basename);
+                    $check
+                },
+                GraphFormat::Zuckerli => {
+                    let $graph = load_zuckerli($basename);
+                    $check
+                },
+            });
+
+            remove_graph($basename);
+        }
+    };
+}
+
+/// Returns a payload of the given length, with bytes that are not all alike.
+fn payload(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i * 31 % 251) as u8).collect()
+}
+
+#[test]
+fn test_list_cache_eviction_order() {
+    let mut cache = ListCache::new(3);
+    for x in 0..3 {
+        cache.insert(x, &[x + 10]);
+    }
+
+    // With no list referenced, the clock evicts them in the order they were cached
+    cache.insert(3, &[13]);
+    assert_eq!(cache.get(0), None);
+    cache.insert(4, &[14]);
+    assert_eq!(cache.get(1), None);
+
+    assert_eq!(cache.get(2), Some(&[12][..]));
+    assert_eq!(cache.get(3), Some(&[13][..]));
+    assert_eq!(cache.get(4), Some(&[14][..]));
+}
+
+#[test]
+fn test_list_cache_second_chance() {
+    let mut cache = ListCache::new(3);
+    for x in 0..3 {
+        cache.insert(x, &[x + 10]);
+    }
+
+    // The list of 0 is referenced, so the clock clears its bit and evicts the list of 1 instead
+    assert_eq!(cache.get(0), Some(&[10][..]));
+    cache.insert(3, &[13]);
+    assert_eq!(cache.get(1), None);
+    assert_eq!(cache.get(0), Some(&[10][..]));
+
+    // Every list is now referenced but the one of 2, which is the next evicted
+    assert_eq!(cache.get(3), Some(&[13][..]));
+    cache.insert(4, &[14]);
+    assert_eq!(cache.get(2), None);
+    assert_eq!(cache.get(0), Some(&[10][..]));
+    assert_eq!(cache.get(3), Some(&[13][..]));
+    assert_eq!(cache.get(4), Some(&[14][..]));
+}
+
+#[test]
+fn test_list_cache_zero_capacity() {
+    let mut cache = ListCache::new(0);
+    cache.insert(0, &[1, 2]);
+    assert_eq!(cache.get(0), None);
+
+    cache.pin(1, vec![2, 3].into());
+    cache.insert(2, &[3]);
+    assert_eq!(cache.get(1), Some(&[2, 3][..]));
+    assert_eq!(cache.get(2), None);
+
+    assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 2, evictions: 0, pinned: 1 });
+}
+
+#[test]
+fn test_list_cache_stats() {
+    let mut cache = ListCache::new(2);
+    assert_eq!(cache.stats(), CacheStats::default());
+    assert_eq!(cache.stats().hit_rate(), 0.);
+
+    cache.pin(100, vec![1].into());
+    for x in 0..5 {
+        cache.insert(x, &[x]);
+    }
+    // Lists already cached or pinned are not cached again
+    cache.insert(4, &[4]);
+    cache.insert(100, &[1]);
+
+    assert_eq!(cache.get(100), Some(&[1][..]));
+    assert_eq!(cache.get(4), Some(&[4][..]));
+    assert_eq!(cache.get(0), None);
+    assert_eq!(cache.get(3), Some(&[3][..]));
+
+    assert_eq!(cache.stats(), CacheStats { hits: 3, misses: 1, evictions: 3, pinned: 1 });
+    assert_eq!(cache.stats().hit_rate(), 0.75);
+}
+
+#[test]
+fn test_pinning_select_degree() {
+    let outdegrees = [2, 0, 5, 5, 1];
+
+    // Ties are broken by node number, and nodes with no successors are never pinned
+    assert_eq!(Pinning::Degree(3).select(outdegrees.len(), |x| outdegrees[x], |_| 0), vec![2, 3, 0]);
+    assert_eq!(Pinning::Degree(10).select(outdegrees.len(), |x| outdegrees[x], |_| 0), vec![2, 3, 0, 4]);
+    assert_eq!(Pinning::Degree(0).select(outdegrees.len(), |x| outdegrees[x], |_| 0), Vec::<usize>::new());
+    assert_eq!(Pinning::Degree(3).select(0, |_| unreachable!(), |_| 0), Vec::<usize>::new());
+}
+
+#[test]
+fn test_pinning_select_references() {
+    // 1 and 2 refer to 0, 3 to 2 and 4 to 1
+    let references = [0, 1, 2, 1, 3];
+
+    assert_eq!(Pinning::References(2).select(references.len(), |_| 1, |x| references[x]), vec![0, 1]);
+    assert_eq!(Pinning::References(10).select(references.len(), |_| 1, |x| references[x]), vec![0, 1, 2]);
+}
+
+#[test]
+fn test_shared_cache() {
+    assert_eq!(SharedCache::new(0).stats(), None);
+
+    let mut cache = SharedCache::new(100);
+    cache.pin([(1000, vec![1, 2].into_boxed_slice())]);
+
+    let mut list = vec![42];
+    for x in 0..200 {
+        assert!(!cache.get_into(x, &mut list));
+        cache.insert(x, &[x]);
+    }
+    // The last list cached by each shard is still there
+    assert!(cache.get_into(199, &mut list));
+    assert_eq!(list, vec![199]);
+    assert!(cache.get_into(1000, &mut list));
+    assert_eq!(list, vec![1, 2]);
+
+    assert_eq!(cache.stats(), Some(CacheStats { hits: 2, misses: 200, evictions: 100, pinned: 1 }));
+
+    // A cache with pinned lists only is present
+    let mut pinned = SharedCache::new(0);
+    pinned.pin([(0, vec![1].into_boxed_slice())]);
+    pinned.insert(1, &[2]);
+    assert!(pinned.get_into(0, &mut list));
+    assert!(!pinned.get_into(1, &mut list));
+    assert_eq!(pinned.stats(), Some(CacheStats { hits: 1, misses: 1, evictions: 0, pinned: 1 }));
+}
+
+/// Returns the properties of the graph the files of the container tests belong to.
+fn test_properties() -> Properties {
+    Properties { nodes: 3, arcs: 5, ..Default::default() }
+}
+
+/// Asserts that `result` is a [`Error::Malformed`] error whose reason contains `reason`.
+fn assert_malformed<T: std::fmt::Debug>(result: crate::Result<T>, reason: &str) {
+    match result {
+        Err(Error::Malformed(message)) => assert!(message.contains(reason), "unexpected reason: {}", message),
+        result => panic!("expected a malformed container, got {:?}", result),
+    }
+}
+
+#[test]
+fn test_header_round_trip() {
+    for len in [0, 1, CHUNK_SIZE, 2 * CHUNK_SIZE + 100] {
+        let header = Header::new(FileKind::Offsets, &test_properties(), &payload(len));
+        assert_eq!(header.chunk_crcs.len(), len.div_ceil(CHUNK_SIZE));
+
+        let bytes = header.to_bytes();
+        let (parsed, header_len) = Header::parse(&bytes).unwrap().unwrap();
+        assert_eq!(parsed, header);
+        assert_eq!(header_len, bytes.len());
+
+        // The header is followed by the payload
+        let mut file = bytes.clone();
+        file.extend_from_slice(&payload(len));
+        assert_eq!(Header::parse(&file).unwrap().unwrap(), (header, bytes.len()));
+    }
+
+    // Bare payloads have no header
+    assert_eq!(Header::parse(&payload(100)).unwrap(), None);
+    assert_eq!(Header::parse(&[]).unwrap(), None);
+}
+
+#[test]
+fn test_header_truncated() {
+    let bytes = Header::new(FileKind::Graph, &test_properties(), &payload(100)).to_bytes();
+
+    for len in MAGIC.len()..bytes.len() {
+        assert_malformed(Header::parse(&bytes[..len]), "truncated container header");
+    }
+}
+
+#[test]
+fn test_header_bad_version() {
+    let mut bytes = Header::new(FileKind::Graph, &test_properties(), &payload(100)).to_bytes();
+
+    for version in [0, VERSION + 1] {
+        bytes[8..10].copy_from_slice(&version.to_le_bytes());
+        assert_malformed(Header::parse(&bytes), "unsupported container version");
+    }
+}
+
+#[test]
+fn test_header_bad_kind() {
+    let mut bytes = Header::new(FileKind::Graph, &test_properties(), &payload(100)).to_bytes();
+    bytes[10] = 4;
+
+    assert_malformed(Header::parse(&bytes), "unknown kind of file 4");
+}
+
+#[test]
+fn test_header_checksum_mismatch() {
+    let bytes = Header::new(FileKind::SyncPoints, &test_properties(), &payload(100)).to_bytes();
+
+    // A byte of the properties, of the checksum of the payload, of the one of its chunk, and of the one of the header itself
+    for i in [20, bytes.len() - 12, bytes.len() - 8, bytes.len() - 1] {
+        let mut corrupted = bytes.clone();
+        corrupted[i] ^= 1;
+        assert_malformed(Header::parse(&corrupted), "checksum mismatch in the container header");
+    }
+}
+
+#[test]
+fn test_verify_payload() {
+    let mut payload = payload(2 * CHUNK_SIZE + 100);
+    let header = Header::new(FileKind::Graph, &test_properties(), &payload);
+    header.verify(&payload).unwrap();
+
+    assert_malformed(header.verify(&payload[1..]), "bytes of payload instead of");
+
+    payload[CHUNK_SIZE + 5] ^= 0x80;
+    assert_malformed(
+        header.verify(&payload),
+        &format!("checksum mismatch in bytes {}..{} of the payload", CHUNK_SIZE, 2 * CHUNK_SIZE),
+    );
+    payload[CHUNK_SIZE + 5] ^= 0x80;
+
+    let last = payload.len() - 1;
+    payload[last] ^= 1;
+    assert_malformed(
+        header.verify(&payload),
+        &format!("checksum mismatch in bytes {}..{} of the payload", 2 * CHUNK_SIZE, 2 * CHUNK_SIZE + 100),
+    );
+}
+
+/// Streams `payload` to the file at `path` and seals it, with the given properties.
+fn seal(path: &str, kind: FileKind, properties: Option<&Properties>, payload: &[u8]) {
+    let mut writer = container::create(path).unwrap();
+    writer.write_all(payload).unwrap();
+    writer.flush().unwrap();
+    drop(writer);
+
+    container::seal(path, kind, properties).unwrap();
+}
+
+#[test]
+fn test_seal_and_load() {
+    let path = temporary_path("seal_and_load");
+    let payload = payload(CHUNK_SIZE + 1000);
+    let properties = test_properties();
+
+    seal(&path, FileKind::Offsets, Some(&properties), &payload);
+    for mmap in [false, true] {
+        assert_eq!(&*container::load(&path, mmap, FileKind::Offsets).unwrap(), &payload[..]);
+    }
+    let header = container::read_header(&path).unwrap().unwrap();
+    assert_eq!((header.kind, header.properties), (FileKind::Offsets, properties));
+    assert_malformed(container::load(&path, false, FileKind::Graph), "holds offsets instead of a graph");
+
+    // Without properties, the file is the bare payload, which is loaded whatever the kind expected
+    seal(&path, FileKind::Offsets, None, &payload);
+    assert_eq!(fs::read(&path).unwrap(), payload);
+    assert_eq!(container::read_header(&path).unwrap(), None);
+    for mmap in [false, true] {
+        assert_eq!(&*container::load(&path, mmap, FileKind::Graph).unwrap(), &payload[..]);
+    }
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_load_corrupted_payload() {
+    let path = temporary_path("load_corrupted_payload");
+    let payload = payload(CHUNK_SIZE + 1000);
+    seal(&path, FileKind::Offsets, Some(&test_properties()), &payload);
+
+    let mut bytes = fs::read(&path).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 1;
+    fs::write(&path, &bytes).unwrap();
+
+    // The checksums of memory-mapped payloads are not verified, so that they are not read whole
+    assert_malformed(container::load(&path, false, FileKind::Offsets), "checksum mismatch in bytes");
+    assert_eq!(container::load(&path, true, FileKind::Offsets).unwrap()[..CHUNK_SIZE], payload[..CHUNK_SIZE]);
+
+    // Their length is
+    fs::write(&path, &bytes[..last]).unwrap();
+    for mmap in [false, true] {
+        assert_malformed(container::load(&path, mmap, FileKind::Offsets), "bytes of payload instead of");
+    }
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_load_corrupted_graph() {
+    let ascii = test_graph("load_corrupted_graph", 300);
+    let basename = &temporary_path("load_corrupted_graph");
+    bvgraph::BVGraphBuilder::new().set_container(true).build().store_graph(&ascii, basename).unwrap();
+    container::verify_files(basename).unwrap();
+
+    let path = format!("{}.graph", basename);
+    let mut bytes = fs::read(&path).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 1;
+    fs::write(&path, &bytes).unwrap();
+
+    assert_malformed(container::verify_files(basename), "checksum mismatch in bytes");
+    with_large_stack(|| {
+        assert_malformed(GraphLoader::new().load(basename).map(|_| ()), "checksum mismatch in bytes");
+        assert_malformed(GraphLoader::new().set_mmap(true).load_checked(basename).map(|_| ()), "checksum mismatch in bytes");
+        // Graphs memory-mapped for plain random access are loaded lazily
+        assert!(GraphLoader::new().set_mmap(true).load(basename).is_ok());
+    });
+
+    remove_graph(basename);
+}
+
+#[test]
+fn test_load_empty_payload() {
+    let path = temporary_path("load_empty_payload");
+
+    seal(&path, FileKind::SyncPoints, Some(&test_properties()), &[]);
+    for mmap in [false, true] {
+        assert!(container::load(&path, mmap, FileKind::SyncPoints).unwrap().is_empty());
+    }
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_sync_points_get() {
+    // Seven lists, followed by the final offset
+    let offsets = [0, 10, 20, 30, 40, 50, 60, 70];
+
+    let sync_points = SyncPoints::from_offsets(3, &offsets);
+    assert_eq!(sync_points, SyncPoints { interval: 3, offsets: vec![0, 30, 60].into() });
+
+    assert_eq!(sync_points.get(0), (0, 0));
+    assert_eq!(sync_points.get(2), (0, 0));
+    assert_eq!(sync_points.get(3), (3, 30));
+    assert_eq!(sync_points.get(5), (3, 30));
+    assert_eq!(sync_points.get(6), (6, 60));
+    // Past the last sync point
+    assert_eq!(sync_points.get(7), (6, 60));
+    assert_eq!(sync_points.get(100), (6, 60));
+
+    assert_eq!(SyncPoints::from_offsets(1, &offsets).offsets, offsets[..7].into());
+    assert_eq!(SyncPoints::from_offsets(10, &offsets).offsets, [0].into());
+
+    // A graph with no nodes has no sync points
+    for offsets in [&[][..], &[0]] {
+        let sync_points = SyncPoints::from_offsets(3, offsets);
+        assert!(sync_points.offsets.is_empty());
+        assert_eq!(sync_points.get(0), (0, 0));
+        assert_eq!(sync_points.get(5), (0, 0));
+    }
+}
+
+#[test]
+fn test_sync_points_store_and_load() {
+    let basename = temporary_path("sync_points_store_and_load");
+    let sync_points = SyncPoints { interval: 16, offsets: vec![0, 1000, 1 << 40].into() };
+
+    for properties in [None, Some(&test_properties())] {
+        sync_points.store(&basename, properties).unwrap();
+        assert_eq!(SyncPoints::load(&basename).unwrap(), sync_points);
+    }
+
+    let empty = SyncPoints { interval: 1, offsets: Box::default() };
+    empty.store(&basename, None).unwrap();
+    assert_eq!(SyncPoints::load(&basename).unwrap(), empty);
+
+    fs::remove_file(format!("{}.sync", basename)).unwrap();
+}
+
+#[test]
+fn test_sync_points_malformed() {
+    let basename = temporary_path("sync_points_malformed");
+    let path = format!("{}.sync", basename);
+
+    for payload in [&[][..], &[16, 0, 0, 0], &[16, 0, 0, 0, 0, 0, 0, 0, 1, 2, 3]] {
+        fs::write(&path, payload).unwrap();
+        assert_malformed(SyncPoints::load(&basename), "truncated sync points");
+    }
+
+    fs::write(&path, [0; 16]).unwrap();
+    assert_malformed(SyncPoints::load(&basename), "invalid sync interval 0");
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_iter_from_sync() {
+    let ascii = test_graph("iter_from_sync", 300);
+    let lists: Vec<_> = ascii.iter_nodes().collect();
+
+    for_each_format!("iter_from_sync", &ascii, 16, |graph, basename| {
+        let sync_points = SyncPoints::load(basename).unwrap();
+        assert_eq!(sync_points.interval, 16);
+        assert_eq!(graph.iter_nodes().collect::<Vec<_>>(), lists, "{}", basename);
+
+        // The first nodes, the nodes around a sync point, and the last ones
+        for x in [0, 1, 15, 16, 17, 100, 287, 288, 289, 298, 299, 300] {
+            assert_eq!(graph.iter_from_sync(x, &sync_points).collect::<Vec<_>>(), lists[x..], "{} from {}", basename, x);
+            assert_eq!(graph.iter_from(x).collect::<Vec<_>>(), lists[x..], "{} from {}", basename, x);
+        }
+    });
+}
+
+/// Asserts that `ranges` are at most `parts` non-empty ranges covering the nodes from 0 to `num_nodes`, in order.
+fn assert_covers(ranges: &[Range<usize>], num_nodes: usize, parts: usize) {
+    assert!(ranges.len() <= parts.max(1), "{} ranges instead of at most {}", ranges.len(), parts);
+    assert!(ranges.iter().all(|range| !range.is_empty()), "empty range in {:?}", ranges);
+
+    let mut end = 0;
+    for range in ranges {
+        assert_eq!(range.start, end, "{:?} does not cover the nodes", ranges);
+        end = range.end;
+    }
+    assert_eq!(end, num_nodes, "{:?} does not cover the nodes", ranges);
+}
+
+#[test]
+fn test_split_nodes() {
+    for parts in [0, 1, 2, 3, 7, 100] {
+        assert_covers(&split_nodes(100, parts, |x| x * 10), 100, parts);
+    }
+    for parts in [0, 1, 2, 3, 7] {
+        assert_eq!(split_nodes(100, parts, |x| x * 10).len(), parts.max(1));
+    }
+
+    // Ranges taking about the same number of bits
+    assert_eq!(split_nodes(100, 4, |x| x * 10), vec![0..25, 25..50, 50..75, 75..100]);
+}
+
+#[test]
+fn test_split_nodes_more_parts_than_nodes() {
+    for num_nodes in [1, 2, 5] {
+        let ranges = split_nodes(num_nodes, 10, |x| x * 3);
+        assert_covers(&ranges, num_nodes, num_nodes);
+    }
+}
+
+#[test]
+fn test_split_nodes_no_nodes() {
+    assert!(split_nodes(0, 4, |_| unreachable!()).is_empty());
+}
+
+#[test]
+fn test_split_nodes_uneven_offsets() {
+    // Most of the bits are taken by the last lists
+    let skewed = |x: usize| if x < 90 { x } else { 90 + (x - 89) * 1000 };
+    // Most of the bits are taken by the first list
+    let front = |x: usize| if x == 0 { 0 } else { 1000 + x };
+    // Every list is empty
+    let empty = |_| 42;
+
+    for parts in [2, 3, 8, 50] {
+        assert_covers(&split_nodes(100, parts, skewed), 100, parts);
+        assert_covers(&split_nodes(100, parts, front), 100, parts);
+        assert_covers(&split_nodes(100, parts, empty), 100, parts);
+    }
+
+    assert_eq!(split_nodes(100, 4, empty), vec![0..100]);
+}
+
+#[test]
+fn test_range_start() {
+    let offset = |x: usize| x * 10;
+    let successors = |x: usize| vec![x; x].into_boxed_slice();
+
+    let start = RangeStart::new(5..9, 3, offset, successors);
+    assert_eq!((start.nodes, start.position), (5..9, 50));
+    assert_eq!(start.window, vec![successors(2), successors(3), successors(4)]);
+
+    // The window is cut at the first node
+    let start = RangeStart::new(2..9, 7, offset, successors);
+    assert_eq!(start.position, 20);
+    assert_eq!(start.window, vec![successors(0), successors(1)]);
+
+    let start = RangeStart::new(0..9, 7, offset, successors);
+    assert_eq!(start.position, 0);
+    assert!(start.window.is_empty());
+
+    // Nothing is decoded for an empty range
+    let start = RangeStart::new(9..9, 7, |_| unreachable!(), |_| unreachable!());
+    assert_eq!((start.nodes, start.position), (9..9, 0));
+    assert!(start.window.is_empty());
+}
+
+#[test]
+fn test_split_iter() {
+    let ascii = test_graph("split_iter", 300);
+    let lists: Vec<_> = ascii.iter_nodes().collect();
+
+    for_each_format!("split_iter", &ascii, 0, |graph, basename| {
+        for parts in [1, 2, 3, 7, 40] {
+            let iters = graph.split_iter(parts);
+            assert!(iters.len() <= parts, "{}: {} ranges instead of at most {}", basename, iters.len(), parts);
+            assert_eq!(iters.into_iter().flatten().collect::<Vec<_>>(), lists, "{} split in {}", basename, parts);
+
+        }
+
+        for threads in [1, 2, 7] {
+            let scanned: Vec<Vec<_>> = graph.par_scan(threads, |iter| iter.collect());
+            assert!(scanned.iter().all(|range| !range.is_empty()), "{}: empty range", basename);
+            assert_eq!(scanned.concat(), lists, "{} scanned on {} threads", basename, threads);
+        }
+    });
+}
+
+#[test]
+fn test_successors_into_reused_buffers() {
+    let ascii = test_graph("successors_into", 300);
+    let num_nodes = ascii.num_nodes();
+
+    for_each_format!("successors_into", &ascii, 0, |graph, basename| {
+        let mut buffers = DecodeBuffers::new();
+        let mut successors = Vec::new();
+
+        // In order, backwards and scattered, so that lists follow longer ones and refer to lists decoded long before
+        let orders: [Vec<usize>; 3] = [
+            (0..num_nodes).collect(),
+            (0..num_nodes).rev().collect(),
+            (0..num_nodes).map(|i| i * 7 % num_nodes).collect(),
+        ];
+        for x in orders.into_iter().flatten() {
+            graph.successors_into(x, &mut buffers, &mut successors);
+            assert_eq!(successors[..], *graph.successors(x), "{}: node {}", basename, x);
+            assert_eq!(successors[..], *ascii.successors(x), "{}: node {}", basename, x);
+        }
+    });
+}
+
+/// Compresses the given nodes in the given format to the given basename, with the given sync interval and threads.
+fn store_nodes(nodes: &[(usize, Vec<usize>)], format: GraphFormat, basename: &str, sync_interval: usize, threads: usize) -> crate::Result<()> {
+    let nodes = nodes.iter().map(|(x, successors)| (*x, successors));
+
+    with_large_stack(|| match format {
+        GraphFormat::BV => bvgraph::BVGraphBuilder::new()
+            .set_sync_interval(sync_interval)
+            .set_threads(threads)
+            .build()
+            .store_nodes(nodes, basename),
+        GraphFormat::Huffman => bvgraph_huffman_out::DefaultBVGraphBuilder::new()
+            .set_sync_interval(sync_interval)
+            .set_threads(threads)
+            .build()
+            .store_nodes(nodes, basename),
+        GraphFormat::Zuckerli => zuckerli_out::DefaultBVGraphBuilder::new()
+            .set_sync_interval(sync_interval)
+            .set_threads(threads)
+            .build()
+            .store_nodes(nodes, basename),
+    })
+}
+
+#[test]
+fn test_store_nodes_not_consecutive() {
+    let ascii = test_graph("not_consecutive", 100);
+    let lists: Vec<(usize, Vec<usize>)> = ascii.iter_nodes().map(|(x, successors)| (x, successors.into())).collect();
+
+    let mut gap = lists.clone();
+    gap.remove(50);
+    let mut repeated = lists.clone();
+    repeated.insert(50, lists[49].clone());
+    let shifted: Vec<_> = lists.iter().map(|(x, successors)| (x + 1, successors.clone())).collect();
+
+    for format in FORMATS {
+        let basename = &temporary_path(&format!("not_consecutive_{:?}", format));
+        compress(&ascii, format, basename, 0, 1);
+
+        for (sync_interval, threads) in [(0, 1), (0, 2), (16, 1)] {
+            for (nodes, found, expected) in [(&gap, 51, 50), (&repeated, 49, 50), (&shifted, 1, 0)] {
+                match store_nodes(nodes, format, basename, sync_interval, threads) {
+                    Err(Error::MisplacedNode { node, expected: x }) => assert_eq!((node, x), (found, expected)),
+                    result => panic!("{:?} instead of node {} misplaced", result, found),
+                }
+            }
+        }
+
+        // The graph compressed before is left as it was
+        assert!(!Path::new(&format!("{}.graph.payload", basename)).exists());
+        let difference = with_large_stack(|| first_difference(&ascii, &GraphLoader::new().load_sequential(basename).unwrap()));
+        assert_eq!(difference, None, "{}", basename);
+
+        remove_graph(basename);
+    }
+}
+
+/// Asserts that the two offsets have the same value for each of the given number of nodes.
+fn assert_same_offsets(first: &impl Offsets, second: &impl Offsets, num_nodes: usize) {
+    for x in 0..num_nodes {
+        assert_eq!(first.get(x), second.get(x), "offset of node {}", x);
+    }
+}
+
+#[test]
+fn test_offsets_backends() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut values = vec![0];
+    for _ in 0..1000 {
+        // Repeated offsets, of empty lists, as well as large gaps
+        let gap = if rng.gen_bool(0.2) { 0 } else { rng.gen_range(1..100_000) };
+        values.push(values.last().unwrap() + gap);
+    }
+    let max_value = *values.last().unwrap();
+
+    let boxed = <Box<[usize]>>::from_values(values.iter().copied(), values.len(), max_value);
+    let ef = EliasFano::from_values(values.iter().copied(), values.len(), max_value);
+    assert_eq!(boxed, values.clone().into());
+    assert_same_offsets(&boxed, &ef, values.len());
+    assert_same_offsets(&<Box<[usize]>>::from_elias_fano(ef.clone()), &ef, values.len());
+}
+
+#[test]
+fn test_loaded_offsets_backends() {
+    let ascii = test_graph("loaded_offsets", 300);
+    let num_nodes = ascii.num_nodes();
+
+    for elias_fano in [false, true] {
+        let basename = &temporary_path(&format!("loaded_offsets_{}", elias_fano));
+        bvgraph::BVGraphBuilder::new().set_elias_fano(elias_fano).build().store_graph(&ascii, basename).unwrap();
+        assert_eq!(Path::new(&format!("{}.offsets.ef", basename)).exists(), elias_fano);
+
+        let props = Properties::load(basename).unwrap();
+        let builder = || GraphLoader::new().bv_builder(&props, basename).unwrap();
+        let boxed = builder().load_offsets(basename).unwrap().build();
+        let ef = builder().load_offsets_as::<EliasFano>(basename).unwrap().build();
+        assert_same_offsets(&boxed.offsets, &ef.offsets, num_nodes);
+
+        // The offsets point to the lists of the graph
+        for x in 0..num_nodes {
+            assert_eq!(*boxed.successors(x), *ascii.successors(x));
+            assert_eq!(*ef.successors(x), *ascii.successors(x));
+        }
+
+        remove_graph(basename);
+    }
+}
+
+#[test]
+fn test_offsets_past_the_end() {
+    let ascii = test_graph("offsets_past_the_end", 300);
+
+    for format in FORMATS {
+        for elias_fano in [false, true] {
+            let basename = &temporary_path(&format!("offsets_past_the_end_{:?}_{}", format, elias_fano));
+            with_large_stack(|| match format {
+                GraphFormat::BV => bvgraph::BVGraphBuilder::new().set_elias_fano(elias_fano).build().store_graph(&ascii, basename),
+                GraphFormat::Huffman => bvgraph_huffman_out::DefaultBVGraphBuilder::new().set_elias_fano(elias_fano).build().store_graph(&ascii, basename),
+                GraphFormat::Zuckerli => zuckerli_out::DefaultBVGraphBuilder::new().set_elias_fano(elias_fano).build().store_graph(&ascii, basename),
+            })
+            .unwrap();
+
+            // The graph loses its second half, which the last offsets point to
+            let path = format!("{}.graph", basename);
+            let graph = fs::read(&path).unwrap();
+            fs::write(&path, &graph[..graph.len() / 2]).unwrap();
+
+            let loaded = with_large_stack(|| GraphLoader::new().load(basename).map(|_| ()));
+            assert_malformed(loaded, "past the end of a");
+
+            remove_graph(basename);
+        }
+    }
+}
+
+#[test]
+fn test_point_queries() {
+    let ascii = test_graph("point_queries", 120);
+    let num_nodes = ascii.num_nodes();
+
+    for_each_format!("point_queries", &ascii, 0, |graph, basename| {
+        for x in 0..num_nodes {
+            let successors = &ascii.successors(x)[..];
+
+            // Each successor, the nodes next to them, and the nodes at both ends
+            let mut candidates = vec![0, num_nodes - 1, num_nodes];
+            for &y in successors.iter() {
+                candidates.extend([y.saturating_sub(1), y, y + 1]);
+            }
+            for y in candidates {
+                assert_eq!(graph.has_arc(x, y), successors.contains(&y), "{}: arc from {} to {}", basename, x, y);
+            }
+
+            for i in 0..=successors.len() {
+                assert_eq!(graph.successor(x, i), successors.get(i).copied(), "{}: successor {} of {}", basename, i, x);
+            }
+
+            let bounds = [0, successors.first().copied().unwrap_or(0), num_nodes / 2, successors.last().map_or(0, |y| y + 1), num_nodes];
+            for lo in bounds {
+                for hi in bounds {
+                    let expected: Vec<_> = successors.iter().copied().filter(|y| (lo..hi).contains(y)).collect();
+                    let in_range: Vec<_> = graph.successors_in_range(x, lo, hi).collect();
+                    assert_eq!(in_range, expected, "{}: successors of {} in [{}, {})", basename, x, lo, hi);
+                }
+            }
+        }
+    });
+}
+
+#[test]
+fn test_successors_iter() {
+    let ascii = test_graph("successors_iter", 300);
+
+    for_each_format!("successors_iter", &ascii, 0, |graph, basename| {
+        for x in 0..ascii.num_nodes() {
+            let successors: Box<[usize]> = graph.successors_iter(x).collect();
+            assert_eq!(successors, graph.successors(x), "{}: node {}", basename, x);
+            assert_eq!(successors, ascii.successors(x), "{}: node {}", basename, x);
+        }
+    });
+}
+
+#[test]
+fn test_stats_without_nodes_or_arcs() {
+    let stats = CompressionStats { graph_bits: 64, ..Default::default() };
+    assert_eq!(stats.bits_per_node(), 0.);
+    assert_eq!(stats.bits_per_link(), 0.);
+    assert_eq!(stats.avg_reference(), 0.);
+    assert_eq!(stats.avg_chain_length(), 0.);
+    assert_eq!(stats.copy_ratio(), 0.);
+    assert_eq!(stats.interval_coverage(), 0.);
+
+    let stats = CompressionStats { nodes: 4, graph_bits: 64, total_reference: 6, total_chain_length: 2, ..Default::default() };
+    assert_eq!(stats.bits_per_node(), 16.);
+    assert_eq!(stats.bits_per_link(), 0.);
+    assert_eq!(stats.avg_reference(), 1.5);
+    assert_eq!(stats.avg_chain_length(), 0.5);
+}
+
+#[test]
+fn test_stats_merge() {
+    let mut stats = CompressionStats {
+        nodes: 10,
+        arcs: 30,
+        graph_bits: 1000,
+        header_bits: 100,
+        outdegree_bits: 1,
+        reference_bits: 2,
+        block_bits: 3,
+        interval_bits: 4,
+        residual_bits: 5,
+        total_reference: 6,
+        total_chain_length: 7,
+        copied_arcs: 8,
+        intervalised_arcs: 9,
+        residual_arcs: 10,
+        successor_gaps: vec![1, 2],
+        residual_gaps: vec![1, 2, 3],
+        bits_per_context: vec![],
+    };
+    let chunk = CompressionStats {
+        nodes: 5,
+        arcs: 20,
+        graph_bits: 500,
+        header_bits: 50,
+        outdegree_bits: 10,
+        reference_bits: 20,
+        block_bits: 30,
+        interval_bits: 40,
+        residual_bits: 50,
+        total_reference: 60,
+        total_chain_length: 70,
+        copied_arcs: 80,
+        intervalised_arcs: 90,
+        residual_arcs: 100,
+        successor_gaps: vec![10, 20, 30],
+        residual_gaps: vec![10],
+        bits_per_context: vec![4, 5],
+    };
+    stats.merge(&chunk);
+
+    // The sums of the sections and of the histograms, which are extended to the longest one
+    assert_eq!(stats, CompressionStats {
+        nodes: 10,
+        arcs: 30,
+        graph_bits: 1000,
+        header_bits: 100,
+        outdegree_bits: 11,
+        reference_bits: 22,
+        block_bits: 33,
+        interval_bits: 44,
+        residual_bits: 55,
+        total_reference: 66,
+        total_chain_length: 77,
+        copied_arcs: 88,
+        intervalised_arcs: 99,
+        residual_arcs: 110,
+        successor_gaps: vec![11, 22, 30],
+        residual_gaps: vec![11, 2, 3],
+        bits_per_context: vec![4, 5],
+    });
+}
+
+#[test]
+fn test_stats_store_and_load() {
+    let stats = CompressionStats {
+        nodes: 3,
+        arcs: 7,
+        graph_bits: 100,
+        header_bits: 0,
+        outdegree_bits: 9,
+        reference_bits: 4,
+        block_bits: 5,
+        interval_bits: 6,
+        residual_bits: 70,
+        total_reference: 4,
+        total_chain_length: 2,
+        copied_arcs: 1,
+        intervalised_arcs: 2,
+        residual_arcs: 4,
+        successor_gaps: vec![3, 0, 4],
+        residual_gaps: vec![2, 2],
+        bits_per_context: vec![7, 0, 8],
+    };
+    let path = temporary_path("stats_store_and_load.stats");
+
+    stats.store(&path).unwrap();
+    assert_eq!(CompressionStats::load(&path).unwrap(), stats);
+
+    let empty = CompressionStats::default();
+    empty.store(&path).unwrap();
+    assert_eq!(CompressionStats::load(&path).unwrap(), empty);
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_stats_of_compressed_graphs() {
+    let ascii = test_graph("stats_of_compressed_graphs", 300);
+
+    for format in FORMATS {
+        let basename = &temporary_path(&format!("stats_of_compressed_graphs_{:?}", format));
+        with_large_stack(|| match format {
+            GraphFormat::BV => bvgraph::BVGraphBuilder::new().set_stats(true).build().store_graph(&ascii, basename),
+            GraphFormat::Huffman => bvgraph_huffman_out::DefaultBVGraphBuilder::new().set_stats(true).build().store_graph(&ascii, basename),
+            GraphFormat::Zuckerli => zuckerli_out::DefaultBVGraphBuilder::new().set_stats(true).build().store_graph(&ascii, basename),
+        })
+        .unwrap();
+
+        let path = format!("{}.stats", basename);
+        let stats = CompressionStats::load(&path).unwrap();
+        assert_eq!((stats.nodes, stats.arcs), (ascii.num_nodes(), ascii.num_arcs()), "{}", basename);
+        assert_eq!(stats.copied_arcs + stats.intervalised_arcs + stats.residual_arcs, ascii.num_arcs() as u64, "{}", basename);
+        assert_eq!(stats.successor_gaps.iter().sum::<u64>(), ascii.num_arcs() as u64, "{}", basename);
+        assert_eq!(stats.to_string(), fs::read_to_string(&path).unwrap(), "{}", basename);
+
+        remove_graph(basename);
+    }
+}
+
+/// Asserts that `graph` has the same lists as `ascii`, scanning it and accessing the nodes around the starts of
+/// the chunks of the given size.
+fn assert_same_graph<G: RandomAccessGraph<NodeT = usize>>(graph: &G, ascii: &AsciiGraph<usize>, chunk_size: usize, basename: &str) {
+    assert_eq!(first_difference(ascii, graph), None, "{}", basename);
+
+    for start in (chunk_size..ascii.num_nodes()).step_by(chunk_size) {
+        for x in start - 2..(start + 2).min(ascii.num_nodes()) {
+            assert_eq!(graph.successors(x), ascii.successors(x), "{}: node {}", basename, x);
+        }
+    }
+}
+
+#[test]
+fn test_parallel_compression() {
+    // More than two chunks, so that the threads compress chunks out of order
+    let num_nodes = 2 * parallel::CHUNK_SIZE + 1000;
+    let ascii = test_graph("parallel_compression", num_nodes);
+
+    for format in FORMATS {
+        // Chunks of CHUNK_SIZE nodes, and chunks starting at each sync point
+        for sync_interval in [0, 10_000] {
+            let basename = &temporary_path(&format!("parallel_compression_{:?}_{}", format, sync_interval));
+            compress(&ascii, format, basename, sync_interval, 3);
+
+            let chunk_size = parallel::chunk_size(sync_interval);
+            with_large_stack(|| match format {
+                GraphFormat::BV => assert_same_graph(&load_bv(basename), &ascii, chunk_size, basename),
+                GraphFormat::Huffman => assert_same_graph(&load_huffman(basename), &ascii, chunk_size, basename),
+                GraphFormat::Zuckerli => assert_same_graph(&load_zuckerli(basename), &ascii, chunk_size, basename),
+            });
+            if sync_interval > 0 {
+                assert_eq!(SyncPoints::load(basename).unwrap().interval, sync_interval);
+            }
+
+            remove_graph(basename);
+        }
+    }
+}
+
+#[test]
+fn test_cached_lists() {
+    let ascii = test_graph("cached_lists", 300);
+    let num_nodes = ascii.num_nodes();
+    // Scattered, and then again, so that lists are found in the cache, evicted, and refer to cached lists
+    let order: Vec<usize> = (0..2 * num_nodes).map(|i| i * 7 % num_nodes).chain((0..num_nodes).rev()).collect();
+
+    for format in FORMATS {
+        let basename = &temporary_path(&format!("cached_lists_{:?}", format));
+        compress(&ascii, format, basename, 0, 1);
+        let props = Properties::load(basename).unwrap();
+
+        for capacity in [0, 1, 16, 1000] {
+            for pinning in [None, Some(Pinning::Degree(30)), Some(Pinning::References(30))] {
+                let loader = GraphLoader::new().set_cache_size(capacity);
+                let case = format!("{} with capacity {} and {:?}", basename, capacity, pinning);
+
+                macro_rules! check {
+                    ($($builder:tt)+) => {{
+                        let mut graph = loader.$($builder)+(&props, basename).unwrap().load_offsets(basename).unwrap().build();
+                        if let Some(pinning) = pinning {
+                            graph.pin(pinning);
+                        }
+
+                        for &x in &order {
+                            assert_eq!(graph.successors(x), ascii.successors(x), "{}: node {}", case, x);
+                        }
+                        match graph.cache_stats() {
+                            Some(stats) => assert!(stats.hits > 0 && stats.misses > 0, "{}: {:?}", case, stats),
+                            None => assert!(capacity == 0 && pinning.is_none(), "{}: no cache", case),
+                        }
+                    }};
+                }
+
+                with_large_stack(|| match format {
+                    GraphFormat::BV => check!(bv_builder),
+                    GraphFormat::Huffman => check!(huffman_builder::<Box<[usize]>>),
+                    GraphFormat::Zuckerli => check!(zuckerli_builder::<Box<[usize]>>),
+                });
+            }
+        }
+
+        remove_graph(basename);
+    }
+}
+
+/// Asserts that a list decoded in checked mode from a corrupted graph with the given number of nodes is well formed.
+fn assert_well_formed(list: &[usize], num_nodes: usize, case: &str) {
+    assert!(list.windows(2).all(|pair| pair[0] < pair[1]), "{}: {:?} is not increasing", case, list);
+    assert!(list.iter().all(|&y| y < num_nodes), "{}: {:?} is out of the graph", case, list);
+}
+
+#[test]
+fn test_checked_bv_corrupted() {
+    let ascii = test_graph("checked_bv_corrupted", 300);
+    let num_nodes = ascii.num_nodes();
+    let source = &temporary_path("checked_bv_corrupted_source");
+    compress(&ascii, GraphFormat::BV, source, 0, 1);
+
+    let basename = &temporary_path("checked_bv_corrupted");
+    fs::copy(format!("{}.properties", source), format!("{}.properties", basename)).unwrap();
+    let props = Properties::load(basename).unwrap();
+    let files = ["graph", "offsets"].map(|extension| (extension, fs::read(format!("{}.{}", source, extension)).unwrap()));
+
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut errors = 0;
+    for i in 0..300 {
+        // A few bits flipped in either file
+        let corrupted = rng.gen_range(0..files.len());
+        for (j, (extension, bytes)) in files.iter().enumerate() {
+            let mut bytes = bytes.clone();
+            if j == corrupted {
+                for _ in 0..rng.gen_range(1..4) {
+                    let bit = rng.gen_range(0..bytes.len() * 8);
+                    bytes[bit / 8] ^= 1 << (bit % 8);
+                }
+            }
+            fs::write(format!("{}.{}", basename, extension), bytes).unwrap();
+        }
+        let case = format!("corruption {} of the .{} file", i, files[corrupted].0);
+
+        let graph = match GraphLoader::new().bv_builder(&props, basename).and_then(|builder| builder.load_offsets(basename)) {
+            Ok(builder) => builder.build(),
+            Err(_) => {
+                errors += 1;
+                continue;
+            },
+        };
+
+        let scanned: Vec<_> = graph.try_iter_nodes().collect();
+        if scanned.iter().any(Result::is_err) {
+            errors += 1;
+        }
+        for (_, list) in scanned.into_iter().flatten() {
+            assert_well_formed(&list, num_nodes, &case);
+        }
+        for x in 0..num_nodes {
+            match graph.try_successors(x) {
+                Ok(list) => assert_well_formed(&list, num_nodes, &case),
+                Err(_) => errors += 1,
+            }
+        }
+    }
+    assert!(errors > 0, "no corruption detected");
+
+    remove_graph(source);
+    remove_graph(basename);
+}
+
+#[test]
+fn test_bv_codes_round_trip() {
+    const CODES: [Code; 4] = [Code::Gamma, Code::Delta, Code::Zeta, Code::Unary];
+
+    let ascii = test_graph("bv_codes_round_trip", 200);
+    let basename = &temporary_path("bv_codes_round_trip");
+    for outdegree in CODES {
+        for block in CODES {
+            for interval in CODES {
+                for residual in CODES {
+                    let codes = format!("{:?}/{:?}/{:?}/{:?}", outdegree, block, interval, residual);
+                    bvgraph::BVGraphBuilder::new()
+                        .set_out_outdegree_coding(outdegree)
+                        .set_out_block_coding(block)
+                        .set_out_interval_coding(interval)
+                        .set_out_residual_coding(residual)
+                        .build()
+                        .store_graph(&ascii, basename)
+                        .unwrap();
+
+                    let props = Properties::load(basename).unwrap();
+                    assert_eq!(
+                        [props.outdegree_coding, props.block_coding, props.interval_coding, props.residual_coding]
+                            .map(|coding| Code::try_from(coding).unwrap()),
+                        [outdegree, block, interval, residual],
+                        "{}",
+                        codes,
+                    );
+                    assert_eq!(first_difference(&load_bv(basename), &ascii), None, "{}", codes);
+                }
+            }
+        }
+    }
+    remove_graph(basename);
+}
+
+#[test]
+fn test_mmap_load() {
+    let ascii = test_graph("mmap_load", 300);
+    let num_nodes = ascii.num_nodes();
+
+    for format in FORMATS {
+        for elias_fano in [false, true] {
+            for use_container in [false, true] {
+                let basename = &temporary_path(&format!("mmap_load_{:?}_{}_{}", format, elias_fano, use_container));
+                with_large_stack(|| match format {
+                    GraphFormat::BV => bvgraph::BVGraphBuilder::new()
+                        .set_elias_fano(elias_fano)
+                        .set_container(use_container)
+                        .build()
+                        .store_graph(&ascii, basename),
+                    GraphFormat::Huffman => bvgraph_huffman_out::DefaultBVGraphBuilder::new()
+                        .set_elias_fano(elias_fano)
+                        .set_container(use_container)
+                        .build()
+                        .store_graph(&ascii, basename),
+                    GraphFormat::Zuckerli => zuckerli_out::DefaultBVGraphBuilder::new()
+                        .set_elias_fano(elias_fano)
+                        .set_container(use_container)
+                        .build()
+                        .store_graph(&ascii, basename),
+                })
+                .unwrap();
+
+                with_large_stack(|| {
+                    let in_memory = GraphLoader::new().load(basename).unwrap();
+                    let mapped = GraphLoader::new().set_mmap(true).load(basename).unwrap();
+                    let checked = GraphLoader::new().set_mmap(true).load_checked(basename).unwrap();
+
+                    assert_eq!(first_difference(&mapped, &in_memory), None, "{}", basename);
+                    assert_eq!(first_difference(&mapped, &ascii), None, "{}", basename);
+                    for x in 0..num_nodes {
+                        assert_eq!(mapped.successors(x), in_memory.successors(x), "{}: node {}", basename, x);
+                        assert_eq!(checked.try_successors(x).unwrap(), in_memory.successors(x), "{}: node {}", basename, x);
+                    }
+                });
+
+                remove_graph(basename);
+            }
+        }
+    }
+}
@@ -1,9 +1,14 @@
-use std::{fs, vec, cmp::Ordering, marker::PhantomData, cell::{RefCell, Cell}, rc::Rc};
+use std::{fs, vec, borrow::Cow, cmp::Ordering, marker::PhantomData, cell::{RefCell, Cell}, rc::Rc, ops::Range};
 
-use sucds::{mii_sequences::{EliasFanoBuilder, EliasFano}, Serializable};
+use crate::{Error, SequentialGraph, RandomAccessGraph, AccessCost, CheckedGraph, CheckedNodes, Store, properties::{GraphFormat, Properties}, utils::{encodings::{UniversalCode, GammaCode, Huff, Huffman, UnaryCode, ZetaCode, zuck_encode, K_ZUCK, I_ZUCK, J_ZUCK}, nat2int, int2nat}, huffman_zuckerli::huffman_decoder::HuffmanDecoder};
+use crate::bitstreams::{BinaryReader, BinaryWriter, InputStream};
 
-use crate::{ImmutableGraph, properties::Properties, utils::{encodings::{UniversalCode, GammaCode, Huffman, zuck_encode, K_ZUCK, I_ZUCK, J_ZUCK}, nat2int, int2nat}, huffman_zuckerli::huffman_decoder::HuffmanDecoder};
-use crate::bitstreams::{BinaryReader, BinaryWriter};
+use super::buffers::DecodeBuffers;
+use super::cache::{CacheStats, Pinning, SharedCache};
+use super::container::{self, FileKind};
+use super::offsets::{Offsets, load_offsets};
+use super::parallel::{self, RangeStart};
+use super::sync::SyncPoints;
 
 pub const FIRST_DEGREE_CTX: usize = 0;
 pub const DEGREE_BASE_CTX: usize = 1;
@@ -31,7 +36,7 @@ struct CompressionVectors {
     residuals: RefCell<Vec<usize>>,
 }
 
-#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Clone, Debug)]
 pub struct BVGraph<
     InBlockCoding: Huffman,
     InBlockCountCoding: UniversalCode,
@@ -47,16 +52,19 @@ pub struct BVGraph<
     OutReferenceCoding: UniversalCode,
     OutIntervalCoding: UniversalCode,
     OutResidualCoding: UniversalCode,
+    O: Offsets = Box<[usize]>,
 > {
     n: usize,
     m: usize,
-    pub graph_memory: Rc<[u8]>,
-    pub offsets: Box<[usize]>,
+    pub graph_memory: InputStream,
+    pub offsets: O,
+    huffman_decoder: HuffmanDecoder,
     graph_binary_wrapper: Rc<RefCell<BinaryReader>>,
     outdegrees_binary_wrapper: RefCell<BinaryReader>,
     cached_node: Cell<Option<usize>>,
     cached_outdegree: Cell<Option<usize>>,
     cached_ptr: Cell<Option<usize>>,
+    cache: SharedCache,
     in_max_ref_count: usize,
     in_window_size: usize,
     in_min_interval_len: usize,
@@ -97,7 +105,8 @@ impl<
     OutReferenceCoding: UniversalCode,
     OutIntervalCoding: UniversalCode,
     OutResidualCoding: UniversalCode,
-> ImmutableGraph for BVGraph<
+    O: Offsets,
+> SequentialGraph for BVGraph<
     InBlockCoding,
     InBlockCountCoding,
     InOutdegreeCoding,
@@ -112,6 +121,7 @@ impl<
     OutReferenceCoding,
     OutIntervalCoding,
     OutResidualCoding,
+    O,
 >
 {
     type NodeT = usize;
@@ -128,23 +138,180 @@ impl<
         self.m
     }
 
+    /// Returns an iterator over the nodes of the BVGraph, each paired with its successors.
+    fn iter_nodes(&self) -> impl Iterator<Item = (Self::NodeT, Box<[Self::NodeT]>)> + '_ {
+        let mut iter = self.iter();
+
+        std::iter::from_fn(move || {
+            let node = iter.next()?;
+            Some((node, iter.successor_array()[..iter.outdegree()].into()))
+        })
+    }
+}
+
+impl<
+    InBlockCoding: Huffman,
+    InBlockCountCoding: UniversalCode,
+    InOutdegreeCoding: Huffman,
+    InOffsetCoding: UniversalCode,
+    InReferenceCoding: UniversalCode,
+    InIntervalCoding: Huffman,
+    InResidualCoding: Huffman,
+    OutBlockCoding: UniversalCode,
+    OutBlockCountCoding: UniversalCode,
+    OutOutdegreeCoding: UniversalCode,
+    OutOffsetCoding: UniversalCode,
+    OutReferenceCoding: UniversalCode,
+    OutIntervalCoding: UniversalCode,
+    OutResidualCoding: UniversalCode,
+    O: Offsets,
+> RandomAccessGraph for BVGraph<
+    InBlockCoding,
+    InBlockCountCoding,
+    InOutdegreeCoding,
+    InOffsetCoding,
+    InReferenceCoding,
+    InIntervalCoding,
+    InResidualCoding,
+    OutBlockCoding,
+    OutBlockCountCoding,
+    OutOutdegreeCoding,
+    OutOffsetCoding,
+    OutReferenceCoding,
+    OutIntervalCoding,
+    OutResidualCoding,
+    O,
+>
+{
+    /// Returns the outdegree of a given node or `None` otherwise.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The node number
     fn outdegree(&self, x: Self::NodeT) -> Option<usize> {
-        todo!("Impossible Huffman with this signature")
+        if x >= self.n {
+            return None;
+        }
+
+        Some(self.outdegree_internal(x, &self.huffman_decoder))
     }
 
+    /// Returns the list of successors of a given node.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The node number
     fn successors(&self, x: Self::NodeT) -> Box<[Self::NodeT]> {
-        todo!("Impossible Huffman with this signature")
+        let mut successors = Vec::new();
+        self.successors_into(x, &mut DecodeBuffers::default(), &mut successors);
+
+        successors.into_boxed_slice()
+    }
+
+    /// Returns a lazy iterator over the successors of a given node.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The node number
+    fn successors_iter(&self, x: Self::NodeT) -> impl Iterator<Item = Self::NodeT> + '_ {
+        assert!(x < self.n, "Node index out of range {}", x);
+        BVGraphSuccessors::new(self, x)
     }
+}
 
-    fn store(&mut self, basename: &str) -> std::io::Result<()> {      
-        let mut graph_obs = BinaryWriter::new();
-        let mut offsets_obs = BinaryWriter::new();
+impl<
+    InBlockCoding: Huffman,
+    InBlockCountCoding: UniversalCode,
+    InOutdegreeCoding: Huffman,
+    InOffsetCoding: UniversalCode,
+    InReferenceCoding: UniversalCode,
+    InIntervalCoding: Huffman,
+    InResidualCoding: Huffman,
+    OutBlockCoding: UniversalCode,
+    OutBlockCountCoding: UniversalCode,
+    OutOutdegreeCoding: UniversalCode,
+    OutOffsetCoding: UniversalCode,
+    OutReferenceCoding: UniversalCode,
+    OutIntervalCoding: UniversalCode,
+    OutResidualCoding: UniversalCode,
+    O: Offsets,
+> CheckedGraph for BVGraph<
+    InBlockCoding,
+    InBlockCountCoding,
+    InOutdegreeCoding,
+    InOffsetCoding,
+    InReferenceCoding,
+    InIntervalCoding,
+    InResidualCoding,
+    OutBlockCoding,
+    OutBlockCountCoding,
+    OutOutdegreeCoding,
+    OutOffsetCoding,
+    OutReferenceCoding,
+    OutIntervalCoding,
+    OutResidualCoding,
+    O,
+>
+{
+    fn try_iter_nodes(&self) -> CheckedNodes<'_> {
+        Box::new(BVGraph::try_iter_nodes(self))
+    }
+
+    fn try_successors(&self, x: usize) -> crate::Result<Box<[usize]>> {
+        BVGraph::try_successors(self, x)
+    }
+
+    fn try_access_cost(&self, x: usize) -> crate::Result<AccessCost> {
+        BVGraph::try_access_cost(self, x)
+    }
+}
+
+impl<
+    InBlockCoding: Huffman,
+    InBlockCountCoding: UniversalCode,
+    InOutdegreeCoding: Huffman,
+    InOffsetCoding: UniversalCode,
+    InReferenceCoding: UniversalCode,
+    InIntervalCoding: Huffman,
+    InResidualCoding: Huffman,
+    OutBlockCoding: UniversalCode,
+    OutBlockCountCoding: UniversalCode,
+    OutOutdegreeCoding: UniversalCode,
+    OutOffsetCoding: UniversalCode,
+    OutReferenceCoding: UniversalCode,
+    OutIntervalCoding: UniversalCode,
+    OutResidualCoding: UniversalCode,
+    O: Offsets,
+> Store for BVGraph<
+    InBlockCoding,
+    InBlockCountCoding,
+    InOutdegreeCoding,
+    InOffsetCoding,
+    InReferenceCoding,
+    InIntervalCoding,
+    InResidualCoding,
+    OutBlockCoding,
+    OutBlockCountCoding,
+    OutOutdegreeCoding,
+    OutOffsetCoding,
+    OutReferenceCoding,
+    OutIntervalCoding,
+    OutResidualCoding,
+    O,
+>
+{
+    fn store(&mut self, basename: &str) -> crate::Result<()> {      
+        let graph_path = format!("{}.graph", basename);
+        let offsets_path = format!("{}.offsets", basename);
+        let mut graph_obs = BinaryWriter::with_sink(container::create(&graph_path)?);
+        let mut offsets_obs = BinaryWriter::with_sink(container::create(&offsets_path)?);
 
         self.compress(&mut graph_obs, &mut offsets_obs);
         
-        let graph = graph_obs.build();
-        let offsets = offsets_obs.build();
+        graph_obs.finish().map_err(Error::io(&graph_path))?;
+        offsets_obs.finish().map_err(Error::io(&offsets_path))?;
         let props = Properties {
+            format: GraphFormat::BV,
             nodes: self.n,
             arcs: self.m,
             window_size: self.out_window_size,
@@ -160,9 +327,10 @@ impl<
             offset_coding: OutOffsetCoding::to_encoding_type()
         };
 
-        fs::write(format!("{}.graph", basename), graph.os).unwrap();
-        fs::write(format!("{}.offsets", basename), offsets.os).unwrap();
-        fs::write(format!("{}.properties", basename), Into::<String>::into(props))?;
+        container::seal(&graph_path, FileKind::Graph, None)?;
+        container::seal(&offsets_path, FileKind::Offsets, None)?;
+        let properties_path = format!("{}.properties", basename);
+        fs::write(&properties_path, Into::<String>::into(props)).map_err(Error::io(&properties_path))?;
 
         Ok(())
     }
@@ -183,6 +351,7 @@ pub struct BVGraphNodeIterator<
     OutReferenceCoding: UniversalCode,
     OutIntervalCoding: UniversalCode,
     OutResidualCoding: UniversalCode,
+    O: Offsets,
     BV: AsRef<BVGraph<
         InBlockCoding,
         InBlockCountCoding,
@@ -198,9 +367,10 @@ pub struct BVGraphNodeIterator<
         OutReferenceCoding,
         OutIntervalCoding,
         OutResidualCoding,
+        O,
 >>>
 {
-    // The number of nodes
+    // The node at which the iteration ends, that is the number of nodes unless iterating on a range
     n: usize,
     // The graph on which we iterate
     graph: BV,
@@ -214,6 +384,10 @@ pub struct BVGraphNodeIterator<
     window: Vec<Vec<usize>>,
     // Outdegrees of the window's lists to be passed to [`decode_list`]
     outd: Vec<usize>,
+    // The buffers for decoding the lists
+    buffers: DecodeBuffers,
+    // The vector receiving the next list, which then takes the place of the oldest list of the window
+    list: Vec<usize>,
     // The index of the node from which we started iterating
     from: usize,
     // The index of the node just before the next one
@@ -232,6 +406,7 @@ pub struct BVGraphNodeIterator<
     _phantom_out_reference_coding: PhantomData<OutReferenceCoding>,
     _phantom_out_interval_coding: PhantomData<OutIntervalCoding>,
     _phantom_out_residual_coding: PhantomData<OutResidualCoding>,
+    _phantom_offsets: PhantomData<O>,
 }
 
 impl<
@@ -249,6 +424,7 @@ impl<
     OutReferenceCoding: UniversalCode,
     OutIntervalCoding: UniversalCode,
     OutResidualCoding: UniversalCode,
+    O: Offsets,
     BV: AsRef<BVGraph<
         InBlockCoding,
         InBlockCountCoding,
@@ -264,6 +440,7 @@ impl<
         OutReferenceCoding,
         OutIntervalCoding,
         OutResidualCoding,
+        O,
 >>> Iterator for BVGraphNodeIterator<
         InBlockCoding,
         InBlockCountCoding,
@@ -279,6 +456,7 @@ impl<
         OutReferenceCoding,
         OutIntervalCoding,
         OutResidualCoding,
+        O,
         BV
 > {
     type Item = usize;
@@ -290,26 +468,15 @@ impl<
 
         self.curr += 1;
         let curr_idx = self.curr as usize % self.cyclic_buffer_size;
-        let decoded_list = 
-            self.graph.as_ref().decode_list(
-                self.curr as usize, 
-                &mut self.ibs, 
-                Some(&mut self.window), 
-                &mut self.outd,
-                &mut self.huff_decoder,
-            );
-
-        let d = self.outd[curr_idx];
-
-        if self.window[curr_idx].len() < d {
-            self.window[curr_idx] = vec![0usize; d];
-        }
-        
-        let mut i = 0; 
-        while i < d && i < decoded_list.len() {
-            self.window[curr_idx][i] = decoded_list[i];
-            i += 1;
-        }
+        self.graph.as_ref().decode_list_into(
+            self.curr as usize, 
+            &mut self.ibs, 
+            Some((&self.window, &mut self.outd)), 
+            &self.huff_decoder,
+            &mut self.buffers,
+            &mut self.list,
+        );
+        std::mem::swap(&mut self.window[curr_idx], &mut self.list);
 
         Some(self.curr as usize)
     }
@@ -330,6 +497,7 @@ impl<
     OutReferenceCoding: UniversalCode,
     OutIntervalCoding: UniversalCode,
     OutResidualCoding: UniversalCode,
+    O: Offsets,
     BV: AsRef<BVGraph<
         InBlockCoding,
         InBlockCountCoding,
@@ -345,6 +513,7 @@ impl<
         OutReferenceCoding,
         OutIntervalCoding,
         OutResidualCoding,
+        O,
 >>> BVGraphNodeIterator<
         InBlockCoding,
         InBlockCountCoding,
@@ -360,6 +529,7 @@ impl<
         OutReferenceCoding,
         OutIntervalCoding,
         OutResidualCoding,
+        O,
         BV
 > {
     #[inline(always)]
@@ -378,6 +548,117 @@ impl<
         assert!(self.curr != self.from as i64 - 1);
         self.window[self.curr as usize % self.cyclic_buffer_size].as_slice()
     }
+
+    /// Moves the iterator to the start of a range of nodes, filling the window with the lists preceding it.
+    pub(crate) fn seek(&mut self, start: RangeStart) {
+        self.ibs.position(start.position as u64);
+
+        let first = start.nodes.start - start.window.len();
+        for (i, list) in start.window.into_iter().enumerate() {
+            let index = (first + i) % self.cyclic_buffer_size;
+            self.outd[index] = list.len();
+            self.window[index] = list.into_vec();
+        }
+
+        self.from = start.nodes.start;
+        self.curr = start.nodes.start as i64 - 1;
+        self.n = start.nodes.end;
+    }
+
+    /// Turns the iterator into one over the nodes, each paired with its successors.
+    pub(crate) fn into_lists(mut self) -> impl Iterator<Item = (usize, Box<[usize]>)> {
+        std::iter::from_fn(move || {
+            let node = self.next()?;
+            Some((node, self.successor_array()[..self.outdegree()].into()))
+        })
+    }
+}
+
+/// A lazy iterator over the successors of a node of a [`BVGraph`].
+/// 
+/// The residuals are decoded one at a time and merged with the successors copied from the reference list,
+/// which is in turn decoded lazily.
+pub struct BVGraphSuccessors<
+    InBlockCoding: Huffman,
+    InBlockCountCoding: UniversalCode,
+    InOutdegreeCoding: Huffman,
+    InOffsetCoding: UniversalCode,
+    InReferenceCoding: UniversalCode,
+    InIntervalCoding: Huffman,
+    InResidualCoding: Huffman,
+    OutBlockCoding: UniversalCode,
+    OutBlockCountCoding: UniversalCode,
+    OutOutdegreeCoding: UniversalCode,
+    OutOffsetCoding: UniversalCode,
+    OutReferenceCoding: UniversalCode,
+    OutIntervalCoding: UniversalCode,
+    OutResidualCoding: UniversalCode,
+    O: Offsets,
+    BV: AsRef<BVGraph<
+        InBlockCoding,
+        InBlockCountCoding,
+        InOutdegreeCoding,
+        InOffsetCoding,
+        InReferenceCoding,
+        InIntervalCoding,
+        InResidualCoding,
+        OutBlockCoding,
+        OutBlockCountCoding,
+        OutOutdegreeCoding,
+        OutOffsetCoding,
+        OutReferenceCoding,
+        OutIntervalCoding,
+        OutResidualCoding,
+        O,
+>> + Clone,
+> {
+    // The graph on which we iterate
+    graph: BV,
+    // The input bit stream, positioned at the next residual
+    ibs: BinaryReader,
+    // The node whose successors are returned
+    x: usize,
+    // The number of successors not returned yet
+    remaining: usize,
+    // The successors of the reference node, if any
+    reference: Option<Box<Self>>,
+    // The copy blocks, alternately copying and skipping successors of the reference
+    blocks: Vec<usize>,
+    // The index of the next block to skip
+    next_block: usize,
+    // The successors of the reference left in the current copy block
+    block_left: usize,
+    // The number of residuals
+    residual_count: usize,
+    // The number of residuals decoded so far
+    curr_residual: usize,
+    // The last decoded residual gap
+    last_residual_delta: usize,
+    // The successor following the last returned residual
+    last_dest_plus_one: usize,
+    // The length of the current run of zero gaps
+    contiguous_zeros_len: usize,
+    // The number of zero gaps implied by the last run-length
+    num_zeros_to_skip: usize,
+    // The next successor copied from the reference
+    next_copied: Option<usize>,
+    // The residual decoded but not returned yet
+    next_residual: Option<usize>,
+    _phantom_in_block_coding: PhantomData<InBlockCoding>,
+    _phantom_in_block_count_coding: PhantomData<InBlockCountCoding>,
+    _phantom_in_outdegree_coding: PhantomData<InOutdegreeCoding>,
+    _phantom_in_offset_coding: PhantomData<InOffsetCoding>,
+    _phantom_in_reference_coding: PhantomData<InReferenceCoding>,
+    _phantom_in_interval_coding: PhantomData<InIntervalCoding>,
+    _phantom_in_residual_coding: PhantomData<InResidualCoding>,
+    _phantom_out_block_coding: PhantomData<OutBlockCoding>,
+    _phantom_out_block_count_coding: PhantomData<OutBlockCountCoding>,
+    _phantom_out_outdegree_coding: PhantomData<OutOutdegreeCoding>,
+    _phantom_out_offset_coding: PhantomData<OutOffsetCoding>,
+    _phantom_out_reference_coding: PhantomData<OutReferenceCoding>,
+    _phantom_out_interval_coding: PhantomData<OutIntervalCoding>,
+    _phantom_out_residual_coding: PhantomData<OutResidualCoding>,
+    _phantom_offsets: PhantomData<O>,
 }
 
 impl<
@@ -395,6 +676,351 @@ impl<
     OutReferenceCoding: UniversalCode,
     OutIntervalCoding: UniversalCode,
     OutResidualCoding: UniversalCode,
+    O: Offsets,
+    BV: AsRef<BVGraph<
+        InBlockCoding,
+        InBlockCountCoding,
+        InOutdegreeCoding,
+        InOffsetCoding,
+        InReferenceCoding,
+        InIntervalCoding,
+        InResidualCoding,
+        OutBlockCoding,
+        OutBlockCountCoding,
+        OutOutdegreeCoding,
+        OutOffsetCoding,
+        OutReferenceCoding,
+        OutIntervalCoding,
+        OutResidualCoding,
+        O,
+>> + Clone,
+> BVGraphSuccessors<
+    InBlockCoding,
+    InBlockCountCoding,
+    InOutdegreeCoding,
+    InOffsetCoding,
+    InReferenceCoding,
+    InIntervalCoding,
+    InResidualCoding,
+    OutBlockCoding,
+    OutBlockCountCoding,
+    OutOutdegreeCoding,
+    OutOffsetCoding,
+    OutReferenceCoding,
+    OutIntervalCoding,
+    OutResidualCoding,
+    O,
+    BV,
+> {
+    fn new(graph: BV, x: usize) -> Self {
+        let bvgraph = graph.as_ref();
+        let huff = &bvgraph.huffman_decoder;
+        let mut ibs = BinaryReader::new(bvgraph.graph_memory.clone());
+        ibs.position(bvgraph.offsets.get(x) as u64);
+
+        let ctx =
+            if x.is_multiple_of(32) {
+                FIRST_DEGREE_CTX
+            } else {
+                DEGREE_BASE_CTX + zuck_encode(x % 32, K_ZUCK, I_ZUCK, J_ZUCK).0.min(NUM_DEGREE_CTX - 1)
+            };
+        let degree = huff.read_next(&mut ibs, ctx);
+
+        let mut successors = BVGraphSuccessors {
+            graph: graph.clone(),
+            ibs,
+            x,
+            remaining: degree,
+            reference: None,
+            blocks: Vec::new(),
+            next_block: 1,
+            block_left: 0,
+            residual_count: degree,
+            curr_residual: 0,
+            last_residual_delta: 0,
+            last_dest_plus_one: 0,
+            contiguous_zeros_len: 0,
+            num_zeros_to_skip: 0,
+            next_copied: None,
+            next_residual: None,
+            _phantom_in_block_coding: PhantomData,
+            _phantom_in_block_count_coding: PhantomData,
+            _phantom_in_outdegree_coding: PhantomData,
+            _phantom_in_offset_coding: PhantomData,
+            _phantom_in_reference_coding: PhantomData,
+            _phantom_in_interval_coding: PhantomData,
+            _phantom_in_residual_coding: PhantomData,
+            _phantom_out_block_coding: PhantomData,
+            _phantom_out_block_count_coding: PhantomData,
+            _phantom_out_outdegree_coding: PhantomData,
+            _phantom_out_offset_coding: PhantomData,
+            _phantom_out_reference_coding: PhantomData,
+            _phantom_out_interval_coding: PhantomData,
+            _phantom_out_residual_coding: PhantomData,
+            _phantom_offsets: PhantomData,
+        };
+
+        if degree == 0 {
+            return successors;
+        }
+
+        let reference = InReferenceCoding::read_next(&mut successors.ibs, bvgraph.in_zeta_k) as usize;
+
+        if reference > 0 {
+            let block_count = huff.read_next(&mut successors.ibs, BLOCK_COUNT_CTX);
+            successors.blocks = Vec::with_capacity(block_count + 1);
+
+            let mut block_end = 0;
+            for i in 0..block_count {
+                let ctx = if i == 0 {BLOCK_CTX} else if i % 2 == 0 {BLOCK_CTX_EVEN} else {BLOCK_CTX_ODD};
+                let block_len = huff.read_next(&mut successors.ibs, ctx) + if i == 0 {0} else {1};
+
+                block_end += block_len;
+                successors.blocks.push(block_len);
+            }
+
+            let reference_successors = BVGraphSuccessors::new(graph.clone(), x - reference);
+
+            // The last block implicitly extends up to the end of the reference list
+            successors.blocks.push(reference_successors.remaining - block_end);
+
+            successors.residual_count -= successors.blocks.iter().step_by(2).sum::<usize>();
+
+            successors.reference = Some(Box::new(reference_successors));
+            successors.block_left = successors.blocks[0];
+            successors.next_copied = successors.advance_copied();
+        }
+
+        successors
+    }
+
+    #[inline(always)]
+    fn advance_copied(&mut self) -> Option<usize> {
+        let reference = self.reference.as_mut()?;
+
+        while self.block_left == 0 {
+            if self.next_block + 1 >= self.blocks.len() {
+                return None;
+            }
+
+            for _ in 0..self.blocks[self.next_block] {
+                reference.next();
+            }
+            self.block_left = self.blocks[self.next_block + 1];
+            self.next_block += 2;
+        }
+
+        self.block_left -= 1;
+        reference.next()
+    }
+
+    #[inline(always)]
+    fn decode_residual(&mut self) -> usize {
+        let huff = &self.graph.as_ref().huffman_decoder;
+        let destination_node;
+
+        if self.curr_residual == 0 {
+            let ctx = FIRST_RESIDUAL_BASE_CTX + 
+                zuck_encode(self.residual_count, K_ZUCK, I_ZUCK, J_ZUCK)
+                .0
+                .min(NUM_FIRST_RESIDUAL_CTX - 1);
+            self.last_residual_delta = huff.read_next(&mut self.ibs, ctx);
+            destination_node = (self.x as i64 + nat2int(self.last_residual_delta as u64)) as usize;
+        } else if self.num_zeros_to_skip > 0 {
+            self.last_residual_delta = 0;
+            destination_node = self.last_dest_plus_one;
+        } else {
+            let ctx = RESIDUALS_BASE_CTX + 
+                zuck_encode(self.last_residual_delta, K_ZUCK, I_ZUCK, J_ZUCK)
+                .0
+                .min(NUM_RESIDUAL_CTX - 1);
+            self.last_residual_delta = huff.read_next(&mut self.ibs, ctx);
+            destination_node = self.last_dest_plus_one + self.last_residual_delta;
+        }
+
+        if self.last_residual_delta == 0 && self.num_zeros_to_skip == 0 {
+            self.contiguous_zeros_len += 1;
+        } else {
+            self.contiguous_zeros_len = 0;
+        }
+
+        if self.num_zeros_to_skip > 0 {
+            self.num_zeros_to_skip -= 1;
+        }
+
+        if self.contiguous_zeros_len >= self.graph.as_ref().in_min_interval_len {
+            self.num_zeros_to_skip = huff.read_next(&mut self.ibs, RLE_CTX);
+            self.contiguous_zeros_len = 0;
+        }
+
+        self.curr_residual += 1;
+
+        destination_node
+    }
+}
+
+impl<
+    InBlockCoding: Huffman,
+    InBlockCountCoding: UniversalCode,
+    InOutdegreeCoding: Huffman,
+    InOffsetCoding: UniversalCode,
+    InReferenceCoding: UniversalCode,
+    InIntervalCoding: Huffman,
+    InResidualCoding: Huffman,
+    OutBlockCoding: UniversalCode,
+    OutBlockCountCoding: UniversalCode,
+    OutOutdegreeCoding: UniversalCode,
+    OutOffsetCoding: UniversalCode,
+    OutReferenceCoding: UniversalCode,
+    OutIntervalCoding: UniversalCode,
+    OutResidualCoding: UniversalCode,
+    O: Offsets,
+    BV: AsRef<BVGraph<
+        InBlockCoding,
+        InBlockCountCoding,
+        InOutdegreeCoding,
+        InOffsetCoding,
+        InReferenceCoding,
+        InIntervalCoding,
+        InResidualCoding,
+        OutBlockCoding,
+        OutBlockCountCoding,
+        OutOutdegreeCoding,
+        OutOffsetCoding,
+        OutReferenceCoding,
+        OutIntervalCoding,
+        OutResidualCoding,
+        O,
+>> + Clone,
+> Iterator for BVGraphSuccessors<
+    InBlockCoding,
+    InBlockCountCoding,
+    InOutdegreeCoding,
+    InOffsetCoding,
+    InReferenceCoding,
+    InIntervalCoding,
+    InResidualCoding,
+    OutBlockCoding,
+    OutBlockCountCoding,
+    OutOutdegreeCoding,
+    OutOffsetCoding,
+    OutReferenceCoding,
+    OutIntervalCoding,
+    OutResidualCoding,
+    O,
+    BV,
+> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        self.remaining -= 1;
+
+        if self.next_residual.is_none() && self.curr_residual < self.residual_count {
+            self.next_residual = Some(self.decode_residual());
+        }
+
+        let Some(mut destination_node) = self.next_residual else {
+            let copied = self.next_copied;
+            self.next_copied = self.advance_copied();
+            return copied;
+        };
+
+        if let Some(copied) = self.next_copied {
+            if copied <= destination_node {
+                // Residuals are coded as gaps skipping the copied successors
+                if self.curr_residual != 1 && copied >= self.last_dest_plus_one {
+                    destination_node += 1;
+                    self.next_residual = Some(destination_node);
+                }
+
+                self.next_copied = self.advance_copied();
+                return Some(copied);
+            }
+        }
+
+        self.next_residual = None;
+        self.last_dest_plus_one = destination_node + 1;
+
+        Some(destination_node)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<
+    InBlockCoding: Huffman,
+    InBlockCountCoding: UniversalCode,
+    InOutdegreeCoding: Huffman,
+    InOffsetCoding: UniversalCode,
+    InReferenceCoding: UniversalCode,
+    InIntervalCoding: Huffman,
+    InResidualCoding: Huffman,
+    OutBlockCoding: UniversalCode,
+    OutBlockCountCoding: UniversalCode,
+    OutOutdegreeCoding: UniversalCode,
+    OutOffsetCoding: UniversalCode,
+    OutReferenceCoding: UniversalCode,
+    OutIntervalCoding: UniversalCode,
+    OutResidualCoding: UniversalCode,
+    O: Offsets,
+    BV: AsRef<BVGraph<
+        InBlockCoding,
+        InBlockCountCoding,
+        InOutdegreeCoding,
+        InOffsetCoding,
+        InReferenceCoding,
+        InIntervalCoding,
+        InResidualCoding,
+        OutBlockCoding,
+        OutBlockCountCoding,
+        OutOutdegreeCoding,
+        OutOffsetCoding,
+        OutReferenceCoding,
+        OutIntervalCoding,
+        OutResidualCoding,
+        O,
+>> + Clone,
+> ExactSizeIterator for BVGraphSuccessors<
+    InBlockCoding,
+    InBlockCountCoding,
+    InOutdegreeCoding,
+    InOffsetCoding,
+    InReferenceCoding,
+    InIntervalCoding,
+    InResidualCoding,
+    OutBlockCoding,
+    OutBlockCountCoding,
+    OutOutdegreeCoding,
+    OutOffsetCoding,
+    OutReferenceCoding,
+    OutIntervalCoding,
+    OutResidualCoding,
+    O,
+    BV,
+> {}
+
+impl<
+    InBlockCoding: Huffman,
+    InBlockCountCoding: UniversalCode,
+    InOutdegreeCoding: Huffman,
+    InOffsetCoding: UniversalCode,
+    InReferenceCoding: UniversalCode,
+    InIntervalCoding: Huffman,
+    InResidualCoding: Huffman,
+    OutBlockCoding: UniversalCode,
+    OutBlockCountCoding: UniversalCode,
+    OutOutdegreeCoding: UniversalCode,
+    OutOffsetCoding: UniversalCode,
+    OutReferenceCoding: UniversalCode,
+    OutIntervalCoding: UniversalCode,
+    OutResidualCoding: UniversalCode,
+    O: Offsets,
 > AsMut<BVGraph<
     InBlockCoding,
     InBlockCountCoding,
@@ -410,6 +1036,7 @@ impl<
     OutReferenceCoding,
     OutIntervalCoding,
     OutResidualCoding,
+    O,
 >> for BVGraph<
     InBlockCoding,
     InBlockCountCoding,
@@ -425,6 +1052,7 @@ impl<
     OutReferenceCoding,
     OutIntervalCoding,
     OutResidualCoding,
+    O,
 > {
 
     fn as_mut(&mut self) -> &mut BVGraph<
@@ -442,6 +1070,7 @@ impl<
         OutReferenceCoding,
     OutIntervalCoding,
         OutResidualCoding,
+        O,
     > {
         self
     }
@@ -462,6 +1091,7 @@ impl<
     OutReferenceCoding: UniversalCode,
     OutIntervalCoding: UniversalCode,
     OutResidualCoding: UniversalCode,
+    O: Offsets,
 > AsRef<BVGraph<
     InBlockCoding,
     InBlockCountCoding,
@@ -477,6 +1107,7 @@ impl<
     OutReferenceCoding,
     OutIntervalCoding,
     OutResidualCoding,
+    O,
 >> for BVGraph<
     InBlockCoding,
     InBlockCountCoding,
@@ -492,6 +1123,7 @@ impl<
     OutReferenceCoding,
     OutIntervalCoding,
     OutResidualCoding,
+    O,
 >
 {
     fn as_ref(&self) -> &BVGraph<
@@ -508,7 +1140,8 @@ impl<
         OutOffsetCoding, 
         OutReferenceCoding,
         OutIntervalCoding, 
-        OutResidualCoding
+        OutResidualCoding,
+        O,
     > {
         self
     }
@@ -529,6 +1162,7 @@ impl<
     OutReferenceCoding: UniversalCode,
     OutIntervalCoding: UniversalCode,
     OutResidualCoding: UniversalCode,
+    O: Offsets,
 > IntoIterator for BVGraph<
     InBlockCoding,
     InBlockCountCoding,
@@ -544,6 +1178,7 @@ impl<
     OutReferenceCoding,
     OutIntervalCoding,
     OutResidualCoding,
+    O,
 > 
 {
     type Item = usize;
@@ -563,6 +1198,7 @@ impl<
         OutReferenceCoding,
         OutIntervalCoding,
         OutResidualCoding,
+        O,
         BVGraph<
             InBlockCoding, 
             InBlockCountCoding, 
@@ -577,7 +1213,8 @@ impl<
             OutOffsetCoding, 
             OutReferenceCoding,
             OutIntervalCoding, 
-            OutResidualCoding
+            OutResidualCoding,
+            O,
         >
     >;
 
@@ -585,7 +1222,7 @@ impl<
         let mut ibs = BinaryReader::new(self.graph_memory.clone());
 
         let mut huff_decoder = HuffmanDecoder::new();
-        huff_decoder.decode_headers(&mut ibs, NUM_CONTEXTS);
+        huff_decoder.decode_headers(&mut ibs, NUM_CONTEXTS).expect("The headers have been decoded when loading the graph");
 
         BVGraphNodeIterator {
             n: self.n,
@@ -594,6 +1231,8 @@ impl<
             cyclic_buffer_size: self.in_window_size + 1,
             window: vec![vec![0usize; self.in_window_size + 1]; 1024],
             outd: vec![0usize; self.in_window_size + 1],
+            buffers: DecodeBuffers::default(),
+            list: Vec::new(),
             graph: self,
             from: 0,
             curr: -1,
@@ -611,6 +1250,7 @@ impl<
             _phantom_out_reference_coding: PhantomData,
             _phantom_out_interval_coding: PhantomData,
             _phantom_out_residual_coding: PhantomData,
+            _phantom_offsets: PhantomData,
         }
     }
 }
@@ -630,6 +1270,7 @@ impl<
     OutReferenceCoding: UniversalCode,
     OutIntervalCoding: UniversalCode,
     OutResidualCoding: UniversalCode,
+    O: Offsets,
 > BVGraph<
     InBlockCoding,
     InBlockCountCoding,
@@ -645,6 +1286,7 @@ impl<
     OutReferenceCoding,
     OutIntervalCoding,
     OutResidualCoding,
+    O,
 > {
     pub fn iter(&self) -> BVGraphNodeIterator<
         InBlockCoding,
@@ -661,43 +1303,231 @@ impl<
         OutReferenceCoding,
         OutIntervalCoding,
         OutResidualCoding,
+        O,
         &Self
     > {
         let mut ibs = BinaryReader::new(self.graph_memory.clone());
 
-        let mut huff_decoder = HuffmanDecoder::new();
-        huff_decoder.decode_headers(&mut ibs, NUM_CONTEXTS);
+        let mut huff_decoder = HuffmanDecoder::new();
+        huff_decoder.decode_headers(&mut ibs, NUM_CONTEXTS).expect("The headers have been decoded when loading the graph");
+
+        BVGraphNodeIterator {
+            n: self.n,
+            graph: self,
+            ibs,
+            huff_decoder,
+            cyclic_buffer_size: self.in_window_size + 1,
+            window: vec![vec![0usize; self.in_window_size + 1]; 1024],
+            outd: vec![0usize; self.in_window_size + 1],
+            buffers: DecodeBuffers::default(),
+            list: Vec::new(),
+            from: 0,
+            curr: -1,
+            _phantom_in_block_coding: PhantomData,
+            _phantom_in_block_count_coding: PhantomData,
+            _phantom_in_outdegree_coding: PhantomData,
+            _phantom_in_offset_coding: PhantomData,
+            _phantom_in_reference_coding: PhantomData,
+            _phantom_in_interval_coding: PhantomData,
+            _phantom_in_residual_coding: PhantomData,
+            _phantom_out_block_coding: PhantomData,
+            _phantom_out_block_count_coding: PhantomData,
+            _phantom_out_outdegree_coding: PhantomData,
+            _phantom_out_offset_coding: PhantomData,
+            _phantom_out_reference_coding: PhantomData,
+            _phantom_out_interval_coding: PhantomData,
+            _phantom_out_residual_coding: PhantomData,
+            _phantom_offsets: PhantomData,
+        }
+    }
+
+    /// Returns an iterator over the nodes of a range, each paired with its successors, starting at its first node.
+    /// 
+    /// The lists preceding the range within the window are decoded by random access, so that the lists
+    /// of the range can refer to them as in a scan starting from node 0.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `nodes` - The range of nodes to iterate on
+    pub fn iter_range(&self, nodes: Range<usize>) -> impl Iterator<Item = (usize, Box<[usize]>)> + '_ {
+        let mut iter = self.iter();
+        iter.seek(self.range_start(nodes));
+
+        iter.into_lists()
+    }
+
+    /// Returns an iterator over the nodes from `x` on, each paired with its successors, as by [`Self::iter_range()`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The first node of the iteration
+    pub fn iter_from(&self, x: usize) -> impl Iterator<Item = (usize, Box<[usize]>)> + '_ {
+        self.iter_range(x..self.n)
+    }
+
+    /// Returns an iterator over the nodes from `x` on, each paired with its successors, without using the offsets.
+    /// 
+    /// The iteration starts with an empty window at the last sync point not after `x`, skipping the lists before `x`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The first node of the iteration
+    /// * `sync_points` - The sync points of the graph
+    pub fn iter_from_sync(&self, x: usize, sync_points: &SyncPoints) -> impl Iterator<Item = (usize, Box<[usize]>)> + '_ {
+        assert!(x <= self.n, "Node index out of range {}", x);
+        let (sync_point, position) = sync_points.get(x);
+
+        let mut iter = self.iter();
+        iter.seek(RangeStart { nodes: sync_point..self.n, position, window: Vec::new() });
+        for _ in sync_point..x {
+            iter.next();
+        }
+
+        iter.into_lists()
+    }
+
+    /// Splits the nodes in at most `parts` ranges of consecutive nodes taking about the same number of bits,
+    /// and returns an iterator on each of them, as by [`Self::iter_range()`].
+    /// 
+    /// The iterators share the graph, so they cannot be moved to other threads: see [`Self::par_scan()`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `parts` - The number of ranges
+    pub fn split_iter(&self, parts: usize) -> Vec<impl Iterator<Item = (usize, Box<[usize]>)> + '_> {
+        parallel::split_nodes(self.n, parts, |x| self.offsets.get(x))
+            .into_iter()
+            .map(|nodes| self.iter_range(nodes))
+            .collect()
+    }
+
+    /// Scans the graph on the given number of threads, each mapping the nodes of one of the ranges
+    /// of [`Self::split_iter()`], paired with their successors.
+    /// 
+    /// The graph cannot be shared among threads, so the lists preceding each range are decoded on the calling
+    /// thread, and each thread decodes its range through a graph of its own, loaded from the same bitstream.
+    /// Returns the results in the order of the ranges.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `threads` - The number of threads
+    /// * `map` - The function mapping the nodes of a range
+    pub fn par_scan<R: Send>(
+        &self, 
+        threads: usize, 
+        map: impl Fn(&mut dyn Iterator<Item = (usize, Box<[usize]>)>) -> R + Sync,
+    ) -> Vec<R> {
+        let starts = parallel::split_nodes(self.n, threads, |x| self.offsets.get(x))
+            .into_iter()
+            .map(|nodes| self.range_start(nodes))
+            .collect();
+
+        let (graph_memory, n, m) = (self.graph_memory.clone(), self.n, self.m);
+        let (max_ref_count, window_size, min_interval_len, zeta_k) = 
+            (self.in_max_ref_count, self.in_window_size, self.in_min_interval_len, self.in_zeta_k);
+
+        parallel::map_ranges(starts, |start| {
+            let graph = BVGraphBuilder::<
+                InBlockCoding, InBlockCountCoding, InOutdegreeCoding, InOffsetCoding, InReferenceCoding, InIntervalCoding, InResidualCoding, 
+                OutBlockCoding, OutBlockCountCoding, OutOutdegreeCoding, OutOffsetCoding, OutReferenceCoding, OutIntervalCoding, OutResidualCoding
+            >::new()
+                .set_num_nodes(n)
+                .set_num_edges(m)
+                .set_in_max_ref_count(max_ref_count)
+                .set_in_window_size(window_size)
+                .set_in_min_interval_len(min_interval_len)
+                .set_in_zeta(zeta_k)
+                .load_graph_from(graph_memory.clone())
+                .expect("The headers have been decoded when loading the graph")
+                .build();
+
+            let mut iter = graph.iter();
+            iter.seek(start);
+            let mut lists = iter.into_lists();
+
+            map(&mut lists)
+        })
+    }
 
-        BVGraphNodeIterator {
-            n: self.n,
-            graph: self,
-            ibs,
-            huff_decoder,
-            cyclic_buffer_size: self.in_window_size + 1,
-            window: vec![vec![0usize; self.in_window_size + 1]; 1024],
-            outd: vec![0usize; self.in_window_size + 1],
-            from: 0,
-            curr: -1,
-            _phantom_in_block_coding: PhantomData,
-            _phantom_in_block_count_coding: PhantomData,
-            _phantom_in_outdegree_coding: PhantomData,
-            _phantom_in_offset_coding: PhantomData,
-            _phantom_in_reference_coding: PhantomData,
-            _phantom_in_interval_coding: PhantomData,
-            _phantom_in_residual_coding: PhantomData,
-            _phantom_out_block_coding: PhantomData,
-            _phantom_out_block_count_coding: PhantomData,
-            _phantom_out_outdegree_coding: PhantomData,
-            _phantom_out_offset_coding: PhantomData,
-            _phantom_out_reference_coding: PhantomData,
-            _phantom_out_interval_coding: PhantomData,
-            _phantom_out_residual_coding: PhantomData,
+    /// Returns where the iteration over a range of nodes starts, decoding the lists preceding it by random access.
+    fn range_start(&self, nodes: Range<usize>) -> RangeStart {
+        assert!(nodes.start <= nodes.end && nodes.end <= self.n, "Node range out of bounds {:?}", nodes);
+
+        RangeStart::new(nodes, self.in_window_size, |x| self.offsets.get(x), |x| RandomAccessGraph::successors(self, x))
+    }
+
+    /// Returns the counters of the cache of decoded lists, if the graph has one
+    /// (see [`BVGraphBuilder::set_cache_size()`] and [`Self::pin()`]).
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache.stats()
+    }
+
+    /// Pins the lists of the nodes chosen by `pinning` in the cache of decoded lists, creating it if absent,
+    /// so that they are never decoded again.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `pinning` - The lists to pin
+    pub fn pin(&mut self, pinning: Pinning) {
+        let nodes = pinning.select(
+            self.n, 
+            |x| self.outdegree_internal(x, &self.huffman_decoder), 
+            |x| self.reference_at(x),
+        );
+        let lists: Vec<_> = nodes.into_iter().map(|x| (x, RandomAccessGraph::successors(self, x))).collect();
+
+        self.cache.pin(lists);
+    }
+
+    /// Returns the reference of the list of `x`, or 0 if it does not refer to any list.
+    fn reference_at(&self, x: usize) -> usize {
+        let degree = self.outdegree_internal(x, &self.huffman_decoder);
+
+        if degree == 0 {
+            return 0;
+        }
+        let mut decoder = self.graph_binary_wrapper.borrow_mut();
+        decoder.position(self.cached_ptr.get().unwrap() as u64);
+        InReferenceCoding::read_next(&mut decoder, self.in_zeta_k) as usize
+    }
+
+    /// Writes the successors of a given node into `successors`, replacing its content, decoding them
+    /// through the given buffers.
+    /// 
+    /// Once the buffers and `successors` have grown to the size of the lists decoded, no memory is allocated,
+    /// but for the lists added to the cache of decoded lists, if any.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The node number
+    /// * `buffers` - The buffers for the intermediate values
+    /// * `successors` - The vector receiving the successors
+    pub fn successors_into(&self, x: usize, buffers: &mut DecodeBuffers, successors: &mut Vec<usize>) {
+        assert!(x < self.n, "Node index out of range {}", x);
+        self.decode_list_at(x, &mut self.graph_binary_wrapper.borrow_mut(), &self.huffman_decoder, buffers, successors);
+    }
+
+    /// Decodes the successor list of `x` into `successors` by random access, unless the list is in the cache.
+    #[inline(always)]
+    fn decode_list_at(
+        &self, 
+        x: usize, 
+        decoder: &mut BinaryReader, 
+        huff: &HuffmanDecoder, 
+        buffers: &mut DecodeBuffers, 
+        successors: &mut Vec<usize>,
+    ) {
+        if self.cache.get_into(x, successors) {
+            return;
         }
+
+        self.decode_list_into(x, decoder, None, huff, buffers, successors);
+        self.cache.insert(x, successors);
     }
 
     #[inline(always)]
-    fn outdegree_internal(&self, x: usize, huff_outdegrees: &mut HuffmanDecoder) -> usize {
-        self.outdegrees_binary_wrapper.borrow_mut().position(self.offsets[x] as u64);
+    fn outdegree_internal(&self, x: usize, huff_outdegrees: &HuffmanDecoder) -> usize {
+        self.outdegrees_binary_wrapper.borrow_mut().position(self.offsets.get(x) as u64);
         let d = 
             if x == 0 || x % 32 == 0 {
                 huff_outdegrees.read_next(&mut self.outdegrees_binary_wrapper.borrow_mut(), FIRST_DEGREE_CTX)
@@ -717,10 +1547,13 @@ impl<
     pub fn successors(
         &mut self, 
         x: usize,
-        huff: &mut HuffmanDecoder,
+        huff: &HuffmanDecoder,
     ) -> Box<[usize]> {
         assert!(x < self.n, "Node index out of range {}", x);
-        self.decode_list(x, &mut self.graph_binary_wrapper.borrow_mut(), None, &mut [], huff).into_boxed_slice()
+        let mut successors = Vec::new();
+        self.decode_list_at(x, &mut self.graph_binary_wrapper.borrow_mut(), huff, &mut DecodeBuffers::default(), &mut successors);
+
+        successors.into_boxed_slice()
     }
 
     #[inline(always)]
@@ -730,14 +1563,33 @@ impl<
         decoder: &mut BinaryReader, 
         window: Option<&mut Vec<Vec<usize>>>, 
         outd: &mut [usize],
-        huff: &mut HuffmanDecoder,
+        huff: &HuffmanDecoder,
     ) -> Vec<usize> {
+        let mut successors = Vec::new();
+        self.decode_list_into(x, decoder, window.map(|window| (&**window, outd)), huff, &mut DecodeBuffers::default(), &mut successors);
+
+        successors
+    }
+
+    /// Decodes the successor list of `x` into `successors`, using `buffers` for the intermediate values.
+    /// 
+    /// During a sequential scan, `window` holds the lists preceding `x` and their outdegrees, where the outdegree
+    /// of `x` is recorded; otherwise, the list is decoded by random access.
+    #[inline(always)]
+    fn decode_list_into(
+        &self, 
+        x: usize, 
+        decoder: &mut BinaryReader, 
+        mut window: Option<(&[Vec<usize>], &mut [usize])>, 
+        huff: &HuffmanDecoder,
+        buffers: &mut DecodeBuffers,
+        successors: &mut Vec<usize>,
+    ) {
+        successors.clear();
+
         let cyclic_buffer_size = self.in_window_size + 1;
         let degree;
-        if window.is_none() {
-            degree = self.outdegree_internal(x, huff);
-            decoder.position(self.cached_ptr.get().unwrap() as u64);
-        } else {
+        if let Some((_, outd)) = &mut window {
             let ctx =
                 if x == 0 || x % 32 == 0 {
                     FIRST_DEGREE_CTX
@@ -746,26 +1598,29 @@ impl<
                 };
             degree = huff.read_next(decoder, ctx);
             outd[x % cyclic_buffer_size] = degree; 
+        } else {
+            degree = self.outdegree_internal(x, huff);
+            decoder.position(self.cached_ptr.get().unwrap() as u64);
         }
 
         if degree == 0 {
-            return Vec::new();
+            return;
         }
 
+        let DecodeBuffers { blocks: block_lengths, reference: ref_list, next, .. } = buffers;
+
         let reference = InReferenceCoding::read_next(decoder, self.in_zeta_k) as i64;
 
         // Position in the circular buffer of the reference of the current node
         let reference_index = ((x as i64 - reference + cyclic_buffer_size as i64) as usize) % cyclic_buffer_size;
-        let decoded_reference;
-        let mut ref_list = Vec::new();
-
-        let mut block_lengths = Vec::default();
+        ref_list.clear();
+        block_lengths.clear();
 
         let mut num_to_copy = 0;
 
         if reference > 0 {
             let block_count = huff.read_next(decoder, BLOCK_COUNT_CTX);
-            block_lengths = Vec::with_capacity(block_count + 1);
+            block_lengths.reserve(block_count + 1);
 
             let mut block_end = 0;
             let mut i = 0;
@@ -786,7 +1641,7 @@ impl<
 
             // If the block count is even, we must compute the number of successors copied implicitly
             block_lengths.push(
-                if window.is_some() {outd[reference_index]} 
+                if let Some((_, outd)) = &window {outd[reference_index]} 
                 else {self.outdegree_internal((x as i64 - reference) as usize, huff)}
                 - block_end
             );
@@ -795,22 +1650,14 @@ impl<
                 num_to_copy += b;
             }
 
-            ref_list =
-                if let Some(window) = window {
-                    Vec::from(&window[reference_index][0..outd[reference_index]])
-                } else {
-                    // Cache decoder's position
-                    let decoder_pos = decoder.get_position();
-                    decoded_reference = self.decode_list(
-                        (x as i64 - reference) as usize, 
-                        decoder,
-                        None, 
-                        &mut [],
-                        huff
-                    );
-                    decoder.position(decoder_pos as u64);
-                    decoded_reference
-                };
+            if let Some((window, outd)) = window {
+                ref_list.extend_from_slice(&window[reference_index][0..outd[reference_index]]);
+            } else {
+                // Cache decoder's position
+                let decoder_pos = decoder.get_position();
+                self.decode_list_at((x as i64 - reference) as usize, decoder, huff, next.get_or_insert_with(Box::default), ref_list);
+                decoder.position(decoder_pos as u64);
+            }
         }
 
         let mut last_dest_plus_one = 0;
@@ -830,7 +1677,7 @@ impl<
         let mut contiguous_zeros_len = 0;
         let mut num_zeros_to_skip = 0;
 
-        let mut temp_list = Vec::with_capacity(degree);
+        successors.reserve_exact(degree);
 
         for j in 0..num_residuals {
             let mut destination_node;
@@ -866,7 +1713,7 @@ impl<
             while num_to_copy_from_current_block > 0 && 
                     ref_list[ref_pos] <= destination_node {
                 num_to_copy_from_current_block -= 1;
-                temp_list.push(ref_list[ref_pos]);
+                successors.push(ref_list[ref_pos]);
 
                 if j != 0 && ref_list[ref_pos] >= last_dest_plus_one {
                     destination_node += 1;
@@ -886,12 +1733,266 @@ impl<
                 contiguous_zeros_len = 0;
             }
 
-            temp_list.push(destination_node);
+            successors.push(destination_node);
             //println!("{}", destination_node);
             last_dest_plus_one = destination_node + 1;
         }
         debug_assert!(ref_pos + num_to_copy_from_current_block <= ref_list.len());
 
+        while num_to_copy_from_current_block > 0 {
+            num_to_copy_from_current_block -= 1;
+            successors.push(ref_list[ref_pos]);
+            ref_pos += 1;
+            if num_to_copy_from_current_block == 0 && next_block + 1 < block_lengths.len() {
+                ref_pos += block_lengths[next_block];
+                num_to_copy_from_current_block = block_lengths[next_block + 1];
+                next_block += 2;
+            }
+        }
+    }
+
+    /// Returns an iterator over the nodes of the graph, each paired with its successors, decoded in checked mode.
+    /// 
+    /// The Huffman headers are decoded again and every list is validated as by [`Self::try_successors()`];
+    /// the iteration stops after the first error.
+    pub fn try_iter_nodes(&self) -> impl Iterator<Item = crate::Result<(usize, Box<[usize]>)>> + '_ {
+        let mut ibs = BinaryReader::new(self.graph_memory.clone());
+        let mut huff = HuffmanDecoder::new();
+        let headers = huff.decode_headers(&mut ibs, NUM_CONTEXTS);
+
+        let mut window: Vec<Box<[usize]>> = vec![Box::default(); self.in_window_size.min(self.n) + 1];
+        let mut x = 0;
+        let mut failed = headers.is_err();
+        let mut headers = headers.err();
+
+        std::iter::from_fn(move || {
+            if let Some(e) = headers.take() {
+                return Some(Err(e));
+            }
+            if failed || x >= self.n {
+                return None;
+            }
+
+            let cyclic_buffer_size = window.len();
+            let successors = self.try_decode_list_with(x, &mut ibs, &huff, |_, reference| {
+                Ok(Cow::Borrowed(&window[reference % cyclic_buffer_size]))
+            });
+
+            match successors {
+                Ok(successors) => {
+                    let successors = successors.into_boxed_slice();
+                    window[x % cyclic_buffer_size] = successors.clone();
+                    x += 1;
+
+                    Some(Ok((x - 1, successors)))
+                },
+                Err(e) => {
+                    failed = true;
+                    Some(Err(e))
+                },
+            }
+        })
+    }
+
+    /// Returns the list of successors of a given node, decoded in checked mode.
+    /// 
+    /// The reference has to fall inside the window and the copy blocks inside the reference list, the copied
+    /// successors cannot exceed the outdegree, and every residual has to be a node of the graph; the resulting
+    /// list has to be strictly increasing. Any violation, as well as an invalid Huffman code or the end of the
+    /// bitstream, is reported as an error.
+    /// The chain of references followed to decode the list cannot be longer than the maximum reference count
+    /// of the graph.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The node number
+    pub fn try_successors(&self, x: usize) -> crate::Result<Box<[usize]>> {
+        assert!(x < self.n, "Node index out of range {}", x);
+        let mut decoder = BinaryReader::new(self.graph_memory.clone());
+
+        self.try_decode_list_at(x, &mut decoder, self.in_max_ref_count, &mut 0).map(Vec::into_boxed_slice)
+    }
+
+    /// Returns the cost of decoding the successors of a given node by random access, that is the bits read
+    /// and the number of references followed, decoding the list in checked mode as [`Self::try_successors()`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - The node number
+    pub fn try_access_cost(&self, x: usize) -> crate::Result<AccessCost> {
+        assert!(x < self.n, "Node index out of range {}", x);
+        let mut decoder = BinaryReader::new(self.graph_memory.clone());
+        let mut chain_depth = 0;
+
+        self.try_decode_list_at(x, &mut decoder, self.in_max_ref_count, &mut chain_depth)?;
+
+        Ok(AccessCost { bits: decoder.read_bits as u64, chain_depth })
+    }
+
+    /// Decodes the successor list of `x` in checked mode, following at most `max_ref_count` references,
+    /// whose number is added to `chain_depth`.
+    fn try_decode_list_at(&self, x: usize, decoder: &mut BinaryReader, max_ref_count: usize, chain_depth: &mut usize) -> crate::Result<Vec<usize>> {
+        let offset = self.offsets.get(x);
+        if offset > self.graph_memory.len() * 8 {
+            return Err(Error::Malformed(format!("node {}: offset {} past the end of the graph", x, offset)));
+        }
+        decoder.position(offset as u64);
+
+        self.try_decode_list_with(x, decoder, &self.huffman_decoder, |decoder, reference| {
+            if max_ref_count == 0 {
+                return Err(Error::Malformed(format!("node {}: reference chain longer than {}", x, self.in_max_ref_count)));
+            }
+
+            *chain_depth += 1;
+            let position = decoder.get_position();
+            let reference_list = self.try_decode_list_at(reference, decoder, max_ref_count - 1, chain_depth)?;
+            decoder.position(position as u64);
+
+            Ok(Cow::Owned(reference_list))
+        })
+    }
+
+    /// Decodes the successor list of `x` like [`Self::decode_list_into()`], validating every decoded value.
+    /// 
+    /// The reference has to fall inside the window and the copy blocks inside the reference list, the copied
+    /// successors cannot exceed the outdegree, and every residual has to be a node of the graph; the resulting
+    /// list has to be strictly increasing. Any violation, as well as an invalid Huffman code or the end of the
+    /// bitstream, is reported as an error.
+    /// 
+    /// The successor list of the reference node, if any, is retrieved through `reference_list`.
+    fn try_decode_list_with<'r>(
+        &self, 
+        x: usize, 
+        decoder: &mut BinaryReader, 
+        huff: &HuffmanDecoder,
+        mut reference_list: impl FnMut(&mut BinaryReader, usize) -> crate::Result<Cow<'r, [usize]>>
+    ) -> crate::Result<Vec<usize>> {
+        // Values are checked against their bounds as `u64`, so that saturated sums always fail the check
+        let check = |value: u64, limit: usize, what: &str| match usize::try_from(value) {
+            Ok(value) if value <= limit => Ok(value),
+            _ => Err(Error::Malformed(format!("node {}: {} {} exceeds {}", x, what, value, limit))),
+        };
+
+        let ctx =
+            if x.is_multiple_of(32) {
+                FIRST_DEGREE_CTX
+            } else {
+                DEGREE_BASE_CTX + zuck_encode(x % 32, K_ZUCK, I_ZUCK, J_ZUCK).0.min(NUM_DEGREE_CTX - 1)
+            };
+        let degree = check(huff.try_read_next(decoder, ctx)? as u64, self.n, "outdegree")?;
+
+        if degree == 0 {
+            return Ok(Vec::new());
+        }
+
+        let reference = InReferenceCoding::try_read_next(decoder, self.in_zeta_k)?;
+        let reference = check(reference, self.in_window_size.min(x), "reference")?;
+
+        let mut ref_list = Cow::Borrowed(&[][..]);
+        let mut block_lengths = Vec::default();
+        let mut num_to_copy = 0;
+
+        if reference > 0 {
+            ref_list = reference_list(decoder, x - reference)?;
+
+            let block_count = huff.try_read_next(decoder, BLOCK_COUNT_CTX)?;
+            let block_count = check(block_count as u64, ref_list.len() + 1, "block count")?;
+
+            let mut block_end = 0;
+            for i in 0..block_count {
+                let ctx = if i == 0 {BLOCK_CTX} else if i % 2 == 0 {BLOCK_CTX_EVEN} else {BLOCK_CTX_ODD};
+                let block_len = (huff.try_read_next(decoder, ctx)? as u64).saturating_add((i != 0) as u64);
+                let block_len = check(block_len, ref_list.len() - block_end, "copy block")?;
+
+                block_end += block_len;
+                block_lengths.push(block_len);
+            }
+
+            // The last block implicitly extends up to the end of the reference list
+            block_lengths.push(ref_list.len() - block_end);
+
+            num_to_copy = block_lengths.iter().step_by(2).sum();
+            check(num_to_copy as u64, degree, "number of copied successors")?;
+        }
+
+        let mut last_dest_plus_one = 0;
+        let num_residuals = degree - num_to_copy;
+
+        let mut last_residual_delta = 0;
+        let mut ref_pos = 0;
+        let mut num_to_copy_from_current_block = if block_lengths.is_empty() {0} else {block_lengths[0]};
+        let mut next_block = 1;
+
+        if num_to_copy_from_current_block == 0 && block_lengths.len() > 2 {
+            ref_pos = block_lengths[1];
+            num_to_copy_from_current_block = block_lengths[2];
+            next_block = 3;
+        }
+
+        let mut contiguous_zeros_len = 0;
+        let mut num_zeros_to_skip: usize = 0;
+
+        let mut temp_list = Vec::new();
+
+        for j in 0..num_residuals {
+            let mut destination_node;
+            if j == 0 {
+                let ctx = FIRST_RESIDUAL_BASE_CTX + 
+                    zuck_encode(num_residuals, K_ZUCK, I_ZUCK, J_ZUCK)
+                    .0
+                    .min(NUM_FIRST_RESIDUAL_CTX - 1);
+                last_residual_delta = huff.try_read_next(decoder, ctx)?;
+                destination_node = (x as i64).checked_add(nat2int(last_residual_delta as u64))
+                    .and_then(|node| u64::try_from(node).ok())
+                    .unwrap_or(u64::MAX);
+            } else if num_zeros_to_skip > 0 {
+                last_residual_delta = 0;
+                destination_node = last_dest_plus_one as u64;
+            } else {
+                let ctx = RESIDUALS_BASE_CTX + 
+                    zuck_encode(last_residual_delta, K_ZUCK, I_ZUCK, J_ZUCK)
+                    .0
+                    .min(NUM_RESIDUAL_CTX - 1);
+                last_residual_delta = huff.try_read_next(decoder, ctx)?;
+                destination_node = (last_dest_plus_one as u64).saturating_add(last_residual_delta as u64);
+            }
+
+            if last_residual_delta == 0 && num_zeros_to_skip == 0 {
+                contiguous_zeros_len += 1;
+            } else {
+                contiguous_zeros_len = 0;
+            }
+
+            num_zeros_to_skip = num_zeros_to_skip.saturating_sub(1);
+
+            while num_to_copy_from_current_block > 0 && 
+                    ref_list[ref_pos] as u64 <= destination_node {
+                num_to_copy_from_current_block -= 1;
+                temp_list.push(ref_list[ref_pos]);
+
+                if j != 0 && ref_list[ref_pos] >= last_dest_plus_one {
+                    destination_node = destination_node.saturating_add(1);
+                }
+
+                ref_pos += 1;
+
+                if num_to_copy_from_current_block == 0 && next_block + 1 < block_lengths.len() {
+                    ref_pos += block_lengths[next_block];
+                    num_to_copy_from_current_block = block_lengths[next_block + 1];
+                    next_block += 2;
+                }
+            }
+
+            if contiguous_zeros_len >= self.in_min_interval_len {
+                num_zeros_to_skip = huff.try_read_next(decoder, RLE_CTX)?;
+                contiguous_zeros_len = 0;
+            }
+
+            let destination_node = check(destination_node, self.n - 1, "residual")?;
+            temp_list.push(destination_node);
+            last_dest_plus_one = destination_node + 1;
+        }
+
         while num_to_copy_from_current_block > 0 {
             num_to_copy_from_current_block -= 1;
             temp_list.push(ref_list[ref_pos]);
@@ -903,7 +2004,11 @@ impl<
             }
         }
 
-        temp_list
+        if temp_list.windows(2).any(|pair| pair[0] >= pair[1]) || temp_list.last().is_some_and(|&last| last >= self.n) {
+            return Err(Error::Malformed(format!("node {}: successors are not distinct nodes of the graph in increasing order", x)));
+        }
+
+        Ok(temp_list)
     }
 
     #[inline(always)]
@@ -1237,6 +2342,21 @@ impl<
     }
 }
 
+/// A graph in the Zuckerli format with the codes it is stored with by the `webgraph` binary: Huffman codes
+/// for the blocks, outdegrees, intervals and residuals, and universal codes for the rest.
+pub type DefaultBVGraph<O = Box<[usize]>> = BVGraph<
+    Huff, GammaCode, Huff, GammaCode, UnaryCode, Huff, Huff,
+    GammaCode, GammaCode, GammaCode, GammaCode, UnaryCode, GammaCode, ZetaCode,
+    O,
+>;
+
+/// The builder of a [`DefaultBVGraph`].
+pub type DefaultBVGraphBuilder<O = Box<[usize]>> = BVGraphBuilder<
+    Huff, GammaCode, Huff, GammaCode, UnaryCode, Huff, Huff,
+    GammaCode, GammaCode, GammaCode, GammaCode, UnaryCode, GammaCode, ZetaCode,
+    O,
+>;
+
 pub struct BVGraphBuilder<
     InBlockCoding: Huffman,
     InBlockCountCoding: UniversalCode,
@@ -1252,11 +2372,13 @@ pub struct BVGraphBuilder<
     OutReferenceCoding: UniversalCode,
     OutIntervalCoding: UniversalCode,
     OutResidualCoding: UniversalCode,
+    O: Offsets = Box<[usize]>,
 > {
     num_nodes: usize,
     num_edges: usize,
-    loaded_graph: Rc<[u8]>,
-    loaded_offsets: Box<[usize]>,
+    loaded_graph: InputStream,
+    loaded_offsets: O,
+    huffman_decoder: HuffmanDecoder,
     graph_binary_wrapper: BinaryReader,
     outdegrees_binary_wrapper: BinaryReader,
     cached_node: Option<usize>,
@@ -1270,6 +2392,8 @@ pub struct BVGraphBuilder<
     out_min_interval_len: usize,
     in_zeta_k: Option<u64>,
     out_zeta_k: Option<u64>,
+    cache_size: usize,
+    mmap: bool,
     _phantom_in_block_coding: PhantomData<InBlockCoding>,
     _phantom_in_block_count_coding: PhantomData<InBlockCountCoding>,
     _phantom_in_outdegree_coding: PhantomData<InOutdegreeCoding>,
@@ -1301,6 +2425,7 @@ impl<
     OutReferenceCoding: UniversalCode,
     OutIntervalCoding: UniversalCode,
     OutResidualCoding: UniversalCode,
+    O: Offsets,
 > Default for BVGraphBuilder<
     InBlockCoding,
     InBlockCountCoding,
@@ -1316,13 +2441,15 @@ impl<
     OutReferenceCoding,
     OutIntervalCoding,
     OutResidualCoding,
+    O,
 > {
     fn default() -> Self {
         Self { 
             num_nodes: 0, 
             num_edges: 0, 
-            loaded_graph: Rc::new([]), 
-            loaded_offsets: Box::default(), 
+            loaded_graph: InputStream::default(), 
+            loaded_offsets: O::default(), 
+            huffman_decoder: HuffmanDecoder::new(),
             graph_binary_wrapper: BinaryReader::default(),
             outdegrees_binary_wrapper: BinaryReader::default(),
             cached_node: None, 
@@ -1336,6 +2463,8 @@ impl<
             out_min_interval_len: 4,
             in_zeta_k: None,
             out_zeta_k: Some(3),
+            cache_size: 0,
+            mmap: false,
             _phantom_in_block_coding: PhantomData,
             _phantom_in_block_count_coding: PhantomData,
             _phantom_in_outdegree_coding: PhantomData,
@@ -1369,6 +2498,7 @@ impl<
     OutReferenceCoding: UniversalCode,
     OutIntervalCoding: UniversalCode,
     OutResidualCoding: UniversalCode,
+    O: Offsets,
 > BVGraphBuilder<
     InBlockCoding,
     InBlockCountCoding,
@@ -1384,10 +2514,11 @@ impl<
     OutReferenceCoding,
     OutIntervalCoding,
     OutResidualCoding,
+    O,
 > {
     pub fn new() -> BVGraphBuilder<
         InBlockCoding, InBlockCountCoding, InOutdegreeCoding, InOffsetCoding, InReferenceCoding, InIntervalCoding, InResidualCoding, 
-        OutBlockCoding, OutBlockCountCoding, OutOutdegreeCoding, OutOffsetCoding, OutReferenceCoding, OutIntervalCoding, OutResidualCoding
+        OutBlockCoding, OutBlockCountCoding, OutOutdegreeCoding, OutOffsetCoding, OutReferenceCoding, OutIntervalCoding, OutResidualCoding, O
     > {
         Self::default()
     }
@@ -1408,17 +2539,27 @@ impl<
     ///     GammaCode, GammaCode, UnaryCode, DeltaCode, GammaCode, GammaCode, ZetaCode
     /// >::new()
     ///     .load_properties(file_base_name);
-    ///     .load_graph(file_base_name);
+    ///     .load_graph(file_base_name)?;
     /// ```
-    pub fn load_graph(mut self, basename: &str) -> Self {
-        let graph = fs::read(format!("{}.graph", basename)).unwrap();
-
-        let graph = graph.into_boxed_slice().into();
+    pub fn load_graph(self, basename: &str) -> crate::Result<Self> {
+        let graph = container::load(format!("{}.graph", basename), self.mmap, FileKind::Graph)?;
+        self.load_graph_from(graph)
+    }
 
-        self.loaded_graph = graph;
+    /// Uses the given in-memory content as the compressed graph, instead of loading it from a file,
+    /// and decodes its Huffman headers.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `graph` - The content of the graph file
+    pub fn load_graph_from(mut self, graph: impl Into<InputStream>) -> crate::Result<Self> {
+        self.loaded_graph = graph.into();
         self.graph_binary_wrapper = BinaryReader::new(self.loaded_graph.clone());
 
-        self
+        self.huffman_decoder = HuffmanDecoder::new();
+        self.huffman_decoder.decode_headers(&mut BinaryReader::new(self.loaded_graph.clone()), NUM_CONTEXTS)?;
+
+        Ok(self)
     }
 
     /// Loads a previously-compressed BVGraph's offsets file.
@@ -1437,32 +2578,18 @@ impl<
     ///     GammaCode, GammaCode, UnaryCode, DeltaCode, GammaCode, GammaCode, ZetaCode
     /// >::new()
     ///     .load_properties(file_base_name);
-    ///     .load_graph(file_base_name);
-    ///     .load_offsets(file_base_name);
+    ///     .load_graph(file_base_name)?;
+    ///     .load_offsets(file_base_name)?;
     /// let graph = builder.build();
     /// ```
-    pub fn load_offsets(mut self, basename: &str) -> Self {
+    pub fn load_offsets(mut self, basename: &str) -> crate::Result<Self> {
         assert!(self.num_nodes > 0, "The number of nodes has to be >0.");
-        let offsets = fs::read(format!("{}.offsets", basename)).unwrap();
 
-        let mut curr = 0;
+        self.loaded_offsets = load_offsets(basename, self.num_nodes, &self.loaded_graph, self.mmap, |offsets_ibs| {
+            InOffsetCoding::try_read_next(offsets_ibs, self.in_zeta_k)
+        })?;
 
-        let mut offsets_ibs = BinaryReader::new(offsets.into());
-        
-        let mut n = self.num_nodes;
-
-        let mut increasing_offsets = Vec::with_capacity(n);
-
-        while n > 0 {
-            curr += InOffsetCoding::read_next(&mut offsets_ibs, self.in_zeta_k);
-            increasing_offsets.push(curr as usize);
-
-            n -= 1;
-        }
-
-        self.loaded_offsets = increasing_offsets.into_boxed_slice();
-
-        self
+        Ok(self)
     }
 
     /// Creates a new binary wrapper around the previously-loaded graph.
@@ -1586,6 +2713,33 @@ impl<
         self
     }
 
+    /// Sets the number of decoded successor lists cached for random access, or 0 not to cache them.
+    /// 
+    /// The cache is consulted for every list decoded by random access, including the lists referred to by other
+    /// lists, and it evicts them with the CLOCK policy. Its counters are given by [`BVGraph::cache_stats()`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `capacity` - The maximum number of cached lists
+    pub fn set_cache_size(mut self, capacity: usize) -> Self {
+        self.cache_size = capacity;
+
+        self
+    }
+
+    /// Sets whether the graph and offsets files have to be memory-mapped instead of read in memory.
+    /// 
+    /// This has to be called before [`Self::load_graph()`] and [`Self::load_offsets()`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `flag` - Flag specifying whether to memory-map the files.
+    pub fn set_mmap(mut self, flag: bool) -> Self {
+        self.mmap = flag;
+
+        self
+    }
+
     /// Constructs the BVGraph object.
     pub fn build(self) -> BVGraph<
         InBlockCoding,
@@ -1602,20 +2756,23 @@ impl<
         OutReferenceCoding,
         OutIntervalCoding,
         OutResidualCoding,
+        O,
     > {
         BVGraph::<
             InBlockCoding, InBlockCountCoding, InOutdegreeCoding, InOffsetCoding, InReferenceCoding, InIntervalCoding, InResidualCoding, 
-            OutBlockCoding, OutBlockCountCoding, OutOutdegreeCoding, OutOffsetCoding, OutReferenceCoding, OutIntervalCoding, OutResidualCoding
+            OutBlockCoding, OutBlockCountCoding, OutOutdegreeCoding, OutOffsetCoding, OutReferenceCoding, OutIntervalCoding, OutResidualCoding, O
         > { 
             n: self.num_nodes, 
             m: self.num_edges, 
             graph_memory: self.loaded_graph, 
             offsets: self.loaded_offsets,
+            huffman_decoder: self.huffman_decoder,
             graph_binary_wrapper: Rc::new(RefCell::new(self.graph_binary_wrapper)),
             outdegrees_binary_wrapper: RefCell::new(self.outdegrees_binary_wrapper),
             cached_node: Cell::new(self.cached_node), 
             cached_outdegree: Cell::new(self.cached_outdegree), 
             cached_ptr: Cell::new(self.cached_ptr), 
+            cache: SharedCache::new(self.cache_size),
             in_max_ref_count: self.in_max_ref_count, 
             in_window_size: self.in_window_size,
             in_min_interval_len: self.in_min_interval_len,